@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::cli::Part;
+use crate::AOCError;
+
+/// Known answers for a year, stored as `inputs/answers.toml`, along with
+/// answers `submit` was told are wrong:
+///
+/// ```toml
+/// [day07]
+/// a = "95437"
+/// b = "24933642"
+///
+/// [day07.wrong]
+/// b = ["1000", "2000"]
+/// ```
+pub struct Answers {
+    path: PathBuf,
+    answers: BTreeMap<(usize, Part), String>,
+    wrong: BTreeMap<(usize, Part), Vec<String>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong { expected: String },
+    Unknown,
+}
+
+impl Answers {
+    /// Load the answers file, a missing file is treated as empty.
+    pub fn load(path: &Path) -> Result<Self, AOCError> {
+        let raw = match fs::read_to_string(path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(AOCError::AnswersFile(format!("{}: {}", path.display(), e))),
+        };
+
+        let (answers, wrong) =
+            parse(&raw).map_err(|e| AOCError::AnswersFile(format!("{}: {}", path.display(), e)))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            answers,
+            wrong,
+        })
+    }
+
+    pub fn get(&self, day: usize, part: Part) -> Option<&str> {
+        self.answers.get(&(day, part)).map(|s| s.as_str())
+    }
+
+    pub fn set(&mut self, day: usize, part: Part, answer: String) {
+        self.answers.insert((day, part), answer);
+    }
+
+    /// Whether `answer` was already submitted and rejected.
+    pub fn is_wrong(&self, day: usize, part: Part, answer: &str) -> bool {
+        self.wrong
+            .get(&(day, part))
+            .is_some_and(|wrong| wrong.iter().any(|w| w == answer))
+    }
+
+    pub fn set_wrong(&mut self, day: usize, part: Part, answer: String) {
+        let wrong = self.wrong.entry((day, part)).or_default();
+        if !wrong.contains(&answer) {
+            wrong.push(answer);
+        }
+    }
+
+    pub fn verify(&self, day: usize, part: Part, answer: &str) -> Verdict {
+        match self.get(day, part) {
+            Some(expected) if expected == answer => Verdict::Correct,
+            Some(expected) => Verdict::Wrong {
+                expected: expected.to_string(),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn save(&self) -> Result<(), AOCError> {
+        fs::write(&self.path, serialize(&self.answers, &self.wrong))
+            .map_err(|e| AOCError::AnswersFile(format!("{}: {}", self.path.display(), e)))
+    }
+}
+
+type AnswerMap = BTreeMap<(usize, Part), String>;
+type WrongMap = BTreeMap<(usize, Part), Vec<String>>;
+
+fn parse(raw: &str) -> Result<(AnswerMap, WrongMap), String> {
+    let table = raw.parse::<toml::Table>().map_err(|e| e.to_string())?;
+    let mut answers = BTreeMap::new();
+    let mut wrong = BTreeMap::new();
+
+    for (key, parts) in table {
+        let day = key
+            .strip_prefix("day")
+            .and_then(|d| d.parse::<usize>().ok())
+            .ok_or_else(|| format!("invalid section [{}], expected [dayNN]", key))?;
+        let parts = parts
+            .as_table()
+            .ok_or_else(|| format!("[{}] must be a table", key))?;
+
+        for (part, answer) in parts {
+            if part == "wrong" {
+                parse_wrong(&key, answer, day, &mut wrong)?;
+                continue;
+            }
+            let part = Part::from_str(part)
+                .ok_or_else(|| format!("invalid part '{}' in [{}]", part, key))?;
+            let answer = answer
+                .as_str()
+                .ok_or_else(|| format!("answer for {}.{} must be a string", key, part))?;
+            answers.insert((day, part), answer.to_string());
+        }
+    }
+
+    Ok((answers, wrong))
+}
+
+fn parse_wrong(
+    key: &str,
+    parts: &toml::Value,
+    day: usize,
+    wrong: &mut WrongMap,
+) -> Result<(), String> {
+    let parts = parts
+        .as_table()
+        .ok_or_else(|| format!("[{}.wrong] must be a table", key))?;
+
+    for (part, answers) in parts {
+        let part = Part::from_str(part)
+            .ok_or_else(|| format!("invalid part '{}' in [{}.wrong]", part, key))?;
+        let answers = answers
+            .as_array()
+            .and_then(|answers| {
+                answers
+                    .iter()
+                    .map(|a| a.as_str().map(str::to_string))
+                    .collect::<Option<Vec<String>>>()
+            })
+            .ok_or_else(|| format!("{}.wrong.{} must be a list of strings", key, part))?;
+        wrong.insert((day, part), answers);
+    }
+
+    Ok(())
+}
+
+fn day_table(table: &mut toml::Table, day: usize) -> &mut toml::Table {
+    table
+        .entry(format!("day{:02}", day))
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        .as_table_mut()
+        .unwrap()
+}
+
+fn serialize(answers: &AnswerMap, wrong: &WrongMap) -> String {
+    let mut table = toml::Table::new();
+    for ((day, part), answer) in answers {
+        day_table(&mut table, *day).insert(part.to_string(), toml::Value::String(answer.clone()));
+    }
+    for ((day, part), answers) in wrong {
+        day_table(&mut table, *day)
+            .entry("wrong")
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .unwrap()
+            .insert(
+                part.to_string(),
+                toml::Value::Array(answers.iter().cloned().map(toml::Value::String).collect()),
+            );
+    }
+    toml::to_string(&table).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = "[day07]
+a = \"95437\"
+b = \"24933642\"
+
+[day10]
+b = \"\"\"
+##..
+..##\"\"\"
+
+[day10.wrong]
+a = [\"12\", \"13\"]
+";
+
+    #[test]
+    fn test_parse() {
+        let (answers, wrong) = parse(ANSWERS).unwrap();
+        assert_eq!(answers[&(7, Part::A)], "95437");
+        assert_eq!(answers[&(7, Part::B)], "24933642");
+        assert_eq!(answers[&(10, Part::B)], "##..\n..##");
+        assert!(!answers.contains_key(&(10, Part::A)));
+        assert_eq!(wrong[&(10, Part::A)], vec!["12", "13"]);
+
+        assert!(parse("[seven]\na = \"1\"").is_err());
+        assert!(parse("[day07]\nc = \"1\"").is_err());
+        assert!(parse("[day07]\na = 1").is_err());
+        assert!(parse("[day07.wrong]\na = \"1\"").is_err());
+    }
+
+    #[test]
+    fn test_serialize_roundtrip() {
+        let (answers, wrong) = parse(ANSWERS).unwrap();
+        assert_eq!(
+            parse(&serialize(&answers, &wrong)).unwrap(),
+            (answers, wrong)
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let (answers, wrong) = parse(ANSWERS).unwrap();
+        let mut answers = Answers {
+            path: PathBuf::new(),
+            answers,
+            wrong,
+        };
+        assert_eq!(answers.verify(7, Part::A, "95437"), Verdict::Correct);
+        assert_eq!(
+            answers.verify(7, Part::B, "1"),
+            Verdict::Wrong {
+                expected: "24933642".to_string()
+            }
+        );
+        assert_eq!(answers.verify(8, Part::A, "1"), Verdict::Unknown);
+
+        assert!(answers.is_wrong(10, Part::A, "13"));
+        assert!(!answers.is_wrong(10, Part::A, "14"));
+        answers.set_wrong(10, Part::A, "14".to_string());
+        answers.set_wrong(10, Part::A, "14".to_string());
+        assert_eq!(answers.wrong[&(10, Part::A)], vec!["12", "13", "14"]);
+    }
+}
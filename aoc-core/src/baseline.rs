@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::bench::Stats;
+use crate::cli::Part;
+use crate::AOCError;
+
+/// How large Welch's t must be for a change to count as more than noise,
+/// roughly a 95% confidence level.
+const T_CRITICAL: f64 = 2.0;
+
+/// Benchmark results saved by `--save-baseline NAME` as
+/// `baselines/NAME.toml`, for `--compare NAME` to measure against:
+///
+/// ```toml
+/// [day11.a]
+/// samples = 120
+/// min_ns = 1180000
+/// median_ns = 1204000
+/// mean_ns = 1211000
+/// stddev_ns = 15000
+/// outliers = 3
+/// ```
+pub struct Baseline {
+    path: PathBuf,
+    timings: BTreeMap<(usize, Part), Stats>,
+}
+
+/// How a part's timing moved against the baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    /// Significantly slower, by more than the threshold.
+    Slower,
+    /// Significantly faster, by more than the threshold.
+    Faster,
+    /// Within the noise or the threshold.
+    Unchanged,
+    /// Not in the baseline.
+    New,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub before: Option<Duration>,
+    pub after: Duration,
+    /// Change of the median, in percent of the baseline.
+    pub delta: f64,
+    pub trend: Trend,
+}
+
+impl Baseline {
+    /// The baseline `name` of the year crate at `root`. It does not need to
+    /// exist yet.
+    pub fn load(root: &Path, name: &str) -> Result<Self, AOCError> {
+        let path = root.join("baselines").join(format!("{}.toml", name));
+        let raw = match fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(AOCError::BaselineFile(format!("{}: {}", path.display(), e))),
+        };
+
+        let timings = parse(&raw)
+            .map_err(|e| AOCError::BaselineFile(format!("{}: {}", path.display(), e)))?;
+
+        Ok(Self { path, timings })
+    }
+
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    pub fn set(&mut self, day: usize, part: Part, stats: Stats) {
+        self.timings.insert((day, part), stats);
+    }
+
+    /// Compare `stats` to the baseline of `day` and `part`. Only a change of
+    /// the median by more than `threshold` percent that Welch's t-test also
+    /// finds significant counts as slower or faster.
+    pub fn compare(&self, day: usize, part: Part, stats: &Stats, threshold: f64) -> Comparison {
+        let Some(before) = self.timings.get(&(day, part)) else {
+            return Comparison {
+                before: None,
+                after: stats.median,
+                delta: 0.0,
+                trend: Trend::New,
+            };
+        };
+
+        let nanos = |d: Duration| d.as_nanos() as f64;
+        let delta =
+            (nanos(stats.median) - nanos(before.median)) / nanos(before.median).max(1.0) * 100.0;
+        let variance = |s: &Stats| nanos(s.stddev).powi(2) / s.samples as f64;
+        let error = (variance(before) + variance(stats)).sqrt();
+        let difference = nanos(stats.mean) - nanos(before.mean);
+        let significant = if error > 0.0 {
+            (difference / error).abs() > T_CRITICAL
+        } else {
+            difference != 0.0
+        };
+
+        let trend = match delta {
+            _ if !significant => Trend::Unchanged,
+            delta if delta > threshold => Trend::Slower,
+            delta if delta < -threshold => Trend::Faster,
+            _ => Trend::Unchanged,
+        };
+        Comparison {
+            before: Some(before.median),
+            after: stats.median,
+            delta,
+            trend,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write the baseline, creating `baselines/` if needed.
+    pub fn save(&self) -> Result<(), AOCError> {
+        let error =
+            |e: std::io::Error| AOCError::BaselineFile(format!("{}: {}", self.path.display(), e));
+        fs::create_dir_all(self.path.parent().unwrap()).map_err(error)?;
+        fs::write(&self.path, serialize(&self.timings)).map_err(error)
+    }
+}
+
+fn parse(raw: &str) -> Result<BTreeMap<(usize, Part), Stats>, String> {
+    let table = raw.parse::<toml::Table>().map_err(|e| e.to_string())?;
+    let mut timings = BTreeMap::new();
+
+    for (key, parts) in table {
+        let day = key
+            .strip_prefix("day")
+            .and_then(|d| d.parse::<usize>().ok())
+            .ok_or_else(|| format!("invalid section [{}], expected [dayNN]", key))?;
+        let parts = parts
+            .as_table()
+            .ok_or_else(|| format!("[{}] must be a table", key))?;
+
+        for (part, stats) in parts {
+            let name = format!("{}.{}", key, part);
+            let part = Part::from_str(part)
+                .ok_or_else(|| format!("invalid part '{}' in [{}]", part, key))?;
+            let field = |field: &str| {
+                stats
+                    .get(field)
+                    .and_then(toml::Value::as_integer)
+                    .and_then(|n| u64::try_from(n).ok())
+                    .ok_or_else(|| format!("[{}] needs a non-negative integer {}", name, field))
+            };
+            let stats = Stats {
+                samples: field("samples")? as usize,
+                min: Duration::from_nanos(field("min_ns")?),
+                median: Duration::from_nanos(field("median_ns")?),
+                mean: Duration::from_nanos(field("mean_ns")?),
+                stddev: Duration::from_nanos(field("stddev_ns")?),
+                outliers: field("outliers")? as usize,
+            };
+            if stats.samples == 0 {
+                return Err(format!("[{}] has no samples", name));
+            }
+            timings.insert((day, part), stats);
+        }
+    }
+
+    Ok(timings)
+}
+
+fn serialize(timings: &BTreeMap<(usize, Part), Stats>) -> String {
+    let mut table = toml::Table::new();
+    for ((day, part), stats) in timings {
+        let nanos = |d: Duration| toml::Value::Integer(d.as_nanos() as i64);
+        let stats = toml::Table::from_iter([
+            (
+                "samples".to_string(),
+                toml::Value::Integer(stats.samples as i64),
+            ),
+            ("min_ns".to_string(), nanos(stats.min)),
+            ("median_ns".to_string(), nanos(stats.median)),
+            ("mean_ns".to_string(), nanos(stats.mean)),
+            ("stddev_ns".to_string(), nanos(stats.stddev)),
+            (
+                "outliers".to_string(),
+                toml::Value::Integer(stats.outliers as i64),
+            ),
+        ]);
+        table
+            .entry(format!("day{:02}", day))
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .unwrap()
+            .insert(part.to_string(), toml::Value::Table(stats));
+    }
+    toml::to_string(&table).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(median_us: u64, stddev_us: u64, samples: usize) -> Stats {
+        Stats {
+            samples,
+            min: Duration::from_micros(median_us - stddev_us),
+            median: Duration::from_micros(median_us),
+            mean: Duration::from_micros(median_us),
+            stddev: Duration::from_micros(stddev_us),
+            outliers: 0,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let root = std::env::temp_dir().join(format!("aoc-baseline-{}", std::process::id()));
+        let mut baseline = Baseline::load(&root, "main").unwrap();
+        assert!(!baseline.exists());
+        baseline.set(11, Part::A, stats(1200, 15, 120));
+        baseline.set(15, Part::B, stats(90_000, 2_000, 22));
+        baseline.save().unwrap();
+
+        let loaded = Baseline::load(&root, "main").unwrap();
+        assert_eq!(loaded.path(), root.join("baselines/main.toml"));
+        assert_eq!(loaded.timings, baseline.timings);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(parse("[day11.a]\nsamples = 3\n").is_err());
+        assert!(parse("[day11.c]\n").is_err());
+        assert!(parse("[eleven.a]\n").is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        let mut baseline = Baseline {
+            path: PathBuf::new(),
+            timings: BTreeMap::new(),
+        };
+        baseline.set(11, Part::A, stats(1000, 20, 100));
+        let compare = |after: &Stats| baseline.compare(11, Part::A, after, 5.0);
+
+        let slower = compare(&stats(1200, 20, 100));
+        assert_eq!(slower.trend, Trend::Slower);
+        assert_eq!(slower.before, Some(Duration::from_micros(1000)));
+        assert!((slower.delta - 20.0).abs() < 1e-9);
+
+        assert_eq!(compare(&stats(800, 20, 100)).trend, Trend::Faster);
+        // Significant, but below the threshold.
+        assert_eq!(compare(&stats(1030, 20, 100)).trend, Trend::Unchanged);
+        // Above the threshold, but lost in the noise.
+        assert_eq!(compare(&stats(1200, 900, 10)).trend, Trend::Unchanged);
+
+        let new = baseline.compare(11, Part::B, &stats(1000, 20, 100), 5.0);
+        assert_eq!((new.before, new.trend), (None, Trend::New));
+    }
+}
@@ -0,0 +1,121 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// How long to run a part before sampling, to warm caches and the branch predictor.
+const WARM_UP: Duration = Duration::from_millis(200);
+/// Time budget for sampling a single part.
+const MEASURE: Duration = Duration::from_secs(2);
+const MIN_SAMPLES: usize = 10;
+const MAX_SAMPLES: usize = 1000;
+
+/// Summary of the samples taken for one part.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+    /// Samples outside of 1.5 IQR of the first and third quartile.
+    pub outliers: usize,
+}
+
+/// Benchmark `f`, the number of samples adapts to how long a single run takes
+/// so slow parts are not run a thousand times.
+pub fn bench<T, F: FnMut() -> T>(mut f: F) -> Stats {
+    let warm_up = Instant::now();
+    let mut runs = 0u32;
+    while runs == 0 || warm_up.elapsed() < WARM_UP {
+        black_box(f());
+        runs += 1;
+    }
+    let estimate = warm_up.elapsed() / runs;
+
+    let samples = (MEASURE.as_nanos() / estimate.as_nanos().max(1))
+        .clamp(MIN_SAMPLES as u128, MAX_SAMPLES as u128) as usize;
+    let times = (0..samples)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect::<Vec<_>>();
+
+    Stats::from_samples(times)
+}
+
+impl Stats {
+    pub fn from_samples(mut times: Vec<Duration>) -> Self {
+        assert!(!times.is_empty(), "no samples to summarize");
+        times.sort();
+
+        let n = times.len();
+        let nanos = times
+            .iter()
+            .map(|t| t.as_nanos() as f64)
+            .collect::<Vec<_>>();
+        let mean = nanos.iter().sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            nanos.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        let q1 = quantile(&nanos, 0.25);
+        let q3 = quantile(&nanos, 0.75);
+        let iqr = q3 - q1;
+        let outliers = nanos
+            .iter()
+            .filter(|&&t| t < q1 - 1.5 * iqr || t > q3 + 1.5 * iqr)
+            .count();
+
+        Self {
+            samples: n,
+            min: times[0],
+            median: Duration::from_nanos(quantile(&nanos, 0.5) as u64),
+            mean: Duration::from_nanos(mean as u64),
+            stddev: Duration::from_nanos(variance.sqrt() as u64),
+            outliers,
+        }
+    }
+}
+
+/// Linearly interpolated quantile of sorted values.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let lower = pos.floor() as usize;
+    let upper = pos.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (pos - lower as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn micros(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&v| Duration::from_micros(v)).collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(micros(&[12, 10, 11, 10, 13, 10, 50]));
+        assert_eq!(stats.samples, 7);
+        assert_eq!(stats.min, Duration::from_micros(10));
+        assert_eq!(stats.median, Duration::from_micros(11));
+        assert_eq!(stats.mean.as_micros(), 16);
+        assert_eq!(stats.outliers, 1);
+
+        let stats = Stats::from_samples(micros(&[5]));
+        assert_eq!(stats.median, Duration::from_micros(5));
+        assert_eq!(stats.stddev, Duration::ZERO);
+        assert_eq!(stats.outliers, 0);
+    }
+
+    #[test]
+    fn test_bench_runs_function() {
+        let mut calls = 0;
+        let stats = bench(|| calls += 1);
+        assert!(stats.samples >= MIN_SAMPLES && stats.samples <= MAX_SAMPLES);
+        assert!(calls > stats.samples);
+    }
+}
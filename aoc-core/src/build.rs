@@ -0,0 +1,130 @@
+//! Build script support for the year crates.
+//!
+//! Every `src/days/dayNN.rs` file is declared as a module and registered in a
+//! generated `get_solver`, so adding a day only means adding its file. With
+//! the year crate's `embed-inputs` feature, `inputs/NN.in` are compiled in too.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Discover `src/days/dayNN.rs` and write `$OUT_DIR/days.rs`, to be
+/// `include!`d from `src/days/mod.rs`.
+pub fn register_days() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let days_dir = manifest_dir.join("src").join("days");
+    let inputs_dir = manifest_dir.join("inputs");
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("days.rs");
+
+    println!("cargo:rerun-if-changed={}", days_dir.display());
+
+    let days = discover_days(&days_dir);
+    let inputs = if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_some() {
+        println!("cargo:rerun-if-changed={}", inputs_dir.display());
+        days.iter()
+            .map(|day| inputs_dir.join(format!("{:02}.in", day)))
+            .filter(|path| path.is_file())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    fs::write(&out_path, generate(&days_dir, &days, &inputs)).unwrap();
+}
+
+fn discover_days(days_dir: &Path) -> Vec<usize> {
+    let mut days = fs::read_dir(days_dir)
+        .unwrap_or_else(|e| panic!("could not read {}: {}", days_dir.display(), e))
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            parse_day(&name)
+        })
+        .collect::<Vec<usize>>();
+    days.sort_unstable();
+    days
+}
+
+fn parse_day(file_name: &str) -> Option<usize> {
+    let digits = file_name.strip_prefix("day")?.strip_suffix(".rs")?;
+    if digits.len() != 2 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+fn generate(days_dir: &Path, days: &[usize], inputs: &[PathBuf]) -> String {
+    let mut out = String::from("// @generated by aoc_core::build::register_days\n\n");
+
+    for day in days {
+        let path = days_dir.join(format!("day{:02}.rs", day));
+        writeln!(out, "#[path = {:?}]", path.display().to_string()).unwrap();
+        writeln!(out, "pub mod day{:02};", day).unwrap();
+    }
+
+    let list = days
+        .iter()
+        .map(|day| day.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+    writeln!(out, "\n/// Days that have a solution module.").unwrap();
+    writeln!(out, "pub const DAYS: &[usize] = &[{}];", list).unwrap();
+
+    out.push_str(
+        "\npub fn get_solver(day: usize) -> Result<Box<dyn aoc_core::Solver>, aoc_core::AOCError> {\n    match day {\n",
+    );
+    for day in days {
+        writeln!(
+            out,
+            "        {} => Ok(Box::new(day{:02}::Solution {{}})),",
+            day, day
+        )
+        .unwrap();
+    }
+    out.push_str("        _ => Err(aoc_core::AOCError::SolutionNotFound),\n    }\n}\n");
+
+    out.push_str("\n/// Inputs compiled in with the `embed-inputs` feature.\n");
+    out.push_str("const EMBEDDED_INPUTS: &[(usize, &str)] = &[\n");
+    for path in inputs {
+        let day = path.file_stem().and_then(|s| s.to_str()).unwrap();
+        writeln!(
+            out,
+            "    ({}, include_str!({:?})),",
+            day.trim_start_matches('0'),
+            path.display().to_string()
+        )
+        .unwrap();
+    }
+    out.push_str(
+        "];\n\npub fn embedded_input(day: usize) -> Option<&'static str> {\n    EMBEDDED_INPUTS.iter().find(|(d, _)| *d == day).map(|(_, input)| *input)\n}\n",
+    );
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_day() {
+        assert_eq!(parse_day("day07.rs"), Some(7));
+        assert_eq!(parse_day("day25.rs"), Some(25));
+        assert_eq!(parse_day("template.rs"), None);
+        assert_eq!(parse_day("mod.rs"), None);
+        assert_eq!(parse_day("day7.rs"), None);
+        assert_eq!(parse_day("day07.rs.orig"), None);
+    }
+
+    #[test]
+    fn test_generate() {
+        let code = generate(
+            Path::new("/src/days"),
+            &[1, 12],
+            &[PathBuf::from("/inputs/12.in")],
+        );
+        assert!(code.contains("#[path = \"/src/days/day01.rs\"]\npub mod day01;"));
+        assert!(code.contains("pub const DAYS: &[usize] = &[1, 12];"));
+        assert!(code.contains("12 => Ok(Box::new(day12::Solution {})),"));
+        assert!(code.contains("(12, include_str!(\"/inputs/12.in\")),"));
+    }
+}
@@ -0,0 +1,668 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::report::Format;
+use crate::InputSource;
+
+pub const FIRST_DAY: usize = 1;
+pub const LAST_DAY: usize = 25;
+
+/// The help text, `name` is the binary: `aoc2022` for a single year or
+/// `aoc` for all of them.
+pub fn usage(name: &str) -> String {
+    format!(
+        "Usage: {name} [run] [OPTIONS]
+       {name} new <DAY>  Create src/days/dayNN.rs from the template and an empty input
+       {name} fetch <N|A..=B>  Download missing inputs (session from $AOC_SESSION or aoc.toml)
+       {name} submit --day <N> --part <a|b>  Solve a part and post its answer
+       {name} generate [--day <N|A..=B>] [--seed <S>] [--size <K>]  Write random inputs to generated/NN.in
+
+Options:
+  --year <YYYY>     Select a year when built with several (default: the latest)
+  --all-years       Run every year in turn, each with its own total
+  --day <N|A..=B>   Run a single day or an inclusive range of days (default: all)
+  --part <a|b>      Only run one part of each day
+  --input <PATH>    Read the puzzle input from PATH, or stdin for '-' (requires a single day)
+  --inputs <DIR>    Read inputs from DIR/NN.in[.gz|.zst] (default: the crate's inputs/), or generate into DIR
+  --verify          Check answers against inputs/answers.toml, fail on a mismatch
+  --record          Write the computed answers to inputs/answers.toml
+  --examples        Run each day's examples instead of its input, fail on a mismatch
+  --bench           Benchmark each part: warm-up, then report min/median/mean/stddev
+  --save-baseline <NAME>  Benchmark every part and save the timings as baselines/NAME.toml
+  --compare <NAME>  Benchmark every part and compare to a saved baseline, fail on a regression
+  --threshold <PCT> Change in percent a part may be slower or faster by (default: 5)
+  --jobs <N>        Run up to N days at once (default: one per core, 1 with --bench)
+  --timeout <TIME>  Give up on a parse or part after TIME, e.g. 500ms, 5s or 2m (default: aoc.toml)
+  --format <FMT>    Report format: text, json, csv or junit (default: text)
+  --output <PATH>   Write the report to PATH instead of stdout (requires --format)
+  --visualize <DIR> Save the frames solvers draw to DIR/dayNNp.gif, or play them for '-'
+  --visualize-format <gif|png>  Save frames as a GIF (default) or DIR/dayNNp/NNNNN.png
+  --seed <S>        Seed for generate, the same seed gives the same inputs (default: 0)
+  --size <K>        Size of generated inputs, in each day's own unit (default: like a real input)
+  --list            List registered solutions and available inputs
+  -v, --verbose     Show what solvers log and count, and their progress; -vv adds debug logs
+  -h, --help        Print this message"
+    )
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    A,
+    B,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::A, Part::B];
+
+    pub(crate) fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "a" | "1" => Some(Part::A),
+            "b" | "2" => Some(Part::B),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::A => write!(f, "a"),
+            Part::B => write!(f, "b"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run,
+    List,
+    Help,
+    /// Scaffold a new day from the template.
+    New(usize),
+    /// Download the inputs of a range of days that are not cached yet.
+    Fetch(RangeInclusive<usize>),
+    /// Solve one part and post its answer.
+    Submit,
+    /// Write random inputs for the selected days that have a generator.
+    Generate,
+}
+
+/// What to do with the known answers in the answers file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AnswerMode {
+    Ignore,
+    Verify,
+    Record,
+}
+
+/// Where `--visualize` puts the frames solvers draw.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Visualize {
+    Terminal,
+    Gif(PathBuf),
+    Png(PathBuf),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Args {
+    pub command: Command,
+    pub year: Option<usize>,
+    pub all_years: bool,
+    pub days: RangeInclusive<usize>,
+    pub part: Option<Part>,
+    pub input: Option<InputSource>,
+    pub inputs: Option<PathBuf>,
+    pub answers: AnswerMode,
+    pub examples: bool,
+    pub bench: bool,
+    pub save_baseline: Option<String>,
+    pub compare: Option<String>,
+    /// In percent, for `--compare`.
+    pub threshold: u32,
+    pub jobs: Option<usize>,
+    pub timeout: Option<Duration>,
+    pub format: Format,
+    pub output: Option<PathBuf>,
+    /// How many times `-v` was given.
+    pub verbose: u8,
+    pub visualize: Option<Visualize>,
+    /// For `generate`.
+    pub seed: Option<u64>,
+    pub size: Option<usize>,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            command: Command::Run,
+            year: None,
+            all_years: false,
+            days: FIRST_DAY..=LAST_DAY,
+            part: None,
+            input: None,
+            inputs: None,
+            answers: AnswerMode::Ignore,
+            examples: false,
+            bench: false,
+            save_baseline: None,
+            compare: None,
+            threshold: 5,
+            jobs: None,
+            timeout: None,
+            format: Format::Text,
+            output: None,
+            verbose: 0,
+            visualize: None,
+            seed: None,
+            size: None,
+        }
+    }
+}
+
+impl Args {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        let mut visualize_format = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--year" | "-y" => {
+                    let value = expect_value(&arg, args.next())?;
+                    let year = value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|&year| year >= 2015)
+                        .ok_or_else(|| format!("invalid year '{}'", value))?;
+                    parsed.year = Some(year);
+                }
+                "--all-years" => parsed.all_years = true,
+                "--day" | "-d" => parsed.days = parse_days(&expect_value(&arg, args.next())?)?,
+                "--part" | "-p" => {
+                    let value = expect_value(&arg, args.next())?;
+                    let part = Part::from_str(&value)
+                        .ok_or_else(|| format!("invalid part '{}', expected 'a' or 'b'", value))?;
+                    parsed.part = Some(part);
+                }
+                "--input" | "-i" => {
+                    parsed.input = Some(match expect_value(&arg, args.next())?.as_str() {
+                        "-" => InputSource::Stdin,
+                        path => InputSource::File(PathBuf::from(path)),
+                    })
+                }
+                "--inputs" => parsed.inputs = Some(PathBuf::from(expect_value(&arg, args.next())?)),
+                "--verify" => parsed.answers = set_answer_mode(parsed.answers, AnswerMode::Verify)?,
+                "--record" => parsed.answers = set_answer_mode(parsed.answers, AnswerMode::Record)?,
+                "--examples" => parsed.examples = true,
+                "--bench" => parsed.bench = true,
+                "--save-baseline" => {
+                    parsed.save_baseline = Some(parse_baseline(&expect_value(&arg, args.next())?)?)
+                }
+                "--compare" => {
+                    parsed.compare = Some(parse_baseline(&expect_value(&arg, args.next())?)?)
+                }
+                "--threshold" => {
+                    let value = expect_value(&arg, args.next())?;
+                    parsed.threshold = value.trim_end_matches('%').parse().map_err(|_| {
+                        format!("invalid threshold '{}', expected a percentage", value)
+                    })?;
+                }
+                "--jobs" | "-j" => {
+                    let value = expect_value(&arg, args.next())?;
+                    let jobs = value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|&jobs| jobs > 0)
+                        .ok_or_else(|| format!("invalid job count '{}'", value))?;
+                    parsed.jobs = Some(jobs);
+                }
+                "--timeout" | "-t" => {
+                    parsed.timeout = Some(parse_duration(&expect_value(&arg, args.next())?)?)
+                }
+                "--format" | "-f" => {
+                    let value = expect_value(&arg, args.next())?;
+                    parsed.format = Format::from_str(&value).ok_or_else(|| {
+                        format!(
+                            "invalid format '{}', expected text, json, csv or junit",
+                            value
+                        )
+                    })?;
+                }
+                "--output" | "-o" => {
+                    parsed.output = Some(PathBuf::from(expect_value(&arg, args.next())?))
+                }
+                "--visualize" => {
+                    parsed.visualize = Some(match expect_value(&arg, args.next())?.as_str() {
+                        "-" => Visualize::Terminal,
+                        dir => Visualize::Gif(PathBuf::from(dir)),
+                    })
+                }
+                "--visualize-format" => {
+                    let value = expect_value(&arg, args.next())?;
+                    visualize_format = match value.as_str() {
+                        "gif" | "png" => Some(value),
+                        _ => {
+                            return Err(format!(
+                                "invalid visualize format '{}', expected gif or png",
+                                value
+                            ))
+                        }
+                    };
+                }
+                "--seed" => {
+                    let value = expect_value(&arg, args.next())?;
+                    let seed = value
+                        .parse::<u64>()
+                        .map_err(|_| format!("invalid seed '{}'", value))?;
+                    parsed.seed = Some(seed);
+                }
+                "--size" => {
+                    let value = expect_value(&arg, args.next())?;
+                    let size = value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|&size| size > 0)
+                        .ok_or_else(|| format!("invalid size '{}'", value))?;
+                    parsed.size = Some(size);
+                }
+                "--list" | "-l" => parsed.command = Command::List,
+                "--verbose" | "-v" => parsed.verbose += 1,
+                "-vv" => parsed.verbose += 2,
+                "--help" | "-h" => parsed.command = Command::Help,
+                "new" => {
+                    parsed.command = Command::New(parse_day(&expect_value(&arg, args.next())?)?)
+                }
+                "fetch" => {
+                    parsed.command = Command::Fetch(parse_days(&expect_value(&arg, args.next())?)?)
+                }
+                "submit" => parsed.command = Command::Submit,
+                "generate" => parsed.command = Command::Generate,
+                "run" => parsed.command = Command::Run,
+                _ => return Err(format!("unknown argument '{}'", arg)),
+            }
+        }
+
+        if parsed.input.is_some() && parsed.days.start() != parsed.days.end() {
+            return Err("--input requires a single --day".to_string());
+        }
+
+        if parsed.command == Command::Submit
+            && (parsed.part.is_none() || parsed.days.start() != parsed.days.end())
+        {
+            return Err("submit requires a single --day and a --part".to_string());
+        }
+
+        if parsed.all_years {
+            if parsed.year.is_some() {
+                return Err("--all-years cannot be combined with --year".to_string());
+            }
+            if !matches!(parsed.command, Command::Run | Command::List | Command::Help) {
+                return Err("new, fetch, submit and generate work on a single --year".to_string());
+            }
+            if parsed.input.is_some() || parsed.format != Format::Text {
+                return Err("--all-years cannot be combined with --input or --format".to_string());
+            }
+        }
+
+        if parsed.examples && (parsed.input.is_some() || parsed.answers != AnswerMode::Ignore) {
+            return Err(
+                "--examples cannot be combined with --input, --verify or --record".to_string(),
+            );
+        }
+
+        match (&parsed.visualize, visualize_format.as_deref()) {
+            (Some(Visualize::Gif(dir)), Some("png")) => {
+                parsed.visualize = Some(Visualize::Png(dir.clone()))
+            }
+            (Some(Visualize::Terminal), Some(_)) | (None, Some(_)) => {
+                return Err("--visualize-format requires --visualize <DIR>".to_string())
+            }
+            (Some(Visualize::Terminal), None) if parsed.format != Format::Text => {
+                return Err("--visualize - cannot be combined with --format".to_string())
+            }
+            _ => (),
+        }
+        if parsed.all_years && parsed.visualize.is_some() {
+            return Err("--visualize works on a single --year".to_string());
+        }
+
+        if parsed.save_baseline.is_some() || parsed.compare.is_some() {
+            if parsed.examples {
+                return Err("--examples cannot be combined with baselines".to_string());
+            }
+            if parsed.compare.is_some() && parsed.format != Format::Text {
+                return Err("--compare cannot be combined with --format".to_string());
+            }
+            // Comparisons need samples to tell a change from noise.
+            parsed.bench = true;
+        }
+
+        if (parsed.seed.is_some() || parsed.size.is_some()) && parsed.command != Command::Generate {
+            return Err("--seed and --size only apply to generate".to_string());
+        }
+
+        if parsed.output.is_some() && parsed.format == Format::Text {
+            return Err("--output requires a --format other than text".to_string());
+        }
+
+        Ok(parsed)
+    }
+
+    /// How many days to run at once. Benchmarks run alone so they do not
+    /// compete for cores.
+    pub fn jobs(&self) -> usize {
+        match self.jobs {
+            Some(jobs) => jobs,
+            None if self.bench => 1,
+            None => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    pub fn parts(&self) -> Vec<Part> {
+        match self.part {
+            Some(part) => vec![part],
+            None => Part::ALL.to_vec(),
+        }
+    }
+}
+
+fn expect_value(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("missing value for '{}'", flag))
+}
+
+fn set_answer_mode(current: AnswerMode, new: AnswerMode) -> Result<AnswerMode, String> {
+    match current {
+        AnswerMode::Ignore => Ok(new),
+        _ if current == new => Ok(new),
+        _ => Err("--verify and --record cannot be combined".to_string()),
+    }
+}
+
+/// A duration such as `500ms`, `5s` or `2m`, a bare number is in seconds.
+pub(crate) fn parse_duration(raw: &str) -> Result<Duration, String> {
+    let raw = raw.trim();
+    let (number, unit) = match raw.find(|c: char| !c.is_ascii_digit() && c != '.') {
+        Some(i) => raw.split_at(i),
+        None => (raw, "s"),
+    };
+    let scale = match unit {
+        "ms" => 0.001,
+        "s" => 1.0,
+        "m" => 60.0,
+        _ => return Err(format!("invalid unit in '{}', expected ms, s or m", raw)),
+    };
+    match number
+        .parse::<f64>()
+        .map(|n| (n, Duration::try_from_secs_f64(n * scale)))
+    {
+        Ok((n, Ok(duration))) if n > 0.0 => Ok(duration),
+        _ => Err(format!("invalid duration '{}', expected e.g. 5s", raw)),
+    }
+}
+
+/// A baseline name, which becomes a file name.
+fn parse_baseline(raw: &str) -> Result<String, String> {
+    let valid = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.');
+    if raw.is_empty() || raw.starts_with('.') || !raw.chars().all(valid) {
+        return Err(format!(
+            "invalid baseline name '{}', expected letters, digits, '-', '_' or '.'",
+            raw
+        ));
+    }
+    Ok(raw.to_string())
+}
+
+fn parse_day(raw: &str) -> Result<usize, String> {
+    match raw.trim().parse::<usize>() {
+        Ok(day) if (FIRST_DAY..=LAST_DAY).contains(&day) => Ok(day),
+        _ => Err(format!(
+            "invalid day '{}', expected a number in {}..={}",
+            raw, FIRST_DAY, LAST_DAY
+        )),
+    }
+}
+
+fn parse_days(raw: &str) -> Result<RangeInclusive<usize>, String> {
+    let (start, end) = if let Some((start, end)) = raw.split_once("..=") {
+        (parse_day(start)?, parse_day(end)?)
+    } else if let Some((start, end)) = raw.split_once("..") {
+        let end = parse_day(end)?;
+        if end == FIRST_DAY {
+            return Err(format!("empty day range '{}'", raw));
+        }
+        (parse_day(start)?, end - 1)
+    } else {
+        let day = parse_day(raw)?;
+        (day, day)
+    };
+
+    if start > end {
+        return Err(format!("empty day range '{}'", raw));
+    }
+
+    Ok(start..=end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_defaults_cover_all_days() {
+        let args = parse(&[]).unwrap();
+        assert_eq!(args.days, 1..=25);
+        assert_eq!(args.parts(), vec![Part::A, Part::B]);
+        assert_eq!(args.command, Command::Run);
+    }
+
+    #[test]
+    fn test_day_ranges() {
+        assert_eq!(parse(&["--day", "7"]).unwrap().days, 7..=7);
+        assert_eq!(parse(&["--day", "10..=15"]).unwrap().days, 10..=15);
+        assert_eq!(parse(&["--day", "10..15"]).unwrap().days, 10..=14);
+        assert!(parse(&["--day", "15..=10"]).is_err());
+        assert!(parse(&["--day", "26"]).is_err());
+        assert!(parse(&["--day", "0"]).is_err());
+        assert!(parse(&["--day"]).is_err());
+    }
+
+    #[test]
+    fn test_part_and_input() {
+        let args = parse(&["--day", "3", "--part", "b", "--input", "example.txt"]).unwrap();
+        assert_eq!(args.parts(), vec![Part::B]);
+        assert_eq!(
+            args.input,
+            Some(InputSource::File(PathBuf::from("example.txt")))
+        );
+        let args = parse(&["--day", "3", "--input", "-"]).unwrap();
+        assert_eq!(args.input, Some(InputSource::Stdin));
+        let args = parse(&["--inputs", "/tmp/inputs"]).unwrap();
+        assert_eq!(args.inputs, Some(PathBuf::from("/tmp/inputs")));
+
+        assert!(parse(&["--part", "c"]).is_err());
+        assert!(parse(&["--input", "example.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_answer_mode() {
+        assert_eq!(parse(&[]).unwrap().answers, AnswerMode::Ignore);
+        assert_eq!(parse(&["--verify"]).unwrap().answers, AnswerMode::Verify);
+        assert_eq!(parse(&["--record"]).unwrap().answers, AnswerMode::Record);
+        assert!(parse(&["--verify", "--record"]).is_err());
+    }
+
+    #[test]
+    fn test_examples() {
+        assert!(parse(&["--examples", "--day", "6"]).unwrap().examples);
+        assert!(parse(&["--examples", "--verify"]).is_err());
+        assert!(parse(&["--examples", "-d", "6", "-i", "06.in"]).is_err());
+    }
+
+    #[test]
+    fn test_bench() {
+        assert!(!parse(&[]).unwrap().bench);
+        assert!(parse(&["--bench", "--day", "11"]).unwrap().bench);
+    }
+
+    #[test]
+    fn test_baselines() {
+        let args = parse(&["--save-baseline", "main", "--day", "11"]).unwrap();
+        assert_eq!(args.save_baseline, Some("main".to_string()));
+        assert!(args.bench);
+        assert_eq!(args.jobs(), 1);
+        let args = parse(&["--compare", "v1.2", "--threshold", "10%"]).unwrap();
+        assert_eq!(
+            (args.compare, args.threshold),
+            (Some("v1.2".to_string()), 10)
+        );
+        assert_eq!(parse(&[]).unwrap().threshold, 5);
+
+        assert!(parse(&["--compare", "../main"]).is_err());
+        assert!(parse(&["--save-baseline", ""]).is_err());
+        assert!(parse(&["--threshold", "five"]).is_err());
+        assert!(parse(&["--compare", "main", "--examples"]).is_err());
+        assert!(parse(&["--compare", "main", "--format", "json"]).is_err());
+    }
+
+    #[test]
+    fn test_jobs() {
+        assert_eq!(parse(&["--jobs", "3"]).unwrap().jobs(), 3);
+        assert_eq!(parse(&["--bench"]).unwrap().jobs(), 1);
+        assert!(parse(&[]).unwrap().jobs() >= 1);
+        assert!(parse(&["--jobs", "0"]).is_err());
+        assert!(parse(&["-j", "many"]).is_err());
+    }
+
+    #[test]
+    fn test_timeout() {
+        assert_eq!(parse(&[]).unwrap().timeout, None);
+        assert_eq!(
+            parse(&["--timeout", "5s"]).unwrap().timeout,
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration("1.5m"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("10"), Ok(Duration::from_secs(10)));
+        assert!(parse_duration("5h").is_err());
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("s").is_err());
+        assert!(parse_duration("99999999999999999999m").is_err());
+        assert!(parse_duration(&format!("{}s", "9".repeat(400))).is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(parse(&[]).unwrap().format, Format::Text);
+        let args = parse(&["--format", "junit", "--output", "report.xml"]).unwrap();
+        assert_eq!(args.format, Format::Junit);
+        assert_eq!(args.output, Some(PathBuf::from("report.xml")));
+
+        assert!(parse(&["--format", "yaml"]).is_err());
+        assert!(parse(&["--output", "report.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(parse(&["new", "23"]).unwrap().command, Command::New(23));
+        assert!(parse(&["new"]).is_err());
+        assert!(parse(&["new", "26"]).is_err());
+    }
+
+    #[test]
+    fn test_fetch() {
+        assert_eq!(
+            parse(&["fetch", "5"]).unwrap().command,
+            Command::Fetch(5..=5)
+        );
+        assert_eq!(
+            parse(&["fetch", "1..=3"]).unwrap().command,
+            Command::Fetch(1..=3)
+        );
+        assert!(parse(&["fetch"]).is_err());
+    }
+
+    #[test]
+    fn test_generate() {
+        let args = parse(&["generate", "--day", "16", "--seed", "7", "--size", "30"]).unwrap();
+        assert_eq!(args.command, Command::Generate);
+        assert_eq!(
+            (args.days, args.seed, args.size),
+            (16..=16, Some(7), Some(30))
+        );
+        let args = parse(&["generate"]).unwrap();
+        assert_eq!((args.seed, args.size), (None, None));
+        assert!(parse(&["generate", "--size", "0"]).is_err());
+        assert!(parse(&["generate", "--seed", "-1"]).is_err());
+        assert!(parse(&["--seed", "7"]).is_err());
+        assert!(parse(&["--all-years", "generate"]).is_err());
+    }
+
+    #[test]
+    fn test_submit() {
+        let args = parse(&["submit", "--day", "7", "--part", "b"]).unwrap();
+        assert_eq!(args.command, Command::Submit);
+        assert_eq!((args.days, args.part), (7..=7, Some(Part::B)));
+        assert!(parse(&["submit", "--day", "7"]).is_err());
+        assert!(parse(&["submit", "--part", "a"]).is_err());
+    }
+
+    #[test]
+    fn test_years() {
+        let args = parse(&["run", "--year", "2021", "--day", "15"]).unwrap();
+        assert_eq!(
+            (args.command, args.year, args.days),
+            (Command::Run, Some(2021), 15..=15)
+        );
+        assert!(parse(&["run", "--all-years"]).unwrap().all_years);
+
+        assert!(parse(&["--year", "21"]).is_err());
+        assert!(parse(&["--all-years", "--year", "2021"]).is_err());
+        assert!(parse(&["--all-years", "fetch", "1"]).is_err());
+        assert!(parse(&["--all-years", "--format", "json"]).is_err());
+    }
+
+    #[test]
+    fn test_verbose() {
+        assert_eq!(parse(&[]).unwrap().verbose, 0);
+        assert_eq!(parse(&["-v"]).unwrap().verbose, 1);
+        assert_eq!(parse(&["-vv"]).unwrap().verbose, 2);
+        assert_eq!(parse(&["--verbose", "-v"]).unwrap().verbose, 2);
+    }
+
+    #[test]
+    fn test_visualize() {
+        assert_eq!(parse(&[]).unwrap().visualize, None);
+        assert_eq!(
+            parse(&["--visualize", "-"]).unwrap().visualize,
+            Some(Visualize::Terminal)
+        );
+        assert_eq!(
+            parse(&["--visualize", "out"]).unwrap().visualize,
+            Some(Visualize::Gif(PathBuf::from("out")))
+        );
+        assert_eq!(
+            parse(&["--visualize-format", "png", "--visualize", "out"])
+                .unwrap()
+                .visualize,
+            Some(Visualize::Png(PathBuf::from("out")))
+        );
+
+        assert!(parse(&["--visualize-format", "png"]).is_err());
+        assert!(parse(&["--visualize", "-", "--visualize-format", "gif"]).is_err());
+        assert!(parse(&["--visualize", "out", "--visualize-format", "mp4"]).is_err());
+        assert!(parse(&["--visualize", "-", "--format", "json"]).is_err());
+        assert!(parse(&["--visualize", "out", "--all-years"]).is_err());
+    }
+
+    #[test]
+    fn test_list() {
+        assert_eq!(parse(&["--list"]).unwrap().command, Command::List);
+        assert!(parse(&["--lsit"]).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::cli::parse_duration;
+use crate::AOCError;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Per-year runner settings, stored as `aoc.toml` next to the year's
+/// `Cargo.toml`:
+///
+/// ```toml
+/// timeout = "30s"
+/// base_url = "https://adventofcode.com"
+/// session_file = "~/.config/aoc/session"
+///
+/// [day17]
+/// timeout = "5s"
+/// ```
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    timeout: Option<Duration>,
+    day_timeouts: BTreeMap<usize, Duration>,
+    base_url: Option<String>,
+    session_file: Option<PathBuf>,
+}
+
+impl Config {
+    /// Load the config file, a missing file is treated as empty.
+    pub fn load(path: &Path) -> Result<Self, AOCError> {
+        let raw = match fs::read_to_string(path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(AOCError::ConfigFile(format!("{}: {}", path.display(), e))),
+        };
+
+        parse(&raw).map_err(|e| AOCError::ConfigFile(format!("{}: {}", path.display(), e)))
+    }
+
+    /// The time limit for each part of `day`, if any.
+    pub fn timeout(&self, day: usize) -> Option<Duration> {
+        self.day_timeouts.get(&day).copied().or(self.timeout)
+    }
+
+    /// Where `fetch` downloads inputs from.
+    pub fn base_url(&self) -> &str {
+        self.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL)
+    }
+
+    /// The file holding the session token for `fetch`, if configured.
+    pub fn session_file(&self) -> Option<&Path> {
+        self.session_file.as_deref()
+    }
+}
+
+fn parse(raw: &str) -> Result<Config, String> {
+    let table = raw.parse::<toml::Table>().map_err(|e| e.to_string())?;
+    let mut config = Config::default();
+
+    for (key, value) in table {
+        match key.as_str() {
+            "timeout" => config.timeout = Some(parse_timeout(&key, &value)?),
+            "base_url" => {
+                let url = parse_string(&key, &value)?.trim_end_matches('/');
+                config.base_url = Some(url.to_string());
+            }
+            "session_file" => {
+                config.session_file = Some(PathBuf::from(parse_string(&key, &value)?))
+            }
+            _ => parse_day(&mut config, &key, &value)?,
+        }
+    }
+
+    Ok(config)
+}
+
+fn parse_day(config: &mut Config, key: &str, value: &toml::Value) -> Result<(), String> {
+    let day = key
+        .strip_prefix("day")
+        .and_then(|d| d.parse::<usize>().ok())
+        .ok_or_else(|| {
+            format!(
+                "unknown key '{}', expected timeout, base_url, session_file or [dayNN]",
+                key
+            )
+        })?;
+    let settings = value
+        .as_table()
+        .ok_or_else(|| format!("[{}] must be a table", key))?;
+
+    for (setting, value) in settings {
+        match setting.as_str() {
+            "timeout" => {
+                let timeout = parse_timeout(&format!("{}.{}", key, setting), value)?;
+                config.day_timeouts.insert(day, timeout);
+            }
+            _ => return Err(format!("unknown setting '{}' in [{}]", setting, key)),
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_string<'a>(key: &str, value: &'a toml::Value) -> Result<&'a str, String> {
+    value
+        .as_str()
+        .ok_or_else(|| format!("{} must be a string", key))
+}
+
+fn parse_timeout(key: &str, value: &toml::Value) -> Result<Duration, String> {
+    let value = value
+        .as_str()
+        .ok_or_else(|| format!("{} must be a string such as \"5s\"", key))?;
+    parse_duration(value).map_err(|e| format!("{}: {}", key, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = parse("timeout = \"30s\"\n\n[day17]\ntimeout = \"500ms\"\n").unwrap();
+        assert_eq!(config.timeout(17), Some(Duration::from_millis(500)));
+        assert_eq!(config.timeout(11), Some(Duration::from_secs(30)));
+        assert_eq!(parse("").unwrap().timeout(17), None);
+
+        assert!(parse("timeout = 5").is_err());
+        assert!(parse("[day17]\ntimeout = \"forever\"").is_err());
+        assert!(parse("[day17]\nthreads = 4").is_err());
+        assert!(parse("[seventeen]\ntimeout = \"5s\"").is_err());
+    }
+
+    #[test]
+    fn test_fetch_settings() {
+        let config =
+            parse("base_url = \"http://proxy.local/aoc/\"\nsession_file = \".session\"").unwrap();
+        assert_eq!(config.base_url(), "http://proxy.local/aoc");
+        assert_eq!(config.session_file(), Some(Path::new(".session")));
+
+        let config = parse("").unwrap();
+        assert_eq!(config.base_url(), DEFAULT_BASE_URL);
+        assert_eq!(config.session_file(), None);
+
+        assert!(parse("base_url = 80").is_err());
+    }
+}
@@ -0,0 +1,284 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::visualize::{Frame, MAX_FRAMES};
+
+/// How often a part may print its progress.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Error => write!(f, "error"),
+            Level::Warn => write!(f, "warn"),
+            Level::Info => write!(f, "info"),
+            Level::Debug => write!(f, "debug"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Log {
+    pub level: Level,
+    pub message: String,
+}
+
+/// What a solver logged, counted and drew while solving one part. The parse
+/// is included with the first part.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diagnostics {
+    pub logs: Vec<Log>,
+    pub counters: BTreeMap<&'static str, u64>,
+    /// Only recorded with `--visualize`.
+    pub frames: Vec<Frame>,
+}
+
+impl Diagnostics {
+    pub(crate) fn extend(&mut self, other: Diagnostics) {
+        self.logs.extend(other.logs);
+        self.frames.extend(other.frames);
+        for (name, n) in other.counters {
+            *self.counters.entry(name).or_default() += n;
+        }
+    }
+}
+
+/// What a collector does besides keeping logs and counters.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Collect {
+    /// Print progress to stderr.
+    pub(crate) progress: bool,
+    pub(crate) frames: bool,
+}
+
+struct Collector {
+    /// Prefix for progress lines, e.g. `[DAY 17b]`.
+    label: String,
+    options: Collect,
+    last_progress: Option<Instant>,
+    diagnostics: Diagnostics,
+}
+
+thread_local! {
+    static COLLECTOR: RefCell<Option<Collector>> = const { RefCell::new(None) };
+}
+
+/// A handle for reporting from inside a solver without touching stdout:
+///
+/// ```
+/// let ctx = aoc_core::Context::current();
+/// ctx.info(format_args!("aligned {} to {}", 3, 0));
+/// ctx.count("nodes expanded");
+/// ctx.progress(12, 30);
+/// ctx.frame(|| aoc_core::Frame::from_text("#.o").annotate("step 1"));
+/// ```
+///
+/// The runner collects what each part reports, shows logs and counters with
+/// `-v`, puts them in `--format` reports and exports frames with
+/// `--visualize`. Outside of a run, and while a part is benchmarked,
+/// everything is dropped.
+///
+/// The handle is fetched rather than passed to `Problem::part1`/`part2`:
+/// what reports is usually a helper deep inside a solver, like 2021 day 19's
+/// `align_scanners`, and threading a parameter through every day and helper
+/// would change all their signatures for the few that report. Each part runs
+/// on its own worker thread, so the collector it finds is always its own.
+#[derive(Debug, Clone, Copy)]
+pub struct Context {
+    _private: (),
+}
+
+impl Context {
+    /// The context of the part running on this thread.
+    pub fn current() -> Self {
+        Context { _private: () }
+    }
+
+    pub fn log(&self, level: Level, message: impl fmt::Display) {
+        with_collector(|c| {
+            c.diagnostics.logs.push(Log {
+                level,
+                message: message.to_string(),
+            })
+        });
+    }
+
+    pub fn error(&self, message: impl fmt::Display) {
+        self.log(Level::Error, message);
+    }
+
+    pub fn warn(&self, message: impl fmt::Display) {
+        self.log(Level::Warn, message);
+    }
+
+    pub fn info(&self, message: impl fmt::Display) {
+        self.log(Level::Info, message);
+    }
+
+    pub fn debug(&self, message: impl fmt::Display) {
+        self.log(Level::Debug, message);
+    }
+
+    /// Add one to the counter `name`.
+    pub fn count(&self, name: &'static str) {
+        self.add(name, 1);
+    }
+
+    pub fn add(&self, name: &'static str, n: u64) {
+        with_collector(|c| *c.diagnostics.counters.entry(name).or_default() += n);
+    }
+
+    /// Report that `done` out of `total` steps of a long search are finished.
+    /// Printed to stderr with `-v`, at most once a second.
+    pub fn progress(&self, done: u64, total: u64) {
+        with_collector(|c| {
+            if !c.options.progress
+                || c.last_progress
+                    .is_some_and(|last| last.elapsed() < PROGRESS_INTERVAL)
+            {
+                return;
+            }
+            c.last_progress = Some(Instant::now());
+            let percent = done as f64 * 100.0 / total.max(1) as f64;
+            eprintln!("{} {}/{} ({:.1}%)", c.label, done, total, percent);
+        });
+    }
+
+    /// Whether frames are being recorded, for solvers that need extra work
+    /// to keep what they draw.
+    pub fn visualizing(&self) -> bool {
+        let mut visualizing = false;
+        with_collector(|c| visualizing = c.options.frames);
+        visualizing
+    }
+
+    /// Record a frame for `--visualize`. `frame` is only called while
+    /// recording, so drawing costs nothing in normal runs.
+    pub fn frame(&self, frame: impl FnOnce() -> Frame) {
+        let mut wanted = false;
+        with_collector(|c| {
+            if !c.options.frames {
+                return;
+            }
+            if c.diagnostics.frames.len() < MAX_FRAMES {
+                wanted = true;
+            } else {
+                c.diagnostics.logs.push(Log {
+                    level: Level::Warn,
+                    message: format!("more than {} frames, dropping the rest", MAX_FRAMES),
+                });
+                c.options.frames = false;
+            }
+        });
+        if !wanted {
+            return;
+        }
+        // Drawing may log or count too, so it runs outside the borrow.
+        let frame = frame();
+        with_collector(|c| {
+            if c.diagnostics.frames.len() < MAX_FRAMES {
+                c.diagnostics.frames.push(frame);
+            }
+        });
+    }
+}
+
+fn with_collector(f: impl FnOnce(&mut Collector)) {
+    COLLECTOR.with(|collector| {
+        if let Some(collector) = collector.borrow_mut().as_mut() {
+            f(collector);
+        }
+    });
+}
+
+/// Run `f` with a fresh collector on this thread and return what it reported.
+pub(crate) fn collect<T>(
+    label: String,
+    options: Collect,
+    f: impl FnOnce() -> T,
+) -> (T, Diagnostics) {
+    let previous = COLLECTOR.with(|collector| {
+        collector.replace(Some(Collector {
+            label,
+            options,
+            last_progress: None,
+            diagnostics: Diagnostics::default(),
+        }))
+    });
+    let result = f();
+    let collected = COLLECTOR.with(|collector| collector.replace(previous));
+    (result, collected.map(|c| c.diagnostics).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect() {
+        let ctx = Context::current();
+        ctx.info("dropped, nothing is collecting");
+
+        let ((), diagnostics) = collect("[DAY 19a]".to_string(), Collect::default(), || {
+            ctx.info(format_args!("aligned {} to {}", 3, 0));
+            ctx.debug("details");
+            ctx.count("rotations");
+            ctx.add("rotations", 23);
+            ctx.progress(1, 2);
+            assert!(!ctx.visualizing());
+            ctx.frame(|| panic!("not recording frames"));
+        });
+        assert_eq!(
+            diagnostics.logs,
+            vec![
+                Log {
+                    level: Level::Info,
+                    message: "aligned 3 to 0".to_string()
+                },
+                Log {
+                    level: Level::Debug,
+                    message: "details".to_string()
+                },
+            ]
+        );
+        assert_eq!(diagnostics.counters["rotations"], 24);
+
+        let ((), after) = collect(String::new(), Collect::default(), || ());
+        assert_eq!(after, Diagnostics::default());
+    }
+
+    #[test]
+    fn test_frames() {
+        let options = Collect {
+            progress: false,
+            frames: true,
+        };
+        let ((), diagnostics) = collect(String::new(), options, || {
+            let ctx = Context::current();
+            assert!(ctx.visualizing());
+            // Drawing can report too.
+            ctx.frame(|| {
+                ctx.count("frames drawn");
+                ctx.visualizing();
+                Frame::new(1, 1).annotate(0)
+            });
+            for step in 1..MAX_FRAMES + 10 {
+                ctx.frame(|| Frame::new(1, 1).annotate(step));
+            }
+        });
+        assert_eq!(diagnostics.frames.len(), MAX_FRAMES);
+        assert_eq!(diagnostics.frames[3].annotations(), ["3"]);
+        assert_eq!(diagnostics.counters["frames drawn"], 1);
+        assert_eq!(diagnostics.logs.len(), 1);
+    }
+}
@@ -0,0 +1,236 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub enum AOCError {
+    SolutionNotFound,
+    InputNotFound,
+    /// An input file that exists but could not be read or decompressed.
+    InputFile(String),
+    InvalidInput(ParseError),
+    NotImplemented,
+    /// A solver panicked, with the panic message and its location.
+    Panicked(String, String),
+    /// A part ran past its time limit, with the time waited for it.
+    TimedOut(Duration),
+    /// A part that was not run because an earlier part of its day timed out.
+    Skipped,
+    AnswersFile(String),
+    ReportFile(String),
+    ConfigFile(String),
+    /// A `--save-baseline` or `--compare` file that could not be used.
+    BaselineFile(String),
+    /// `new` could not create a day, or would have overwritten one.
+    Scaffold(String),
+    /// `fetch` could not find a session token or download an input.
+    Fetch(String),
+    /// `submit` could not post an answer, or refused to.
+    Submit(String),
+    /// `generate` could not write an input, or refused to.
+    Generate(String),
+    /// `--visualize` could not write its frames.
+    Visualize(String),
+    /// An answer drawn in block letters that could not be read.
+    Ocr(String),
+}
+
+impl AOCError {
+    /// The variant name, used as the status in run reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AOCError::SolutionNotFound => "SolutionNotFound",
+            AOCError::InputNotFound => "InputNotFound",
+            AOCError::InputFile(_) => "InputFile",
+            AOCError::InvalidInput(_) => "InvalidInput",
+            AOCError::NotImplemented => "NotImplemented",
+            AOCError::Panicked(..) => "Panicked",
+            AOCError::TimedOut(_) => "TimedOut",
+            AOCError::Skipped => "Skipped",
+            AOCError::AnswersFile(_) => "AnswersFile",
+            AOCError::ReportFile(_) => "ReportFile",
+            AOCError::ConfigFile(_) => "ConfigFile",
+            AOCError::BaselineFile(_) => "BaselineFile",
+            AOCError::Scaffold(_) => "Scaffold",
+            AOCError::Fetch(_) => "Fetch",
+            AOCError::Submit(_) => "Submit",
+            AOCError::Generate(_) => "Generate",
+            AOCError::Visualize(_) => "Visualize",
+            AOCError::Ocr(_) => "Ocr",
+        }
+    }
+}
+
+impl fmt::Display for AOCError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AOCError::InvalidInput(e) => write!(f, "{}: {}", self.name(), e),
+            AOCError::Panicked(message, location) => {
+                write!(f, "{}: '{}' at {}", self.name(), message, location)
+            }
+            AOCError::TimedOut(elapsed) => write!(f, "{} after {:.2?}", self.name(), elapsed),
+            AOCError::InputFile(e)
+            | AOCError::AnswersFile(e)
+            | AOCError::ReportFile(e)
+            | AOCError::ConfigFile(e)
+            | AOCError::BaselineFile(e)
+            | AOCError::Scaffold(e)
+            | AOCError::Fetch(e)
+            | AOCError::Submit(e)
+            | AOCError::Generate(e)
+            | AOCError::Visualize(e)
+            | AOCError::Ocr(e) => {
+                write!(f, "{}: {}", self.name(), e)
+            }
+            _ => write!(f, "{}", self.name()),
+        }
+    }
+}
+
+impl From<ParseError> for AOCError {
+    fn from(e: ParseError) -> Self {
+        AOCError::InvalidInput(e)
+    }
+}
+
+/// Where and why a day's parser rejected its input. Lines and columns are
+/// 1-based, the day is filled in by the runner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: Option<usize>,
+    pub line: usize,
+    pub column: usize,
+    /// The offending text.
+    pub text: String,
+    pub message: String,
+    /// The full line containing the offending text, for the snippet.
+    pub source_line: String,
+}
+
+impl ParseError {
+    /// An error for `text`, a slice of `input`, which locates it by its
+    /// position in `input`. Falls back to searching for it if `text` was not
+    /// borrowed from `input`.
+    pub fn at(input: &str, text: &str, message: impl Into<String>) -> Self {
+        let start = input.as_ptr() as usize;
+        let ptr = text.as_ptr() as usize;
+        let offset = if ptr >= start && ptr + text.len() <= start + input.len() {
+            Some(ptr - start)
+        } else {
+            input.find(text)
+        };
+
+        let (line, column, source_line) = match offset {
+            Some(offset) => {
+                let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+                let line_end = input[offset..]
+                    .find('\n')
+                    .map_or(input.len(), |i| offset + i);
+                (
+                    input[..offset].matches('\n').count() + 1,
+                    input[line_start..offset].chars().count() + 1,
+                    input[line_start..line_end]
+                        .trim_end_matches('\r')
+                        .to_string(),
+                )
+            }
+            None => (0, 0, String::new()),
+        };
+
+        Self {
+            day: None,
+            line,
+            column,
+            text: text.to_string(),
+            message: message.into(),
+            source_line,
+        }
+    }
+
+    /// Parse `text`, a slice of `input`, as a number, or an error at it.
+    pub fn number<T: FromStr>(input: &str, text: &str) -> Result<T, Self> {
+        text.parse()
+            .map_err(|_| Self::at(input, text, "expected a number"))
+    }
+
+    /// A source snippet with the offending text underlined.
+    pub fn snippet(&self) -> String {
+        if self.line == 0 {
+            return format!("  | {:?}", self.text);
+        }
+        let number = self.line.to_string();
+        let pad = " ".repeat(number.len());
+        format!(
+            "{} |\n{} | {}\n{} | {}{}",
+            pad,
+            number,
+            self.source_line,
+            pad,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.text.chars().count().max(1)),
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {}, ", day)?;
+        }
+        write!(
+            f,
+            "line {}, column {}: {} (found {:?})",
+            self.line, self.column, self.message, self.text
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "noop\naddx 3\naddx foo\nnoop";
+
+    #[test]
+    fn test_parse_error_at() {
+        let token = INPUT.lines().nth(2).unwrap().split(' ').nth(1).unwrap();
+        let e = ParseError::at(INPUT, token, "expected a number");
+        assert_eq!((e.line, e.column), (3, 6));
+        assert_eq!(e.text, "foo");
+        assert_eq!(e.source_line, "addx foo");
+        assert_eq!(
+            e.to_string(),
+            "line 3, column 6: expected a number (found \"foo\")"
+        );
+
+        let e = ParseError::at(INPUT, "addx 3", "not borrowed");
+        assert_eq!((e.line, e.column), (2, 1));
+
+        let e = ParseError::at(INPUT, "jmp", "missing");
+        assert_eq!((e.line, e.column), (0, 0));
+    }
+
+    #[test]
+    fn test_parse_error_number() {
+        let token = &INPUT[17..20];
+        assert_eq!(ParseError::number::<i32>(INPUT, &INPUT[10..11]), Ok(3));
+        let e = ParseError::number::<i32>(INPUT, token).unwrap_err();
+        assert_eq!((e.line, e.column), (3, 6));
+        assert_eq!(e.message, "expected a number");
+    }
+
+    #[test]
+    fn test_parse_error_crlf() {
+        let input = "noop\r\nbad\r\n";
+        let e = ParseError::at(input, &input[6..9], "unknown instruction");
+        assert_eq!((e.line, e.column), (2, 1));
+        assert_eq!(e.source_line, "bad");
+    }
+
+    #[test]
+    fn test_snippet() {
+        let token = &INPUT[17..20];
+        let e = ParseError::at(INPUT, token, "expected a number");
+        assert_eq!(e.snippet(), "  |\n3 | addx foo\n  |      ^^^");
+    }
+}
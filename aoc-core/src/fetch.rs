@@ -0,0 +1,183 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::{AOCError, InputSource};
+
+/// Checked for a session token before the configured `session_file`.
+const SESSION_VAR: &str = "AOC_SESSION";
+
+/// The session cookie to download inputs with: `$AOC_SESSION` if set,
+/// otherwise the contents of `session_file` from `aoc.toml`. A relative
+/// `session_file` is relative to the year crate's `root`, a leading `~/` to
+/// the home directory.
+pub fn session(config: &Config, root: &Path) -> Result<String, AOCError> {
+    if let Some(session) = env::var(SESSION_VAR).ok().filter(|s| !s.trim().is_empty()) {
+        return Ok(session.trim().to_string());
+    }
+
+    let path = config.session_file().ok_or_else(|| {
+        AOCError::Fetch(format!(
+            "no session token, set {} or session_file in aoc.toml",
+            SESSION_VAR
+        ))
+    })?;
+    let path = match (path.strip_prefix("~"), env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => root.join(path),
+    };
+
+    let session = fs::read_to_string(&path)
+        .map_err(|e| AOCError::Fetch(format!("{}: {}", path.display(), e)))?;
+    match session.trim() {
+        "" => Err(AOCError::Fetch(format!("{} is empty", path.display()))),
+        session => Ok(session.to_string()),
+    }
+}
+
+/// The HTTP client for talking to the puzzle site.
+pub(crate) fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .user_agent(concat!("aoc-core/", env!("CARGO_PKG_VERSION")))
+        .build()
+}
+
+/// Download the input for `day` of `year` from `base_url` into
+/// `inputs/NN.in`. Returns `None` without making a request if `inputs`
+/// already has the day, compressed or not, so an input is only ever
+/// downloaded once. The empty placeholder left by `new` does not count.
+pub fn fetch_input(
+    base_url: &str,
+    session: &str,
+    year: usize,
+    day: usize,
+    inputs: &Path,
+) -> Result<Option<PathBuf>, AOCError> {
+    let path = inputs.join(format!("{:02}.in", day));
+    let placeholder = fs::metadata(&path).is_ok_and(|m| m.len() == 0);
+    if !placeholder && InputSource::Dir(inputs.to_path_buf()).exists(day) {
+        return Ok(None);
+    }
+
+    let url = format!("{}/{}/day/{}/input", base_url, year, day);
+    let input = match agent()
+        .get(&url)
+        .set("Cookie", &format!("session={}", session))
+        .call()
+    {
+        Ok(response) => response
+            .into_string()
+            .map_err(|e| AOCError::Fetch(format!("{}: {}", url, e)))?,
+        Err(ureq::Error::Status(status, response)) => {
+            let body = response.into_string().unwrap_or_default();
+            return Err(AOCError::Fetch(format!(
+                "{}: HTTP {}: {}",
+                url,
+                status,
+                body.trim()
+            )));
+        }
+        Err(e) => return Err(AOCError::Fetch(format!("{}: {}", url, e))),
+    };
+
+    // Write next to the final path and rename, so an interrupted download
+    // never leaves a truncated input that would be treated as cached.
+    let partial = inputs.join(format!("{:02}.in.part", day));
+    fs::create_dir_all(inputs)
+        .and_then(|_| fs::write(&partial, input))
+        .and_then(|_| fs::rename(&partial, &path))
+        .map_err(|e| AOCError::Fetch(format!("{}: {}", path.display(), e)))?;
+
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    use tiny_http::{Header, Response, Server};
+
+    use super::*;
+
+    /// Serve `/2022/day/N/input` for the session "secret", counting requests.
+    fn stub_server() -> (String, Arc<AtomicUsize>) {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", server.server_addr());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let authorized = request
+                    .headers()
+                    .iter()
+                    .any(|h| h.field.equiv("Cookie") && h.value.as_str() == "session=secret");
+                let day = request
+                    .url()
+                    .strip_prefix("/2022/day/")
+                    .and_then(|rest| rest.strip_suffix("/input"));
+                let response = match day {
+                    _ if !authorized => {
+                        Response::from_string("Please log in").with_status_code(400)
+                    }
+                    Some("25") => Response::from_string("Not unlocked yet").with_status_code(404),
+                    Some(day) => Response::from_string(format!("input for day {}\n", day)),
+                    None => Response::from_string("Not found").with_status_code(404),
+                };
+                let header = Header::from_bytes("Content-Type", "text/plain").unwrap();
+                request.respond(response.with_header(header)).unwrap();
+            }
+        });
+
+        (base_url, requests)
+    }
+
+    #[test]
+    fn test_fetch_input() {
+        let (base_url, requests) = stub_server();
+        let inputs = env::temp_dir().join(format!("aoc-fetch-{}", std::process::id()));
+
+        let path = fetch_input(&base_url, "secret", 2022, 3, &inputs).unwrap();
+        assert_eq!(path, Some(inputs.join("03.in")));
+        assert_eq!(
+            fs::read_to_string(inputs.join("03.in")).unwrap(),
+            "input for day 3\n"
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // Cached inputs, plain or compressed, are never requested again.
+        assert_eq!(
+            fetch_input(&base_url, "secret", 2022, 3, &inputs).unwrap(),
+            None
+        );
+        fs::write(inputs.join("04.in.gz"), "not really gzip").unwrap();
+        assert_eq!(
+            fetch_input(&base_url, "secret", 2022, 4, &inputs).unwrap(),
+            None
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        fs::write(inputs.join("06.in"), "").unwrap();
+        fetch_input(&base_url, "secret", 2022, 6, &inputs).unwrap();
+        assert_eq!(
+            fs::read_to_string(inputs.join("06.in")).unwrap(),
+            "input for day 6\n"
+        );
+
+        assert!(matches!(
+            fetch_input(&base_url, "wrong", 2022, 5, &inputs),
+            Err(AOCError::Fetch(e)) if e.contains("HTTP 400")
+        ));
+        assert!(matches!(
+            fetch_input(&base_url, "secret", 2022, 25, &inputs),
+            Err(AOCError::Fetch(e)) if e.contains("Not unlocked yet")
+        ));
+        assert!(!inputs.join("05.in").exists() && !inputs.join("25.in").exists());
+
+        fs::remove_dir_all(&inputs).unwrap();
+    }
+}
@@ -0,0 +1,133 @@
+use std::fs::File;
+use std::io::{self, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+
+use crate::AOCError;
+
+/// Extensions tried, in order, for a day's file in an inputs directory.
+const EXTENSIONS: [&str; 3] = ["in", "in.gz", "in.zst"];
+
+/// Where a day's puzzle input is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// `NN.in` in a directory, or its `.gz`/`.zst` compressed version.
+    Dir(PathBuf),
+    /// A single file, compressed if it ends in `.gz` or `.zst`.
+    File(PathBuf),
+    /// Standard input, selected with `--input -`.
+    Stdin,
+    /// An input compiled into the binary with the `embed-inputs` feature.
+    Embedded(&'static str),
+}
+
+impl InputSource {
+    /// Read and [`normalize`] the input for `day`.
+    pub fn read(&self, day: usize) -> Result<String, AOCError> {
+        let raw = match self {
+            InputSource::Dir(dir) => {
+                let path = day_file(dir, day).ok_or(AOCError::InputNotFound)?;
+                read_file(&path)?
+            }
+            InputSource::File(path) => read_file(path)?,
+            InputSource::Stdin => {
+                let mut raw = String::new();
+                io::stdin()
+                    .read_to_string(&mut raw)
+                    .map_err(|e| AOCError::InputFile(format!("stdin: {}", e)))?;
+                raw
+            }
+            InputSource::Embedded(input) => input.to_string(),
+        };
+        Ok(normalize(&raw))
+    }
+
+    /// Whether there is an input for `day`, without reading it.
+    pub fn exists(&self, day: usize) -> bool {
+        match self {
+            InputSource::Dir(dir) => day_file(dir, day).is_some(),
+            InputSource::File(path) => path.is_file(),
+            InputSource::Stdin | InputSource::Embedded(_) => true,
+        }
+    }
+}
+
+/// Turn CRLF line endings into LF and drop trailing whitespace, including the
+/// final newline, so every source hands solvers the same text.
+pub fn normalize(raw: &str) -> String {
+    raw.replace("\r\n", "\n").trim_end().to_string()
+}
+
+fn day_file(dir: &Path, day: usize) -> Option<PathBuf> {
+    EXTENSIONS
+        .iter()
+        .map(|ext| dir.join(format!("{:02}.{}", day, ext)))
+        .find(|path| path.is_file())
+}
+
+fn read_file(path: &Path) -> Result<String, AOCError> {
+    let read = || -> io::Result<String> {
+        let file = File::open(path)?;
+        let mut raw = String::new();
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("gz") => GzDecoder::new(file).read_to_string(&mut raw)?,
+            Some("zst") => zstd::Decoder::new(file)?.read_to_string(&mut raw)?,
+            _ => io::BufReader::new(file).read_to_string(&mut raw)?,
+        };
+        Ok(raw)
+    };
+
+    read().map_err(|e| match e.kind() {
+        ErrorKind::NotFound => AOCError::InputNotFound,
+        _ => AOCError::InputFile(format!("{}: {}", path.display(), e)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("1\r\n2\r\n\r\n3\r\n"), "1\n2\n\n3");
+        assert_eq!(normalize("    [D]\n[N] [C]\n \n"), "    [D]\n[N] [C]");
+        assert_eq!(normalize(">><<\n"), ">><<");
+    }
+
+    #[test]
+    fn test_dir_and_compressed_files() {
+        let dir = std::env::temp_dir().join(format!("aoc-inputs-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        fs::write(dir.join("01.in"), "plain\r\n").unwrap();
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(b"gzipped\n").unwrap();
+        fs::write(dir.join("02.in.gz"), gz.finish().unwrap()).unwrap();
+        fs::write(
+            dir.join("03.in.zst"),
+            zstd::encode_all(&b"zstd\n"[..], 0).unwrap(),
+        )
+        .unwrap();
+        fs::write(dir.join("04.in.gz"), "not gzip").unwrap();
+
+        let source = InputSource::Dir(dir.clone());
+        assert_eq!(source.read(1).unwrap(), "plain");
+        assert_eq!(source.read(2).unwrap(), "gzipped");
+        assert_eq!(source.read(3).unwrap(), "zstd");
+        assert!(matches!(source.read(4), Err(AOCError::InputFile(_))));
+        assert!(matches!(source.read(5), Err(AOCError::InputNotFound)));
+        assert!(source.exists(3) && !source.exists(5));
+
+        let file = InputSource::File(dir.join("03.in.zst"));
+        assert_eq!(file.read(25).unwrap(), "zstd");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,74 @@
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+use crate::AOCError;
+
+thread_local! {
+    /// Set while running inside `catch`, so the hook stays quiet there only.
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    static LAST_PANIC: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Run `f`, turning a panic into `AOCError::Panicked` instead of unwinding
+/// through the runner. Panics outside of `catch` are reported as usual.
+pub fn catch<T>(f: impl FnOnce() -> T) -> Result<T, AOCError> {
+    INSTALL_HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING.with(|c| c.get()) {
+                return default_hook(info);
+            }
+            let message = match info.payload().downcast_ref::<&str>() {
+                Some(s) => s.to_string(),
+                None => match info.payload().downcast_ref::<String>() {
+                    Some(s) => s.clone(),
+                    None => "Box<dyn Any>".to_string(),
+                },
+            };
+            let location = info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+                .unwrap_or_else(|| "unknown".to_string());
+            LAST_PANIC.with(|p| *p.borrow_mut() = Some((message, location)));
+        }));
+    });
+
+    let was_catching = CATCHING.with(|c| c.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|c| c.set(was_catching));
+
+    result.map_err(|_| {
+        let (message, location) = LAST_PANIC
+            .with(|p| p.borrow_mut().take())
+            .unwrap_or_else(|| ("unknown panic".to_string(), "unknown".to_string()));
+        AOCError::Panicked(message, location)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catch() {
+        assert_eq!(catch(|| 42).unwrap(), 42);
+
+        match catch(|| -> usize { todo!() }) {
+            Err(AOCError::Panicked(message, location)) => {
+                assert_eq!(message, "not yet implemented");
+                assert!(location.contains("isolate.rs"), "{}", location);
+            }
+            _ => panic!("expected a panic"),
+        }
+
+        let v: Vec<u32> = vec![];
+        let index = 3;
+        match catch(|| v[index]) {
+            Err(AOCError::Panicked(message, _)) => assert!(message.contains("index out of bounds")),
+            _ => panic!("expected a panic"),
+        }
+    }
+}
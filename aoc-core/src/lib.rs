@@ -0,0 +1,29 @@
+mod answers;
+mod baseline;
+mod bench;
+pub mod build;
+pub mod cli;
+mod config;
+mod context;
+mod error;
+mod fetch;
+mod input;
+mod isolate;
+mod memory;
+pub mod ocr;
+mod problem;
+mod report;
+mod rng;
+mod runner;
+mod scaffold;
+mod submit;
+mod visualize;
+
+pub use context::{Context, Level};
+pub use error::{AOCError, ParseError};
+pub use input::InputSource;
+pub use problem::{check_examples, parse_error, Example, Problem, Solver};
+pub use report::Format;
+pub use rng::Rng;
+pub use runner::{main, Year};
+pub use visualize::{Color, Frame};
@@ -0,0 +1,161 @@
+#[cfg(feature = "count-allocs")]
+use std::cell::Cell;
+
+/// What one part allocated. Only allocations made on the thread that solved
+/// the part are counted, so days running in parallel do not mix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Memory {
+    /// Allocations, reallocations included.
+    pub allocations: u64,
+    pub bytes: u64,
+    /// The most bytes live at once, above what was live when the part started.
+    pub peak: u64,
+}
+
+/// The counting allocator, only compiled with the `count-allocs` feature so
+/// the default build keeps the system allocator untouched.
+#[cfg(feature = "count-allocs")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    #[derive(Clone, Copy)]
+    pub(super) struct Counter {
+        pub(super) allocations: u64,
+        pub(super) bytes: u64,
+        pub(super) live: i64,
+        pub(super) peak: i64,
+    }
+
+    thread_local! {
+        // A const initializer and no destructor, so using it from the allocator
+        // neither allocates nor fails during thread teardown.
+        pub(super) static COUNTER: Cell<Counter> = const {
+            Cell::new(Counter {
+                allocations: 0,
+                bytes: 0,
+                live: 0,
+                peak: 0,
+            })
+        };
+    }
+
+    pub(super) fn update(f: impl FnOnce(&mut Counter)) {
+        let _ = COUNTER.try_with(|cell| {
+            let mut counter = cell.get();
+            f(&mut counter);
+            cell.set(counter);
+        });
+    }
+
+    pub(super) fn allocated(size: usize, freed: usize) {
+        update(|c| {
+            c.allocations += 1;
+            c.bytes += size as u64;
+            c.live += size as i64 - freed as i64;
+            c.peak = c.peak.max(c.live);
+        });
+    }
+
+    /// Forwards to the system allocator, counting per thread.
+    pub struct CountingAlloc;
+
+    #[global_allocator]
+    static GLOBAL: CountingAlloc = CountingAlloc;
+
+    unsafe impl GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                allocated(layout.size(), 0);
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                allocated(layout.size(), 0);
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            update(|c| c.live -= layout.size() as i64);
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            if !new_ptr.is_null() {
+                allocated(new_size, layout.size());
+            }
+            new_ptr
+        }
+    }
+}
+
+/// Run `f` and count what it allocates, `None` unless the counting allocator
+/// is installed.
+#[cfg(feature = "count-allocs")]
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<Memory>) {
+    use counting::COUNTER;
+
+    let mut start = COUNTER.with(Cell::get);
+    start.peak = start.live;
+    COUNTER.with(|cell| cell.set(start));
+
+    let result = f();
+    let end = COUNTER.with(Cell::get);
+    let memory = Memory {
+        allocations: end.allocations - start.allocations,
+        bytes: end.bytes - start.bytes,
+        peak: (end.peak - start.live).max(0) as u64,
+    };
+    (result, Some(memory))
+}
+
+#[cfg(not(feature = "count-allocs"))]
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<Memory>) {
+    (f(), None)
+}
+
+#[cfg(all(test, feature = "count-allocs"))]
+mod tests {
+    use super::counting::{allocated, update, COUNTER};
+    use super::*;
+
+    #[test]
+    fn test_counter() {
+        let before = COUNTER.with(Cell::get);
+        let mut start = before;
+        start.peak = start.live;
+        COUNTER.with(|cell| cell.set(start));
+
+        allocated(100, 0);
+        allocated(300, 100);
+        update(|c| c.live -= 300);
+        allocated(50, 0);
+
+        let end = COUNTER.with(Cell::get);
+        assert_eq!(end.allocations - start.allocations, 3);
+        assert_eq!(end.bytes - start.bytes, 450);
+        assert_eq!(end.peak - start.live, 300);
+        assert_eq!(end.live - start.live, 50);
+        COUNTER.with(|cell| cell.set(before));
+    }
+
+    #[test]
+    fn test_measure() {
+        let (v, memory) = measure(|| {
+            let scratch = vec![0u8; 4096];
+            drop(scratch);
+            Vec::<u64>::with_capacity(16)
+        });
+        let memory = memory.unwrap();
+        assert_eq!(v.capacity(), 16);
+        assert_eq!(memory.allocations, 2);
+        assert_eq!(memory.bytes, 4096 + 128);
+        assert_eq!(memory.peak, 4096);
+    }
+}
@@ -0,0 +1,213 @@
+use crate::AOCError;
+
+/// The 4 by 6 letters most puzzles draw, one column apart. Only the letters
+/// seen in puzzles so far are known.
+const SMALL: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+/// The 6 by 10 letters of the larger puzzles, two columns apart.
+#[rustfmt::skip]
+const LARGE: &[(char, &str)] = &[
+    ('A', "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#"),
+    ('B', "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####."),
+    ('C', ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####."),
+    ('E', "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######"),
+    ('F', "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#....."),
+    ('G', ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#"),
+    ('H', "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#"),
+    ('J', "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###.."),
+    ('K', "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#"),
+    ('L', "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######"),
+    ('N', "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#"),
+    ('P', "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#....."),
+    ('R', "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#"),
+    ('X', "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#"),
+    ('Z', "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######"),
+];
+
+/// Lit pixels are `#` or `█`, anything else is dark.
+fn is_lit(c: char) -> bool {
+    matches!(c, '#' | '█')
+}
+
+/// The rows of lit pixels, without blank rows around them.
+fn pixels(art: &str) -> Vec<Vec<bool>> {
+    let rows = art
+        .lines()
+        .map(|line| line.chars().map(is_lit).collect::<Vec<bool>>())
+        .collect::<Vec<_>>();
+    let lit = |row: &&Vec<bool>| row.contains(&true);
+    let first = rows.iter().position(|row| lit(&row)).unwrap_or(rows.len());
+    let last = rows
+        .iter()
+        .rposition(|row| lit(&row))
+        .map_or(first, |i| i + 1);
+    rows[first..last].to_vec()
+}
+
+/// Cut `rows` into glyphs at the columns with nothing lit, as `#` and `.`
+/// text, with the column each glyph starts at.
+fn glyphs(rows: &[Vec<bool>]) -> Vec<(usize, String)> {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let lit = |x: usize| rows.iter().any(|row| row.get(x) == Some(&true));
+
+    let mut glyphs = Vec::new();
+    let mut x = 0;
+    while x < width {
+        if !lit(x) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while x < width && lit(x) {
+            x += 1;
+        }
+        let glyph = rows
+            .iter()
+            .map(|row| {
+                (start..x)
+                    .map(|x| match row.get(x) {
+                        Some(true) => '#',
+                        _ => '.',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        glyphs.push((start, glyph));
+    }
+    glyphs
+}
+
+/// Read the capital letters drawn by `art` in either puzzle font, such as
+/// the screen of 2022 day 10. Errors name the first glyph that matches no
+/// known letter.
+pub fn read_letters(art: &str) -> Result<String, AOCError> {
+    let rows = pixels(art);
+    let font = match rows.len() {
+        6 => SMALL,
+        10 => LARGE,
+        0 => return Err(AOCError::Ocr("nothing is drawn".to_string())),
+        n => {
+            return Err(AOCError::Ocr(format!(
+                "letters are 6 or 10 rows high, not {}",
+                n
+            )))
+        }
+    };
+
+    glyphs(&rows)
+        .into_iter()
+        .map(|(column, glyph)| {
+            font.iter()
+                .find(|(_, letter)| glyphs(&pixels(letter))[0].1 == glyph)
+                .map(|(c, _)| *c)
+                .ok_or_else(|| {
+                    AOCError::Ocr(format!(
+                        "unknown glyph at column {}:\n{}",
+                        column + 1,
+                        glyph
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// Replace an answer drawn in block letters, which is any answer spanning
+/// several lines, by the letters. Returns the drawing as well, so it can be
+/// shown next to them.
+pub(crate) fn read_answer(
+    answer: Result<String, AOCError>,
+) -> (Result<String, AOCError>, Option<String>) {
+    match answer {
+        Ok(art) if art.trim().contains('\n') => (read_letters(&art), Some(art)),
+        answer => (answer, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draw `text` the way the puzzles do: glyphs `gap` columns apart, lit
+    /// with `on` and dark with `off`.
+    fn draw(text: &str, font: &[(char, &str)], gap: usize, on: char, off: char) -> String {
+        let letters = text
+            .chars()
+            .map(|c| font.iter().find(|(l, _)| *l == c).unwrap().1)
+            .collect::<Vec<&str>>();
+        let height = letters[0].lines().count();
+        (0..height)
+            .map(|y| {
+                letters
+                    .iter()
+                    .map(|letter| {
+                        let row = letter.lines().nth(y).unwrap();
+                        format!("{}{}", row, ".".repeat(gap))
+                    })
+                    .collect::<String>()
+                    .replace('#', &on.to_string())
+                    .replace('.', &off.to_string())
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_read_small_letters() {
+        let all = SMALL.iter().map(|(c, _)| *c).collect::<String>();
+        assert_eq!(read_letters(&draw(&all, SMALL, 1, '#', '.')).unwrap(), all);
+        let art = format!("\n{}\n", draw("EHZFZHCZ", SMALL, 1, '#', '.'));
+        assert_eq!(read_letters(&art).unwrap(), "EHZFZHCZ");
+        assert_eq!(
+            read_letters(&draw("RGZEHURK", SMALL, 1, '█', ' ')).unwrap(),
+            "RGZEHURK"
+        );
+    }
+
+    #[test]
+    fn test_read_large_letters() {
+        let all = LARGE.iter().map(|(c, _)| *c).collect::<String>();
+        assert_eq!(read_letters(&draw(&all, LARGE, 2, '#', '.')).unwrap(), all);
+    }
+
+    #[test]
+    fn test_unknown_glyph() {
+        let mut art = draw("ABC", SMALL, 1, '#', '.');
+        // Turn the B into an unknown shape.
+        art = art.replacen("###.", "####", 1);
+        match read_letters(&art) {
+            Err(AOCError::Ocr(e)) => assert!(e.starts_with("unknown glyph at column 6:\n####")),
+            other => panic!("expected an Ocr error, got {:?}", other),
+        }
+        assert!(matches!(read_letters("#..#\n####"), Err(AOCError::Ocr(_))));
+        assert!(matches!(read_letters("...\n..."), Err(AOCError::Ocr(_))));
+    }
+
+    #[test]
+    fn test_read_answer() {
+        assert_eq!(read_answer(Ok("42".to_string())).1, None);
+        let art = format!("\n{}", draw("HI", SMALL, 1, '#', '.'));
+        let (answer, drawn) = read_answer(Ok(art.clone()));
+        assert_eq!(answer.unwrap(), "HI");
+        assert_eq!(drawn, Some(art));
+    }
+}
@@ -0,0 +1,224 @@
+use std::any::Any;
+
+use crate::cli::Part;
+use crate::input::normalize;
+use crate::{AOCError, ParseError, Rng};
+
+/// A worked example from the puzzle text with the answers it should give,
+/// `None` for a part the example does not cover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Example {
+    pub input: &'static str,
+    pub part1: Option<&'static str>,
+    pub part2: Option<&'static str>,
+}
+
+impl Example {
+    pub fn answer(&self, part: Part) -> Option<&'static str> {
+        match part {
+            Part::A => self.part1,
+            Part::B => self.part2,
+        }
+    }
+}
+
+/// A day's puzzle. The input is parsed once and shared by both parts, so
+/// parsing and solving can be timed separately.
+pub trait Problem {
+    type Input;
+
+    /// Checked by `--examples` and by the tests from [`example_tests!`].
+    const EXAMPLES: &'static [Example] = &[];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError>;
+    fn part1(&self, _input: &Self::Input) -> Option<String>;
+    fn part2(&self, _input: &Self::Input) -> Option<String>;
+
+    /// A random input in the real format for `generate`, or `None` for days
+    /// without a generator. `size` scales it, in a unit each day documents;
+    /// without it the input is about as large as a real one.
+    fn generate(&self, _rng: &mut Rng, _size: Option<usize>) -> Option<String> {
+        None
+    }
+}
+
+/// Object safe view of a [`Problem`] with the input type erased, which is what
+/// the runner holds for each registered day.
+pub trait Solver {
+    fn prepare(&self, input: &str) -> Result<Box<dyn Any>, AOCError>;
+    fn solve(&self, input: &dyn Any, part: Part) -> Option<String>;
+    fn examples(&self) -> &'static [Example];
+    fn generate(&self, rng: &mut Rng, size: Option<usize>) -> Option<String>;
+}
+
+impl<P> Solver for P
+where
+    P: Problem,
+    P::Input: 'static,
+{
+    fn prepare(&self, input: &str) -> Result<Box<dyn Any>, AOCError> {
+        Ok(Box::new(self.parse(input)?))
+    }
+
+    fn solve(&self, input: &dyn Any, part: Part) -> Option<String> {
+        let input = input
+            .downcast_ref::<P::Input>()
+            .expect("input was prepared by a different solver");
+        match part {
+            Part::A => self.part1(input),
+            Part::B => self.part2(input),
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        P::EXAMPLES
+    }
+
+    fn generate(&self, rng: &mut Rng, size: Option<usize>) -> Option<String> {
+        Problem::generate(self, rng, size)
+    }
+}
+
+/// Solve every example that has an answer for `part`, which is `"part1"` or
+/// `"part2"` as named by [`example_tests!`], and describe the mismatches.
+pub fn check_examples(solver: &dyn Solver, part: &str) -> Result<(), String> {
+    let part = Part::from_str(part.trim_start_matches("part"))
+        .ok_or_else(|| format!("invalid part '{}'", part))?;
+    let examples = solver
+        .examples()
+        .iter()
+        .enumerate()
+        .filter_map(|(i, example)| Some((i + 1, example.input, example.answer(part)?)))
+        .collect::<Vec<_>>();
+    if examples.is_empty() {
+        return Err(format!("no examples with an answer for part {}", part));
+    }
+
+    let mut failures = Vec::new();
+    for (i, input, expected) in examples {
+        let answer = solver
+            .prepare(&normalize(input))
+            .map(|input| solver.solve(input.as_ref(), part));
+        match answer {
+            Ok(Some(answer)) if answer == expected => (),
+            Ok(Some(answer)) => failures.push(format!(
+                "example {}: expected {:?}, got {:?}",
+                i, expected, answer
+            )),
+            Ok(None) => failures.push(format!("example {}: {}", i, AOCError::NotImplemented)),
+            Err(e) => failures.push(format!("example {}: {}", i, e)),
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(failures.join("\n"))
+    }
+}
+
+/// The error `solver` reports for `input`, for tests of where parse errors
+/// point. Panics when the input parses or fails in another way.
+pub fn parse_error<P: Problem>(solver: &P, input: &str) -> ParseError {
+    match solver.parse(input) {
+        Err(AOCError::InvalidInput(e)) => e,
+        Err(e) => panic!("expected a parse error, got {:?}", e),
+        Ok(_) => panic!("expected a parse error, {:?} parsed", input),
+    }
+}
+
+/// Generate a test per part that checks the solution's [`Problem::EXAMPLES`].
+/// Parts can be listed to leave one out or to add attributes:
+///
+/// ```ignore
+/// aoc_core::example_tests!(Solution {});
+/// aoc_core::example_tests!(Solution {}, part1, #[ignore] part2);
+/// ```
+#[macro_export]
+macro_rules! example_tests {
+    ($solution:expr) => {
+        $crate::example_tests!($solution, part1, part2);
+    };
+    ($solution:expr, $($(#[$attr:meta])* $part:ident),+) => {
+        $(
+            #[test]
+            $(#[$attr])*
+            fn $part() {
+                if let Err(e) = $crate::check_examples(&$solution, stringify!($part)) {
+                    panic!("{}", e);
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum {}
+
+    impl Problem for Sum {
+        type Input = Vec<u32>;
+
+        const EXAMPLES: &'static [Example] = &[
+            Example {
+                input: "1\r\n2\r\n3\r\n",
+                part1: Some("6"),
+                part2: None,
+            },
+            Example {
+                input: "40\n2",
+                part1: Some("42"),
+                part2: None,
+            },
+        ];
+
+        fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+            input
+                .lines()
+                .map(|l| {
+                    l.parse()
+                        .map_err(|_| ParseError::at(input, l, "expected a number").into())
+                })
+                .collect()
+        }
+
+        fn part1(&self, input: &Self::Input) -> Option<String> {
+            Some(input.iter().sum::<u32>().to_string())
+        }
+
+        fn part2(&self, _input: &Self::Input) -> Option<String> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_solver() {
+        let solver: Box<dyn Solver> = Box::new(Sum {});
+        let input = solver.prepare("1\n2\n3").unwrap();
+        assert_eq!(solver.solve(input.as_ref(), Part::A), Some("6".to_string()));
+        assert_eq!(solver.solve(input.as_ref(), Part::B), None);
+        assert!(solver.prepare("1\nx").is_err());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let e = parse_error(&Sum {}, "1\nx");
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "x"));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a parse error")]
+    fn test_parse_error_parsed() {
+        parse_error(&Sum {}, "1\n2");
+    }
+
+    #[test]
+    fn test_check_examples() {
+        assert_eq!(check_examples(&Sum {}, "part1"), Ok(()));
+        assert!(check_examples(&Sum {}, "part2").is_err());
+    }
+
+    example_tests!(Sum {}, part1);
+}
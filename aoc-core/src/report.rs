@@ -0,0 +1,460 @@
+use std::fmt::Write;
+use std::process;
+use std::time::Duration;
+
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::answers::Verdict;
+use crate::bench::Stats;
+use crate::cli::Part;
+use crate::context::Diagnostics;
+use crate::memory::Memory;
+use crate::AOCError;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+    Junit,
+}
+
+impl Format {
+    pub(crate) fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            "junit" => Some(Format::Junit),
+            _ => None,
+        }
+    }
+}
+
+/// The outcome of running one part of one day.
+pub struct Record {
+    pub day: usize,
+    pub part: Part,
+    pub answer: Result<String, AOCError>,
+    /// The block letters a multi-line answer was read from.
+    pub art: Option<String>,
+    pub parse_time: Option<Duration>,
+    pub solve_time: Duration,
+    pub input_hash: Option<String>,
+    pub verdict: Option<Verdict>,
+    /// Only set in `--bench` mode.
+    pub bench: Option<Stats>,
+    /// Only set with the `count-allocs` feature.
+    pub memory: Option<Memory>,
+    pub diagnostics: Diagnostics,
+}
+
+impl Record {
+    pub fn status(&self) -> String {
+        match &self.answer {
+            Ok(_) => "Ok".to_string(),
+            Err(e) => e.name().to_string(),
+        }
+    }
+
+    fn answer_str(&self) -> &str {
+        match &self.answer {
+            Ok(answer) => answer,
+            Err(_) => "",
+        }
+    }
+
+    fn verdict_str(&self) -> Option<&'static str> {
+        self.verdict.as_ref().map(|v| match v {
+            Verdict::Correct => "correct",
+            Verdict::Wrong { .. } => "wrong",
+            Verdict::Unknown => "unknown",
+        })
+    }
+}
+
+/// Metadata shared by every record of a run.
+pub struct RunInfo {
+    pub year: usize,
+    pub revision: String,
+    pub total_time: Duration,
+}
+
+pub fn input_hash(input: &str) -> String {
+    Sha256::digest(input.as_bytes())
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, b| {
+            write!(hex, "{:02x}", b).unwrap();
+            hex
+        })
+}
+
+/// The git revision of the working tree, `unknown` outside of a checkout.
+pub fn git_revision() -> String {
+    process::Command::new("git")
+        .args(["describe", "--always", "--dirty"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .map(|rev| rev.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+pub fn render(format: Format, info: &RunInfo, records: &[Record]) -> String {
+    match format {
+        Format::Text => String::new(),
+        Format::Json => render_json(info, records),
+        Format::Csv => render_csv(info, records),
+        Format::Junit => render_junit(info, records),
+    }
+}
+
+fn render_json(info: &RunInfo, records: &[Record]) -> String {
+    let records = records
+        .iter()
+        .map(|r| {
+            json!({
+                "year": info.year,
+                "day": r.day,
+                "part": r.part.to_string(),
+                "status": r.status(),
+                "answer": r.answer.as_ref().ok(),
+                "error": r.answer.as_ref().err().map(|e| e.to_string()),
+                "parse_us": r.parse_time.map(|t| t.as_micros() as u64),
+                "solve_us": r.solve_time.as_micros() as u64,
+                "input_sha256": r.input_hash,
+                "verdict": r.verdict_str(),
+                "revision": info.revision,
+                "bench": r.bench.as_ref().map(|b| json!({
+                    "samples": b.samples,
+                    "min_ns": b.min.as_nanos() as u64,
+                    "median_ns": b.median.as_nanos() as u64,
+                    "mean_ns": b.mean.as_nanos() as u64,
+                    "stddev_ns": b.stddev.as_nanos() as u64,
+                    "outliers": b.outliers,
+                })),
+                "memory": r.memory.map(|m| json!({
+                    "allocations": m.allocations,
+                    "allocated_bytes": m.bytes,
+                    "peak_bytes": m.peak,
+                })),
+                "logs": r.diagnostics.logs.iter().map(|log| json!({
+                    "level": log.level.to_string(),
+                    "message": log.message,
+                })).collect::<Vec<_>>(),
+                "counters": r.diagnostics.counters,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::to_string_pretty(&records).unwrap()
+}
+
+fn render_csv(info: &RunInfo, records: &[Record]) -> String {
+    let mut out = String::from(
+        "year,day,part,status,answer,parse_us,solve_us,input_sha256,verdict,revision,\
+         allocations,allocated_bytes,peak_bytes\n",
+    );
+    for r in records {
+        let memory = |field: fn(&Memory) -> u64| {
+            r.memory
+                .as_ref()
+                .map(|m| field(m).to_string())
+                .unwrap_or_default()
+        };
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            info.year,
+            r.day,
+            r.part,
+            r.status(),
+            csv_escape(r.answer_str()),
+            r.parse_time
+                .map(|t| t.as_micros().to_string())
+                .unwrap_or_default(),
+            r.solve_time.as_micros(),
+            r.input_hash.as_deref().unwrap_or(""),
+            r.verdict_str().unwrap_or(""),
+            csv_escape(&info.revision),
+            memory(|m| m.allocations),
+            memory(|m| m.bytes),
+            memory(|m| m.peak),
+        )
+        .unwrap();
+    }
+    out
+}
+
+/// Parts without a solution to run, which JUnit reports as skipped rather
+/// than as errors.
+fn is_skipped(record: &Record) -> bool {
+    matches!(
+        record.answer,
+        Err(AOCError::NotImplemented | AOCError::SolutionNotFound | AOCError::Skipped)
+    )
+}
+
+fn render_junit(info: &RunInfo, records: &[Record]) -> String {
+    // Examples that fail to run are also marked wrong, but they count as
+    // errors or skips like their testcases.
+    let failures = records
+        .iter()
+        .filter(|r| r.answer.is_ok() && matches!(r.verdict, Some(Verdict::Wrong { .. })))
+        .count();
+    let skipped = records.iter().filter(|r| is_skipped(r)).count();
+    let errors = records.iter().filter(|r| r.answer.is_err()).count() - skipped;
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(
+        out,
+        "<testsuite name=\"aoc{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" \
+         time=\"{:.6}\">",
+        info.year,
+        records.len(),
+        failures,
+        errors,
+        skipped,
+        info.total_time.as_secs_f64()
+    )
+    .unwrap();
+    writeln!(out, "  <properties>").unwrap();
+    writeln!(
+        out,
+        "    <property name=\"revision\" value=\"{}\"/>",
+        xml_escape(&info.revision)
+    )
+    .unwrap();
+    writeln!(out, "  </properties>").unwrap();
+
+    for r in records {
+        let time = r.parse_time.unwrap_or_default() + r.solve_time;
+        write!(
+            out,
+            "  <testcase classname=\"aoc{}.day{:02}\" name=\"part_{}\" time=\"{:.6}\"",
+            info.year,
+            r.day,
+            r.part,
+            time.as_secs_f64()
+        )
+        .unwrap();
+
+        match (&r.answer, &r.verdict) {
+            _ if is_skipped(r) => {
+                writeln!(out, ">\n    <skipped message=\"{}\"/>", r.status()).unwrap()
+            }
+            (Err(e), _) => writeln!(
+                out,
+                ">\n    <error type=\"{}\" message=\"{}\"/>",
+                e.name(),
+                xml_escape(&e.to_string())
+            )
+            .unwrap(),
+            (Ok(answer), Some(Verdict::Wrong { expected })) => writeln!(
+                out,
+                ">\n    <failure message=\"expected {}, got {}\"/>",
+                xml_escape(expected),
+                xml_escape(answer)
+            )
+            .unwrap(),
+            (Ok(answer), _) => writeln!(
+                out,
+                ">\n    <system-out>{}</system-out>",
+                xml_escape(answer)
+            )
+            .unwrap(),
+        }
+        if !r.diagnostics.logs.is_empty() {
+            let logs = r
+                .diagnostics
+                .logs
+                .iter()
+                .map(|log| format!("{}: {}", log.level, log.message))
+                .collect::<Vec<String>>()
+                .join("\n");
+            writeln!(out, "    <system-err>{}</system-err>", xml_escape(&logs)).unwrap();
+        }
+        writeln!(out, "  </testcase>").unwrap();
+    }
+
+    out.push_str("</testsuite>\n");
+    out
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{Level, Log};
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                day: 7,
+                part: Part::A,
+                answer: Ok("95437".to_string()),
+                art: None,
+                parse_time: Some(Duration::from_micros(12)),
+                solve_time: Duration::from_micros(123),
+                input_hash: Some(input_hash("abc")),
+                verdict: Some(Verdict::Wrong {
+                    expected: "1".to_string(),
+                }),
+                bench: Some(Stats::from_samples(vec![Duration::from_micros(123)])),
+                memory: Some(Memory {
+                    allocations: 3,
+                    bytes: 2048,
+                    peak: 1024,
+                }),
+                diagnostics: Diagnostics {
+                    logs: vec![Log {
+                        level: Level::Info,
+                        message: "a < b".to_string(),
+                    }],
+                    counters: [("nodes", 42)].into_iter().collect(),
+                    frames: Vec::new(),
+                },
+            },
+            Record {
+                day: 10,
+                part: Part::B,
+                answer: Ok("\n#..#\n,\"".to_string()),
+                art: None,
+                parse_time: None,
+                solve_time: Duration::from_micros(5),
+                input_hash: None,
+                verdict: None,
+                bench: None,
+                memory: None,
+                diagnostics: Diagnostics::default(),
+            },
+            Record {
+                day: 11,
+                part: Part::A,
+                answer: Err(AOCError::InputNotFound),
+                art: None,
+                parse_time: None,
+                solve_time: Duration::ZERO,
+                input_hash: None,
+                verdict: None,
+                bench: None,
+                memory: None,
+                diagnostics: Diagnostics::default(),
+            },
+            Record {
+                day: 11,
+                part: Part::B,
+                answer: Err(AOCError::NotImplemented),
+                art: None,
+                parse_time: None,
+                solve_time: Duration::ZERO,
+                input_hash: None,
+                verdict: None,
+                bench: None,
+                memory: None,
+                diagnostics: Diagnostics::default(),
+            },
+        ]
+    }
+
+    fn info() -> RunInfo {
+        RunInfo {
+            year: 2022,
+            revision: "abc1234".to_string(),
+            total_time: Duration::from_millis(1),
+        }
+    }
+
+    #[test]
+    fn test_input_hash() {
+        assert_eq!(
+            input_hash("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_render_json() {
+        let out = render(Format::Json, &info(), &records());
+        let parsed: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(parsed[0]["day"], 7);
+        assert_eq!(parsed[0]["part"], "a");
+        assert_eq!(parsed[0]["status"], "Ok");
+        assert_eq!(parsed[0]["parse_us"], 12);
+        assert_eq!(parsed[0]["verdict"], "wrong");
+        assert_eq!(parsed[0]["revision"], "abc1234");
+        assert_eq!(parsed[0]["bench"]["median_ns"], 123000);
+        assert!(parsed[1]["bench"].is_null());
+        assert_eq!(parsed[0]["memory"]["peak_bytes"], 1024);
+        assert!(parsed[1]["memory"].is_null());
+        assert_eq!(parsed[0]["logs"][0]["level"], "info");
+        assert_eq!(parsed[0]["counters"]["nodes"], 42);
+        assert_eq!(parsed[1]["logs"].as_array().unwrap().len(), 0);
+        assert_eq!(parsed[1]["answer"], "\n#..#\n,\"");
+        assert_eq!(parsed[2]["status"], "InputNotFound");
+        assert!(parsed[2]["answer"].is_null());
+        assert_eq!(parsed[2]["error"], "InputNotFound");
+        assert!(parsed[0]["error"].is_null());
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let out = render(Format::Csv, &info(), &records());
+        let lines = out.lines().collect::<Vec<&str>>();
+        assert_eq!(
+            lines[0],
+            "year,day,part,status,answer,parse_us,solve_us,input_sha256,verdict,revision,\
+             allocations,allocated_bytes,peak_bytes"
+        );
+        assert!(lines[1].starts_with("2022,7,a,Ok,95437,12,123,ba7816bf"));
+        assert!(lines[1].ends_with(",abc1234,3,2048,1024"));
+        assert_eq!(lines[2], "2022,10,b,Ok,\"");
+        assert!(out.contains("2022,11,a,InputNotFound,,,0,,,abc1234,,,\n"));
+    }
+
+    #[test]
+    fn test_render_junit() {
+        let out = render(Format::Junit, &info(), &records());
+        assert!(out.contains("tests=\"4\" failures=\"1\" errors=\"1\" skipped=\"1\""));
+        assert!(out.contains("<failure message=\"expected 1, got 95437\"/>"));
+        assert!(out.contains("<system-out>\n#..#\n,&quot;</system-out>"));
+        assert!(out.contains("<error type=\"InputNotFound\""));
+        assert_eq!(out.matches("<error ").count(), 1);
+        assert!(out.contains("<skipped message=\"NotImplemented\"/>"));
+        assert!(out.contains("<system-err>info: a &lt; b</system-err>"));
+    }
+
+    #[test]
+    fn test_render_junit_examples() {
+        // --examples marks every answer but the expected one as wrong.
+        let wrong = || {
+            Some(Verdict::Wrong {
+                expected: "1".to_string(),
+            })
+        };
+        let mut records = records();
+        records[2].verdict = wrong();
+        records[3].answer = Err(AOCError::Skipped);
+        records[3].verdict = wrong();
+        let out = render(Format::Junit, &info(), &records);
+        assert!(out.contains("tests=\"4\" failures=\"1\" errors=\"1\" skipped=\"1\""));
+        assert_eq!(out.matches("<failure ").count(), 1);
+    }
+}
@@ -0,0 +1,1334 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::answers::{Answers, Verdict};
+use crate::baseline::{Baseline, Comparison, Trend};
+use crate::bench::{self, Stats};
+use crate::cli::{self, AnswerMode, Args, Command, Part};
+use crate::config::Config;
+use crate::context::{self, Collect, Diagnostics, Level};
+use crate::fetch;
+use crate::isolate;
+use crate::memory::{self, Memory};
+use crate::ocr;
+use crate::report::{self, Format, Record, RunInfo};
+use crate::scaffold;
+use crate::submit::{self, Outcome};
+use crate::visualize;
+use crate::{AOCError, InputSource, ParseError, Rng, Solver};
+
+/// A year of puzzles, as seen by the runner.
+pub struct Year {
+    pub year: usize,
+    /// Days with a registered solution, in ascending order.
+    pub days: &'static [usize],
+    pub get_solver: fn(usize) -> Result<Box<dyn Solver>, AOCError>,
+    /// The year crate's directory, which holds `aoc.toml` and `inputs/`.
+    pub root: &'static str,
+    /// Inputs compiled in with the `embed-inputs` feature.
+    pub embedded_input: fn(usize) -> Option<&'static str>,
+}
+
+impl Year {
+    fn inputs_dir(&self) -> PathBuf {
+        Path::new(self.root).join("inputs")
+    }
+
+    /// Where to read `day` from: `--input`, then an embedded input, then the
+    /// inputs directory.
+    fn input_source(&self, args: &Args, day: usize) -> InputSource {
+        if let Some(source) = &args.input {
+            return source.clone();
+        }
+        match (self.embedded_input)(day) {
+            Some(input) => InputSource::Embedded(input),
+            None => InputSource::Dir(args.inputs.clone().unwrap_or_else(|| self.inputs_dir())),
+        }
+    }
+}
+
+/// Stack size for the per-day worker threads, matching the main thread so
+/// recursive solvers behave the same as when run inline.
+const WORKER_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// What a day's worker thread reports back, in this order.
+enum Event {
+    /// The parse time and input hash, or why the day could not start.
+    Prepared(Result<(Duration, String), AOCError>),
+    Solved(
+        Result<String, AOCError>,
+        Duration,
+        Option<Memory>,
+        Diagnostics,
+    ),
+    /// The bench samples, or the panic that stopped them.
+    Benched(Result<Stats, AOCError>),
+}
+
+#[derive(Default)]
+struct Tally {
+    correct: usize,
+    wrong: usize,
+    unknown: usize,
+}
+
+/// The entry point of a binary built with one or more years, in ascending
+/// order. Without `--year` or `--all-years` the latest year is used.
+pub fn main(years: &[Year]) {
+    let name = match years {
+        [year] => format!("aoc{}", year.year),
+        _ => "aoc".to_string(),
+    };
+    let (args, years) = match Args::parse(std::env::args().skip(1))
+        .and_then(|args| select_years(years, &args).map(|years| (args, years)))
+    {
+        Ok(selected) => selected,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::usage(&name));
+            process::exit(2);
+        }
+    };
+    // Everything except `run` and `--list` is limited to one year by the CLI.
+    let year = years[0];
+
+    match args.command {
+        Command::Help => println!("{}", cli::usage(&name)),
+        Command::List => {
+            for (i, year) in years.iter().enumerate() {
+                print_year_header(&years, i);
+                list_days(year, &args);
+            }
+        }
+        Command::New(day) => match scaffold::new_day(Path::new(year.root), day) {
+            Ok(created) => {
+                println!("Created {}", created.solution.display());
+                if let Some(input) = created.input {
+                    println!("Created {}", input.display());
+                }
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+        },
+        Command::Fetch(ref days) => {
+            if let Err(e) = fetch_days(year, &args, days.clone()) {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+        }
+        Command::Generate => {
+            if let Err(e) = generate_days(year, &args) {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+        }
+        Command::Submit => match submit_part(year, &args) {
+            Ok(true) => (),
+            Ok(false) => process::exit(1),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+        },
+        Command::Run => {
+            let mut passed = true;
+            for (i, year) in years.iter().enumerate() {
+                print_year_header(&years, i);
+                match run_days(year, &args) {
+                    Ok(year_passed) => passed &= year_passed,
+                    Err(e) => {
+                        eprintln!("error: {}", e);
+                        passed = false;
+                    }
+                }
+            }
+            if !passed {
+                process::exit(1);
+            }
+        }
+    }
+}
+
+fn select_years<'a>(years: &'a [Year], args: &Args) -> Result<Vec<&'a Year>, String> {
+    match args.year {
+        _ if args.all_years => Ok(years.iter().collect()),
+        Some(wanted) => match years.iter().find(|year| year.year == wanted) {
+            Some(year) => Ok(vec![year]),
+            None => Err(format!(
+                "year {} is not built in, expected one of {}",
+                wanted,
+                years
+                    .iter()
+                    .map(|year| year.year.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )),
+        },
+        None => Ok(years.last().into_iter().collect()),
+    }
+}
+
+/// Separate the output of each year when running more than one.
+fn print_year_header(years: &[&Year], i: usize) {
+    if years.len() > 1 {
+        if i > 0 {
+            println!();
+        }
+        println!("[YEAR {}]", years[i].year);
+    }
+}
+
+/// Download the inputs of `days` that are not cached yet, stopping at the
+/// first failure, which is usually a day that has not unlocked.
+fn fetch_days(year: &Year, args: &Args, days: RangeInclusive<usize>) -> Result<(), AOCError> {
+    let root = Path::new(year.root);
+    let config = Config::load(&root.join("aoc.toml"))?;
+    let session = fetch::session(&config, root)?;
+    let inputs = args.inputs.clone().unwrap_or_else(|| year.inputs_dir());
+
+    for day in days {
+        match fetch::fetch_input(config.base_url(), &session, year.year, day, &inputs)? {
+            Some(path) => println!("Fetched {}", path.display()),
+            None => println!("Day {:02}: already cached", day),
+        }
+    }
+    Ok(())
+}
+
+/// Write a random input for each selected day with a generator, as `NN.in`
+/// in `--inputs` or `generated/` so that `--inputs` can run them. Refuses to
+/// write over the real inputs.
+fn generate_days(year: &Year, args: &Args) -> Result<(), AOCError> {
+    let dir = args
+        .inputs
+        .clone()
+        .unwrap_or_else(|| Path::new(year.root).join("generated"));
+    let real_inputs = year.inputs_dir().canonicalize().ok();
+    if real_inputs.is_some() && dir.canonicalize().ok() == real_inputs {
+        return Err(AOCError::Generate(format!(
+            "not writing to {}, it holds the real inputs",
+            dir.display()
+        )));
+    }
+
+    let single = args.days.start() == args.days.end();
+    let mut generated = 0;
+    for day in args
+        .days
+        .clone()
+        .filter(|day| single || year.days.contains(day))
+    {
+        let solver = (year.get_solver)(day)?;
+        let mut rng = Rng::new(args.seed.unwrap_or(0));
+        let Some(input) = solver.generate(&mut rng, args.size) else {
+            continue;
+        };
+        let path = dir.join(format!("{:02}.in", day));
+        let error = |e: io::Error| AOCError::Generate(format!("{}: {}", path.display(), e));
+        fs::create_dir_all(&dir).map_err(error)?;
+        fs::write(&path, input + "\n").map_err(error)?;
+        println!("Generated {}", path.display());
+        generated += 1;
+    }
+
+    if generated == 0 {
+        let days = if single {
+            format!("day {}", args.days.start())
+        } else {
+            format!("days {:?}", args.days)
+        };
+        return Err(AOCError::Generate(format!("no generator for {}", days)));
+    }
+    Ok(())
+}
+
+/// Solve the selected part of the selected day and post the answer, unless
+/// the answers file already knows whether it is right. Correct answers are
+/// recorded there, wrong ones too so they are never posted twice. Returns
+/// `false` if the answer was not accepted.
+fn submit_part(year: &Year, args: &Args) -> Result<bool, AOCError> {
+    let (day, part) = (*args.days.start(), args.parts()[0]);
+    let root = Path::new(year.root);
+    let config = Config::load(&root.join("aoc.toml"))?;
+    let mut answers = Answers::load(&year.inputs_dir().join("answers.toml"))?;
+
+    let timeout = args.timeout.or_else(|| config.timeout(day));
+    let record = run_day(
+        year,
+        day,
+        &[part],
+        year.input_source(args, day),
+        false,
+        timeout,
+        Collect {
+            progress: args.verbose > 0,
+            frames: false,
+        },
+    )?
+    .remove(0);
+    println!("{}", format_answer(&record));
+    for line in format_diagnostics(&record.diagnostics, args.verbose) {
+        println!("{}", line);
+    }
+    let Ok(answer) = record.answer else {
+        return Ok(false);
+    };
+
+    let refuse =
+        |reason: String| AOCError::Submit(format!("not submitting {}, {}", answer, reason));
+    match answers.verify(day, part, &answer) {
+        Verdict::Correct => {
+            println!("Already known to be correct");
+            return Ok(true);
+        }
+        Verdict::Wrong { expected } => {
+            return Err(refuse(format!(
+                "{} records {} as correct",
+                answers.path().display(),
+                expected
+            )))
+        }
+        Verdict::Unknown if answers.is_wrong(day, part, &answer) => {
+            return Err(refuse("it was already rejected".to_string()))
+        }
+        Verdict::Unknown => (),
+    }
+
+    let session = fetch::session(&config, root)?;
+    let outcome =
+        submit::submit_answer(config.base_url(), &session, year.year, day, part, &answer)?;
+    println!("Submitted: {}", outcome);
+
+    match outcome {
+        Outcome::Correct => answers.set(day, part, answer),
+        _ if outcome.is_wrong() => answers.set_wrong(day, part, answer),
+        _ => return Ok(false),
+    }
+    answers.save()?;
+    Ok(outcome == Outcome::Correct)
+}
+
+/// Run the selected days, returns `false` if any answer failed verification
+/// or any part got slower than its baseline.
+fn run_days(year: &Year, args: &Args) -> Result<bool, AOCError> {
+    let mut answers = match args.answers {
+        AnswerMode::Ignore => None,
+        AnswerMode::Verify | AnswerMode::Record => {
+            Some(Answers::load(&year.inputs_dir().join("answers.toml"))?)
+        }
+    };
+    let config = Config::load(&Path::new(year.root).join("aoc.toml"))?;
+    let mut saved = match &args.save_baseline {
+        Some(name) => Some(Baseline::load(Path::new(year.root), name)?),
+        None => None,
+    };
+    let baseline = match &args.compare {
+        Some(name) => match Baseline::load(Path::new(year.root), name)? {
+            baseline if baseline.exists() => Some(baseline),
+            baseline => {
+                return Err(AOCError::BaselineFile(format!(
+                    "{}: no such baseline, save one with --save-baseline {}",
+                    baseline.path().display(),
+                    name
+                )))
+            }
+        },
+        None => None,
+    };
+    let mut comparisons = Vec::new();
+    let text = args.format == Format::Text;
+    let mut tally = Tally::default();
+    let mut records = Vec::new();
+
+    let days = args.days.clone().collect::<Vec<usize>>();
+    let collect = Collect {
+        progress: args.verbose > 0,
+        frames: args.visualize.is_some(),
+    };
+    let run = |day| {
+        let timeout = args.timeout.or_else(|| config.timeout(day));
+        if args.examples {
+            return run_examples(year, day, &args.parts(), timeout, collect);
+        }
+        run_day(
+            year,
+            day,
+            &args.parts(),
+            year.input_source(args, day),
+            args.bench,
+            timeout,
+            collect,
+        )
+    };
+    // How many animations each part has saved, as examples each get one.
+    let mut animations = BTreeMap::<(usize, Part), usize>::new();
+
+    let start = Instant::now();
+    run_parallel(&days, args.jobs(), run, |day, result| {
+        let mut day_records = match result {
+            Ok(day_records) => day_records,
+            Err(e) => {
+                if text {
+                    println!("{}", format_day_error(day, &e));
+                }
+                records.extend(args.parts().into_iter().map(|part| Record {
+                    day,
+                    part,
+                    answer: Err(e.clone()),
+                    art: None,
+                    parse_time: None,
+                    solve_time: Duration::ZERO,
+                    input_hash: None,
+                    verdict: None,
+                    bench: None,
+                    memory: None,
+                    diagnostics: Diagnostics::default(),
+                }));
+                return;
+            }
+        };
+
+        if let (true, Some(parse_time)) = (text, day_records.first().and_then(|r| r.parse_time)) {
+            println!("{}", format_parse(day, parse_time));
+        }
+        for record in day_records.iter_mut() {
+            let mut line = format_answer(record);
+            if let Some(verdict) = &record.verdict {
+                line.push_str(&format_verdict(verdict));
+                tally.add(verdict);
+            } else if let (Some(answers), Ok(answer)) = (answers.as_mut(), &record.answer) {
+                match args.answers {
+                    AnswerMode::Verify => {
+                        let verdict = answers.verify(day, record.part, answer);
+                        line.push_str(&format_verdict(&verdict));
+                        tally.add(&verdict);
+                        record.verdict = Some(verdict);
+                    }
+                    AnswerMode::Record => answers.set(day, record.part, answer.clone()),
+                    AnswerMode::Ignore => (),
+                }
+            }
+            if text {
+                println!("{}", line);
+                for line in format_diagnostics(&record.diagnostics, args.verbose) {
+                    println!("{}", line);
+                }
+            }
+            if let Some(stats) = &record.bench {
+                if let Some(saved) = saved.as_mut() {
+                    saved.set(day, record.part, stats.clone());
+                }
+                if let Some(baseline) = &baseline {
+                    let comparison =
+                        baseline.compare(day, record.part, stats, args.threshold as f64);
+                    comparisons.push((day, record.part, comparison));
+                }
+            }
+            // The frames are not part of reports, drop them once exported.
+            let frames = std::mem::take(&mut record.diagnostics.frames);
+            if let (Some(target), false) = (&args.visualize, frames.is_empty()) {
+                let n = animations.entry((day, record.part)).or_default();
+                *n += 1;
+                let name = match *n {
+                    1 => format!("day{:02}{}", day, record.part),
+                    n => format!("day{:02}{}-{}", day, record.part, n),
+                };
+                match visualize::export(target, &name, &frames) {
+                    Ok(Some(path)) if text => {
+                        println!("    {} frames saved to {}", frames.len(), path.display())
+                    }
+                    Ok(_) => (),
+                    Err(e) => eprintln!("error: {}", e),
+                }
+            }
+        }
+        records.extend(day_records);
+    });
+    let duration = start.elapsed();
+    if text {
+        println!(
+            "Total: {}µs (with IO), {}µs summed over days",
+            duration.as_micros(),
+            summed_time(&records).as_micros()
+        );
+        if let Some(line) = format_panicked(&records) {
+            println!("{}", line);
+        }
+    }
+
+    match (args.answers, answers) {
+        (AnswerMode::Ignore, _) if text && args.examples => {
+            println!("Examples: {} correct, {} wrong", tally.correct, tally.wrong);
+        }
+        (AnswerMode::Verify, _) if text => {
+            println!(
+                "Verified: {} correct, {} wrong, {} unknown",
+                tally.correct, tally.wrong, tally.unknown
+            );
+        }
+        (AnswerMode::Record, Some(answers)) => {
+            answers.save()?;
+            if text {
+                println!("Recorded answers to {}", answers.path().display());
+            }
+        }
+        _ => (),
+    }
+
+    if !text {
+        let info = RunInfo {
+            year: year.year,
+            revision: report::git_revision(),
+            total_time: duration,
+        };
+        let rendered = report::render(args.format, &info, &records);
+        match &args.output {
+            Some(path) => fs::write(path, rendered)
+                .map_err(|e| AOCError::ReportFile(format!("{}: {}", path.display(), e)))?,
+            None => println!("{}", rendered.trim_end()),
+        }
+    }
+
+    let regressions = comparisons
+        .iter()
+        .filter(|(_, _, c)| c.trend == Trend::Slower)
+        .map(|(day, part, _)| format!("{:02}{}", day, part))
+        .collect::<Vec<String>>();
+    if let (Some(name), true) = (&args.compare, text) {
+        println!(
+            "Compared to baseline {} (threshold {}%):",
+            name, args.threshold
+        );
+        for (day, part, comparison) in &comparisons {
+            println!("{}", format_comparison(*day, *part, comparison));
+        }
+        if !regressions.is_empty() {
+            println!("Regressions: {}", regressions.join(", "));
+        }
+    }
+    if let Some(saved) = saved {
+        saved.save()?;
+        if text {
+            println!("Saved baseline to {}", saved.path().display());
+        }
+    }
+
+    Ok(tally.wrong == 0 && regressions.is_empty())
+}
+
+fn list_days(year: &Year, args: &Args) {
+    for day in args.days.clone() {
+        let solver = if year.days.contains(&day) {
+            "registered"
+        } else {
+            "-"
+        };
+        let input = if year.input_source(args, day).exists(day) {
+            "input"
+        } else {
+            "-"
+        };
+        println!("[DAY {:02}] {:10} {}", day, solver, input);
+    }
+}
+
+/// Run `days` on up to `jobs` threads. Each result is handed to `on_day` on
+/// the calling thread, in the order of `days`, as soon as it and all the days
+/// before it are done.
+fn run_parallel<T, R, F>(days: &[usize], jobs: usize, run: R, mut on_day: F)
+where
+    T: Send,
+    R: Fn(usize) -> T + Sync,
+    F: FnMut(usize, T),
+{
+    let next = AtomicUsize::new(0);
+    let (results, rx) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(days.len()) {
+            let (next, run, results) = (&next, &run, results.clone());
+            scope.spawn(move || {
+                while let Some(&day) = days.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    if results.send((day, run(day))).is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+        drop(results);
+
+        let mut done = BTreeMap::new();
+        let mut pending = days.iter().peekable();
+        for (day, result) in rx {
+            done.insert(day, result);
+            while let Some(result) = pending.peek().and_then(|day| done.remove(*day)) {
+                on_day(*pending.next().unwrap(), result);
+            }
+        }
+    });
+}
+
+/// Time spent parsing and solving, added up over all days. Compared to the
+/// wall time this shows how much running days in parallel saved.
+fn summed_time(records: &[Record]) -> Duration {
+    let mut last_day = None;
+    records
+        .iter()
+        .map(|r| {
+            let parse_time = match last_day.replace(r.day) {
+                Some(day) if day == r.day => Duration::ZERO,
+                _ => r.parse_time.unwrap_or_default(),
+            };
+            parse_time + r.solve_time
+        })
+        .sum()
+}
+
+/// Run a day on a worker thread, waiting at most `timeout` for the parse and
+/// for each part, and again for its bench. A timed out part cannot be
+/// stopped, it is abandoned to finish in the background and the day's
+/// remaining parts are skipped. A timed out bench keeps the part's answer and
+/// single run time, but skips the remaining parts the same way. A timed out
+/// parse fails the whole day.
+fn run_day(
+    year: &Year,
+    day: usize,
+    parts: &[Part],
+    source: InputSource,
+    bench: bool,
+    timeout: Option<Duration>,
+    collect: Collect,
+) -> Result<Vec<Record>, AOCError> {
+    let (events, rx) = mpsc::channel();
+    spawn_worker(year, day, parts, source, bench, collect, events);
+
+    let start = Instant::now();
+    let (parse_time, input_hash) = match recv(&rx, timeout) {
+        Ok(Event::Prepared(prepared)) => prepared.map_err(|e| match e {
+            AOCError::InvalidInput(e) => AOCError::InvalidInput(ParseError {
+                day: Some(day),
+                ..e
+            }),
+            e => e,
+        })?,
+        Ok(_) => unreachable!("the worker reports Prepared first"),
+        Err(RecvTimeoutError::Timeout) => return Err(AOCError::TimedOut(start.elapsed())),
+        Err(RecvTimeoutError::Disconnected) => return Err(worker_stopped()),
+    };
+
+    let mut timed_out = false;
+    let mut records = Vec::new();
+    for &part in parts {
+        let start = Instant::now();
+        let event = (!timed_out).then(|| recv(&rx, timeout));
+        let (answer, solve_time, memory, diagnostics) = match event {
+            None => (
+                Err(AOCError::Skipped),
+                Duration::ZERO,
+                None,
+                Diagnostics::default(),
+            ),
+            Some(Ok(Event::Solved(answer, solve_time, memory, diagnostics))) => {
+                (answer, solve_time, memory, diagnostics)
+            }
+            Some(Ok(_)) => unreachable!("the worker reports Solved for each part"),
+            Some(Err(RecvTimeoutError::Disconnected)) => (
+                Err(worker_stopped()),
+                start.elapsed(),
+                None,
+                Diagnostics::default(),
+            ),
+            Some(Err(RecvTimeoutError::Timeout)) => {
+                timed_out = true;
+                let elapsed = start.elapsed();
+                let answer = Err(AOCError::TimedOut(elapsed));
+                (answer, elapsed, None, Diagnostics::default())
+            }
+        };
+        let (answer, art) = ocr::read_answer(answer);
+        let (answer, bench) = match answer {
+            Ok(answer) if bench => match recv(&rx, timeout) {
+                Ok(Event::Benched(Ok(stats))) => (Ok(answer), Some(stats)),
+                Ok(Event::Benched(Err(e))) => (Err(e), None),
+                Err(RecvTimeoutError::Timeout) => {
+                    timed_out = true;
+                    (Ok(answer), None)
+                }
+                _ => (Err(worker_stopped()), None),
+            },
+            answer => (answer, None),
+        };
+        records.push(Record {
+            day,
+            part,
+            answer,
+            art,
+            parse_time: Some(parse_time),
+            solve_time,
+            input_hash: Some(input_hash.clone()),
+            verdict: None,
+            bench,
+            memory,
+            diagnostics,
+        });
+    }
+
+    Ok(records)
+}
+
+/// Run each of the day's examples through [`run_day`], for the parts it has
+/// an answer for, and attach the verdicts.
+fn run_examples(
+    year: &Year,
+    day: usize,
+    parts: &[Part],
+    timeout: Option<Duration>,
+    collect: Collect,
+) -> Result<Vec<Record>, AOCError> {
+    let solver = (year.get_solver)(day)?;
+    let mut records = Vec::new();
+
+    for example in solver.examples() {
+        let parts = parts
+            .iter()
+            .copied()
+            .filter(|&part| example.answer(part).is_some())
+            .collect::<Vec<Part>>();
+        if parts.is_empty() {
+            continue;
+        }
+
+        let source = InputSource::Embedded(example.input);
+        for mut record in run_day(year, day, &parts, source, false, timeout, collect)? {
+            let expected = example.answer(record.part).unwrap();
+            // Examples give drawn answers as drawn, and example drawings
+            // need not be letters.
+            if record.art.as_deref() == Some(expected) {
+                record.answer = Ok(expected.to_string());
+                record.art = None;
+            }
+            record.verdict = Some(match &record.answer {
+                Ok(answer) if answer == expected => Verdict::Correct,
+                _ => Verdict::Wrong {
+                    expected: expected.to_string(),
+                },
+            });
+            records.push(record);
+        }
+    }
+
+    Ok(records)
+}
+
+/// For a worker that went away without reporting, which only a panic outside
+/// of [`isolate::catch`] can cause.
+fn worker_stopped() -> AOCError {
+    AOCError::Panicked(
+        "the worker stopped without reporting".to_string(),
+        "unknown".to_string(),
+    )
+}
+
+fn recv(rx: &Receiver<Event>, timeout: Option<Duration>) -> Result<Event, RecvTimeoutError> {
+    match timeout {
+        Some(timeout) => rx.recv_timeout(timeout),
+        None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+    }
+}
+
+/// Prepare the input and solve each part, sending an [`Event`] after each
+/// step. Stops early once the runner has stopped listening.
+fn spawn_worker(
+    year: &Year,
+    day: usize,
+    parts: &[Part],
+    source: InputSource,
+    bench: bool,
+    collect: Collect,
+    events: Sender<Event>,
+) {
+    let get_solver = year.get_solver;
+    let parts = parts.to_vec();
+
+    let worker = move || {
+        let label = format!("[DAY {:02} ]", day);
+        let (prepared, mut pending) =
+            context::collect(label, collect, || prepare(get_solver, day, &source));
+        let (solver, parsed) = match prepared {
+            Ok((solver, parsed, parse_time, input_hash)) => {
+                let _ = events.send(Event::Prepared(Ok((parse_time, input_hash))));
+                (solver, parsed)
+            }
+            Err(e) => {
+                let _ = events.send(Event::Prepared(Err(e)));
+                return;
+            }
+        };
+
+        for part in parts {
+            let solve = || solver.solve(parsed.as_ref(), part);
+            let label = format!("[DAY {:02}{}]", day, part);
+            let start = Instant::now();
+            let ((answer, memory), diagnostics) =
+                context::collect(label, collect, || memory::measure(|| isolate::catch(solve)));
+            let solve_time = start.elapsed();
+            let answer = answer.and_then(|answer| answer.ok_or(AOCError::NotImplemented));
+            let solved = answer.is_ok();
+            // What the parse reported goes with the first part.
+            pending.extend(diagnostics);
+            let diagnostics = std::mem::take(&mut pending);
+            if events
+                .send(Event::Solved(answer, solve_time, memory, diagnostics))
+                .is_err()
+            {
+                return;
+            }
+            if solved && bench {
+                let stats = isolate::catch(|| bench::bench(solve));
+                if events.send(Event::Benched(stats)).is_err() {
+                    return;
+                }
+            }
+        }
+    };
+
+    thread::Builder::new()
+        .name(format!("day{:02}", day))
+        .stack_size(WORKER_STACK_SIZE)
+        .spawn(worker)
+        .expect("failed to spawn a worker thread");
+}
+
+type Prepared = (Box<dyn Solver>, Box<dyn Any>, Duration, String);
+
+fn prepare(
+    get_solver: fn(usize) -> Result<Box<dyn Solver>, AOCError>,
+    day: usize,
+    source: &InputSource,
+) -> Result<Prepared, AOCError> {
+    let solver = get_solver(day)?;
+    let input = source.read(day)?;
+    let input_hash = report::input_hash(&input);
+
+    let start = Instant::now();
+    let parsed = isolate::catch(|| solver.prepare(&input)).and_then(|parsed| parsed)?;
+    let parse_time = start.elapsed();
+
+    Ok((solver, parsed, parse_time, input_hash))
+}
+
+impl Tally {
+    fn add(&mut self, verdict: &Verdict) {
+        match verdict {
+            Verdict::Correct => self.correct += 1,
+            Verdict::Wrong { .. } => self.wrong += 1,
+            Verdict::Unknown => self.unknown += 1,
+        }
+    }
+}
+
+/// The answer line, followed by the drawing for answers read from block
+/// letters.
+fn format_answer(record: &Record) -> String {
+    let line = format_answer_line(record);
+    match &record.art {
+        Some(art) => format!("{}\n{}", line, art.trim_matches('\n')),
+        None => line,
+    }
+}
+
+fn format_answer_line(record: &Record) -> String {
+    let answer = match &record.answer {
+        Ok(answer) => answer,
+        Err(e) => return format!("[DAY {:02}{}] {}", record.day, record.part, e),
+    };
+    let mut timing = match &record.bench {
+        Some(stats) => format_stats(stats),
+        None => format!("{:6} µs", record.solve_time.as_micros()),
+    };
+    if let Some(memory) = &record.memory {
+        timing.push_str(&format_memory(memory));
+    }
+    format!(
+        "[DAY {:02}{}] {}: {}",
+        record.day, record.part, timing, answer
+    )
+}
+
+fn format_day_error(day: usize, e: &AOCError) -> String {
+    match e {
+        AOCError::InvalidInput(e) => format!(
+            "[DAY {:02}] InvalidInput: line {}, column {}: {}\n{}",
+            day,
+            e.line,
+            e.column,
+            e.message,
+            e.snippet()
+        ),
+        e => format!("[DAY {:02}] {}", day, e),
+    }
+}
+
+/// Summary of the parts that panicked, if any did.
+fn format_panicked(records: &[Record]) -> Option<String> {
+    let panicked = records
+        .iter()
+        .filter(|r| matches!(r.answer, Err(AOCError::Panicked(..))))
+        .map(|r| format!("{:02}{}", r.day, r.part))
+        .collect::<Vec<String>>();
+    if panicked.is_empty() {
+        None
+    } else {
+        Some(format!("Panicked: {}", panicked.join(", ")))
+    }
+}
+
+fn format_parse(day: usize, time: Duration) -> String {
+    format!("[DAY {:02} ] {:6} µs: (parse)", day, time.as_micros())
+}
+
+/// The logs and counters shown under a part's answer: none by default,
+/// everything but debug logs with `-v` and everything with `-vv`.
+fn format_diagnostics(diagnostics: &Diagnostics, verbose: u8) -> Vec<String> {
+    if verbose == 0 {
+        return Vec::new();
+    }
+    let max_level = if verbose > 1 {
+        Level::Debug
+    } else {
+        Level::Info
+    };
+    let logs = diagnostics
+        .logs
+        .iter()
+        .filter(|log| log.level <= max_level)
+        .map(|log| format!("    {}: {}", log.level, log.message));
+    let counters = diagnostics
+        .counters
+        .iter()
+        .map(|(name, n)| format!("    {} = {}", name, n));
+    logs.chain(counters).collect()
+}
+
+fn format_memory(memory: &Memory) -> String {
+    format!(
+        ", {} allocs, {} allocated, {} peak",
+        memory.allocations,
+        format_bytes(memory.bytes),
+        format_bytes(memory.peak)
+    )
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+fn format_stats(stats: &Stats) -> String {
+    format!(
+        "min {:>9.2?}, median {:>9.2?}, mean {:>9.2?} ± {:>9.2?} ({} samples, {} outliers)",
+        stats.min, stats.median, stats.mean, stats.stddev, stats.samples, stats.outliers,
+    )
+}
+
+fn format_comparison(day: usize, part: Part, comparison: &Comparison) -> String {
+    let Some(before) = comparison.before else {
+        return format!(
+            "[DAY {:02}{}] {:>9} -> {:>9.2?}  (new)",
+            day, part, "", comparison.after
+        );
+    };
+    let trend = match comparison.trend {
+        Trend::Slower => "SLOWER",
+        Trend::Faster => "faster",
+        Trend::Unchanged | Trend::New => "unchanged",
+    };
+    format!(
+        "[DAY {:02}{}] {:>9.2?} -> {:>9.2?} {:+7.1}% {}",
+        day, part, before, comparison.after, comparison.delta, trend
+    )
+}
+
+fn format_verdict(verdict: &Verdict) -> String {
+    match verdict {
+        Verdict::Correct => " (correct)".to_string(),
+        Verdict::Wrong { expected } => format!(" (WRONG, expected {})", expected),
+        Verdict::Unknown => " (unknown)".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Problem;
+
+    struct Slow {}
+
+    impl Problem for Slow {
+        type Input = u64;
+
+        fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+            Ok(input.trim().parse().unwrap())
+        }
+
+        fn part1(&self, millis: &Self::Input) -> Option<String> {
+            thread::sleep(Duration::from_millis(*millis));
+            Some(millis.to_string())
+        }
+
+        fn part2(&self, millis: &Self::Input) -> Option<String> {
+            self.part1(millis)
+        }
+    }
+
+    struct SlowParse {}
+
+    impl Problem for SlowParse {
+        type Input = ();
+
+        fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+            thread::sleep(Duration::from_millis(input.trim().parse().unwrap()));
+            Ok(())
+        }
+
+        fn part1(&self, _input: &Self::Input) -> Option<String> {
+            None
+        }
+
+        fn part2(&self, _input: &Self::Input) -> Option<String> {
+            None
+        }
+    }
+
+    fn slow_year() -> Year {
+        Year {
+            year: 2022,
+            days: &[1],
+            get_solver: |_| Ok(Box::new(Slow {})),
+            root: env!("CARGO_MANIFEST_DIR"),
+            embedded_input: |_| None,
+        }
+    }
+
+    fn record(day: usize, part: Part, answer: Result<String, AOCError>) -> Record {
+        Record {
+            day,
+            part,
+            answer,
+            art: None,
+            parse_time: None,
+            solve_time: Duration::from_micros(123),
+            input_hash: None,
+            verdict: None,
+            bench: None,
+            memory: None,
+            diagnostics: Diagnostics::default(),
+        }
+    }
+
+    #[test]
+    fn test_run_day_timeout() {
+        let source = InputSource::Embedded("200\n");
+
+        let records = run_day(
+            &slow_year(),
+            1,
+            &Part::ALL,
+            source.clone(),
+            false,
+            None,
+            Collect::default(),
+        )
+        .unwrap();
+        assert_eq!(records[1].answer.as_ref().unwrap(), "200");
+
+        let timeout = Some(Duration::from_millis(20));
+        let records = run_day(
+            &slow_year(),
+            1,
+            &Part::ALL,
+            source,
+            false,
+            timeout,
+            Collect::default(),
+        )
+        .unwrap();
+        match &records[0].answer {
+            Err(AOCError::TimedOut(elapsed)) => assert!(*elapsed >= Duration::from_millis(20)),
+            answer => panic!("expected a timeout, got {:?}", answer),
+        }
+        assert!(matches!(records[1].answer, Err(AOCError::Skipped)));
+
+        let year = Year {
+            get_solver: |_| Ok(Box::new(SlowParse {})),
+            ..slow_year()
+        };
+        let source = InputSource::Embedded("200\n");
+        match run_day(
+            &year,
+            1,
+            &Part::ALL,
+            source,
+            false,
+            timeout,
+            Collect::default(),
+        ) {
+            Err(AOCError::TimedOut(elapsed)) => assert!(elapsed >= Duration::from_millis(20)),
+            result => panic!("expected a timeout, got {:?}", result.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_run_day_bench_timeout() {
+        // One run fits in the timeout, the bench's samples do not.
+        let source = InputSource::Embedded("30\n");
+        let timeout = Some(Duration::from_millis(100));
+        let records = run_day(
+            &slow_year(),
+            1,
+            &Part::ALL,
+            source,
+            true,
+            timeout,
+            Collect::default(),
+        )
+        .unwrap();
+        assert_eq!(records[0].answer.as_ref().unwrap(), "30");
+        assert!(records[0].bench.is_none());
+        assert!(matches!(records[1].answer, Err(AOCError::Skipped)));
+    }
+
+    /// Solves its part once, then panics when benched.
+    struct Flaky {}
+
+    static FLAKY_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    impl Problem for Flaky {
+        type Input = ();
+
+        fn parse(&self, _input: &str) -> Result<Self::Input, AOCError> {
+            Ok(())
+        }
+
+        fn part1(&self, _input: &Self::Input) -> Option<String> {
+            if FLAKY_CALLS.fetch_add(1, Ordering::Relaxed) > 0 {
+                panic!("flaky");
+            }
+            Some("1".to_string())
+        }
+
+        fn part2(&self, _input: &Self::Input) -> Option<String> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_run_day_bench_panic() {
+        let year = Year {
+            get_solver: |_| Ok(Box::new(Flaky {})),
+            ..slow_year()
+        };
+        let source = InputSource::Embedded("");
+        let records = run_day(&year, 1, &[Part::A], source, true, None, Collect::default());
+        match &records.unwrap()[0].answer {
+            Err(AOCError::Panicked(message, _)) => assert_eq!(message, "flaky"),
+            answer => panic!("expected a panic, got {:?}", answer),
+        }
+    }
+
+    #[test]
+    fn test_run_parallel_keeps_day_order() {
+        let run = |day| {
+            thread::sleep(Duration::from_millis(10 * (4 - day as u64)));
+            day * 10
+        };
+        let mut seen = Vec::new();
+        run_parallel(&[1, 2, 3], 3, run, |day, result| seen.push((day, result)));
+        assert_eq!(seen, vec![(1, 10), (2, 20), (3, 30)]);
+
+        seen.clear();
+        run_parallel(&[], 3, run, |day, result| seen.push((day, result)));
+        assert!(seen.is_empty());
+    }
+
+    #[test]
+    fn test_summed_time() {
+        let mut records = vec![
+            record(1, Part::A, Ok("1".to_string())),
+            record(1, Part::B, Ok("2".to_string())),
+            record(2, Part::A, Err(AOCError::InputNotFound)),
+        ];
+        records[0].parse_time = Some(Duration::from_micros(100));
+        records[1].parse_time = Some(Duration::from_micros(100));
+        assert_eq!(summed_time(&records), Duration::from_micros(100 + 3 * 123));
+    }
+
+    #[test]
+    fn test_format_answer() {
+        assert_eq!(
+            format_answer(&record(7, Part::A, Ok("95437".to_string()))),
+            "[DAY 07a]    123 µs: 95437"
+        );
+        assert_eq!(
+            format_answer(&record(25, Part::B, Err(AOCError::NotImplemented))),
+            "[DAY 25b] NotImplemented"
+        );
+
+        let mut benched = record(11, Part::B, Ok("42".to_string()));
+        benched.bench = Some(Stats::from_samples(vec![
+            Duration::from_micros(100),
+            Duration::from_micros(300),
+        ]));
+        assert_eq!(
+            format_answer(&benched),
+            "[DAY 11b] min  100.00µs, median  200.00µs, mean  200.00µs ±  141.42µs (2 samples, 0 outliers): 42"
+        );
+
+        let mut counted = record(14, Part::A, Ok("24".to_string()));
+        counted.memory = Some(Memory {
+            allocations: 5012,
+            bytes: 3 * 1024 * 1024 + 512 * 1024,
+            peak: 900,
+        });
+        assert_eq!(
+            format_answer(&counted),
+            "[DAY 14a]    123 µs, 5012 allocs, 3.5 MiB allocated, 900 B peak: 24"
+        );
+
+        let mut drawn = record(10, Part::B, Ok("HI".to_string()));
+        drawn.art = Some("\n#..#.###\n####..#.\n#..#.###\n".to_string());
+        assert_eq!(
+            format_answer(&drawn),
+            "[DAY 10b]    123 µs: HI\n#..#.###\n####..#.\n#..#.###"
+        );
+    }
+
+    #[test]
+    fn test_format_day_error() {
+        assert_eq!(
+            format_day_error(5, &AOCError::InputNotFound),
+            "[DAY 05] InputNotFound"
+        );
+
+        let input = "noop\naddx x\n";
+        let e = ParseError::at(input, &input[10..11], "expected a number");
+        assert_eq!(
+            format_day_error(10, &AOCError::InvalidInput(e)),
+            "[DAY 10] InvalidInput: line 2, column 6: expected a number\n  |\n2 | addx x\n  |      ^"
+        );
+    }
+
+    #[test]
+    fn test_format_panicked() {
+        let panicked = || AOCError::Panicked("boom".to_string(), "day22.rs:1:1".to_string());
+        let records = vec![
+            record(10, Part::A, Ok("1".to_string())),
+            record(10, Part::B, Err(panicked())),
+            record(22, Part::A, Err(panicked())),
+        ];
+        assert_eq!(format_panicked(&records[..1]), None);
+        assert_eq!(
+            format_panicked(&records),
+            Some("Panicked: 10b, 22a".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_parse() {
+        assert_eq!(
+            format_parse(16, Duration::from_micros(42)),
+            "[DAY 16 ]     42 µs: (parse)"
+        );
+    }
+
+    #[test]
+    fn test_format_verdict() {
+        assert_eq!(format_verdict(&Verdict::Correct), " (correct)");
+        assert_eq!(
+            format_verdict(&Verdict::Wrong {
+                expected: "42".to_string()
+            }),
+            " (WRONG, expected 42)"
+        );
+    }
+
+    #[test]
+    fn test_format_comparison() {
+        let comparison = Comparison {
+            before: Some(Duration::from_micros(1200)),
+            after: Duration::from_micros(1350),
+            delta: 12.5,
+            trend: Trend::Slower,
+        };
+        assert_eq!(
+            format_comparison(11, Part::A, &comparison),
+            "[DAY 11a]    1.20ms ->    1.35ms   +12.5% SLOWER"
+        );
+        let comparison = Comparison {
+            before: None,
+            trend: Trend::New,
+            ..comparison
+        };
+        assert_eq!(
+            format_comparison(14, Part::B, &comparison),
+            "[DAY 14b]           ->    1.35ms  (new)"
+        );
+    }
+
+    #[test]
+    fn test_format_diagnostics() {
+        let ctx = crate::Context::current();
+        let ((), diagnostics) = context::collect(String::new(), Collect::default(), || {
+            ctx.info("aligned 3 to 0");
+            ctx.debug("12 overlapping beacons");
+            ctx.count("rotations tried");
+        });
+
+        assert!(format_diagnostics(&diagnostics, 0).is_empty());
+        assert_eq!(
+            format_diagnostics(&diagnostics, 1),
+            vec!["    info: aligned 3 to 0", "    rotations tried = 1"]
+        );
+        assert_eq!(format_diagnostics(&diagnostics, 2).len(), 3);
+    }
+
+    #[test]
+    fn test_select_years() {
+        let years = [
+            Year {
+                year: 2021,
+                ..slow_year()
+            },
+            slow_year(),
+        ];
+        let selected = |args: &[&str]| {
+            let args = Args::parse(args.iter().map(|s| s.to_string())).unwrap();
+            select_years(&years, &args)
+                .map(|years| years.iter().map(|y| y.year).collect::<Vec<_>>())
+        };
+
+        assert_eq!(selected(&[]), Ok(vec![2022]));
+        assert_eq!(selected(&["--year", "2021"]), Ok(vec![2021]));
+        assert_eq!(selected(&["--all-years"]), Ok(vec![2021, 2022]));
+        assert!(selected(&["--year", "2020"]).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use crate::AOCError;
+
+/// What `new` created, for the summary printed afterwards.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Scaffolded {
+    pub solution: PathBuf,
+    /// `None` if the input file already existed and was left alone.
+    pub input: Option<PathBuf>,
+}
+
+/// Create `src/days/dayNN.rs` from `src/days/template.rs` and an empty
+/// `inputs/NN.in` under the year crate's `root`. The build script registers
+/// the new module, so nothing else needs editing. An existing solution is
+/// never overwritten.
+pub fn new_day(root: &Path, day: usize) -> Result<Scaffolded, AOCError> {
+    let days_dir = root.join("src").join("days");
+    let template_path = days_dir.join("template.rs");
+    let solution = days_dir.join(format!("day{:02}.rs", day));
+    let input = root.join("inputs").join(format!("{:02}.in", day));
+
+    let template = fs::read_to_string(&template_path)
+        .map_err(|e| AOCError::Scaffold(format!("{}: {}", template_path.display(), e)))?;
+
+    let create = |path: &Path, contents: &str| {
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)?
+            .write_all(contents.as_bytes())
+    };
+
+    create(&solution, &template).map_err(|e| match e.kind() {
+        ErrorKind::AlreadyExists => AOCError::Scaffold(format!(
+            "{} already exists, refusing to overwrite it",
+            solution.display()
+        )),
+        _ => AOCError::Scaffold(format!("{}: {}", solution.display(), e)),
+    })?;
+
+    let input = match fs::create_dir_all(input.parent().unwrap()).and_then(|_| create(&input, "")) {
+        Ok(()) => Some(input),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => None,
+        Err(e) => return Err(AOCError::Scaffold(format!("{}: {}", input.display(), e))),
+    };
+
+    Ok(Scaffolded { solution, input })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_day() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src").join("days")).unwrap();
+        fs::write(
+            root.join("src/days/template.rs"),
+            "pub struct Solution {}\n",
+        )
+        .unwrap();
+
+        let created = new_day(&root, 23).unwrap();
+        assert_eq!(created.solution, root.join("src/days/day23.rs"));
+        assert_eq!(created.input, Some(root.join("inputs/23.in")));
+        assert_eq!(
+            fs::read_to_string(&created.solution).unwrap(),
+            "pub struct Solution {}\n"
+        );
+        assert_eq!(fs::read_to_string(root.join("inputs/23.in")).unwrap(), "");
+
+        fs::write(&created.solution, "// work in progress\n").unwrap();
+        assert!(matches!(new_day(&root, 23), Err(AOCError::Scaffold(_))));
+        assert_eq!(
+            fs::read_to_string(&created.solution).unwrap(),
+            "// work in progress\n"
+        );
+
+        fs::write(root.join("inputs/24.in"), "1 2 3\n").unwrap();
+        assert_eq!(new_day(&root, 24).unwrap().input, None);
+        assert_eq!(
+            fs::read_to_string(root.join("inputs/24.in")).unwrap(),
+            "1 2 3\n"
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
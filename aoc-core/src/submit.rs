@@ -0,0 +1,230 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::cli::{parse_duration, Part};
+use crate::fetch;
+use crate::AOCError;
+
+/// What the puzzle site said about a submitted answer.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint.
+    Wrong,
+    /// Submitted too soon after the previous answer, with the wait left if
+    /// the site said.
+    RateLimited(Option<Duration>),
+    /// The part is already solved, or not unlocked yet.
+    WrongLevel,
+    /// A response none of the above matched, with its text.
+    Unrecognized(String),
+}
+
+impl Outcome {
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Outcome::TooHigh | Outcome::TooLow | Outcome::Wrong)
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "correct"),
+            Outcome::TooHigh => write!(f, "wrong, too high"),
+            Outcome::TooLow => write!(f, "wrong, too low"),
+            Outcome::Wrong => write!(f, "wrong"),
+            Outcome::RateLimited(Some(wait)) => write!(f, "rate limited, wait {:?}", wait),
+            Outcome::RateLimited(None) => write!(f, "rate limited"),
+            Outcome::WrongLevel => write!(f, "already solved, or not unlocked yet"),
+            Outcome::Unrecognized(text) => write!(f, "unrecognized response: {}", text),
+        }
+    }
+}
+
+/// Post `answer` for `part` of `day` to `base_url` and parse the reply.
+pub fn submit_answer(
+    base_url: &str,
+    session: &str,
+    year: usize,
+    day: usize,
+    part: Part,
+    answer: &str,
+) -> Result<Outcome, AOCError> {
+    let url = format!("{}/{}/day/{}/answer", base_url, year, day);
+    let level = match part {
+        Part::A => "1",
+        Part::B => "2",
+    };
+
+    let body = match fetch::agent()
+        .post(&url)
+        .set("Cookie", &format!("session={}", session))
+        .send_form(&[("level", level), ("answer", answer)])
+    {
+        Ok(response) => response
+            .into_string()
+            .map_err(|e| AOCError::Submit(format!("{}: {}", url, e)))?,
+        Err(ureq::Error::Status(status, response)) => {
+            let body = response.into_string().unwrap_or_default();
+            return Err(AOCError::Submit(format!(
+                "{}: HTTP {}: {}",
+                url,
+                status,
+                body.trim()
+            )));
+        }
+        Err(e) => return Err(AOCError::Submit(format!("{}: {}", url, e))),
+    };
+
+    Ok(parse_response(&body))
+}
+
+/// Classify the `<article>` of an answer page by its wording.
+fn parse_response(html: &str) -> Outcome {
+    let article = html
+        .split_once("<article>")
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(html, |(article, _)| article);
+    let text = strip_tags(article);
+
+    if text.contains("That's the right answer") {
+        Outcome::Correct
+    } else if text.contains("That's not the right answer") {
+        if text.contains("too high") {
+            Outcome::TooHigh
+        } else if text.contains("too low") {
+            Outcome::TooLow
+        } else {
+            Outcome::Wrong
+        }
+    } else if text.contains("You gave an answer too recently") {
+        Outcome::RateLimited(parse_wait(&text))
+    } else if text.contains("You don't seem to be solving the right level") {
+        Outcome::WrongLevel
+    } else {
+        Outcome::Unrecognized(text)
+    }
+}
+
+/// The wait in "You have 1m 37s left to wait".
+fn parse_wait(text: &str) -> Option<Duration> {
+    let (_, rest) = text.split_once("You have ")?;
+    let (wait, _) = rest.split_once(" left to wait")?;
+    wait.split_whitespace()
+        .map(parse_duration)
+        .sum::<Result<Duration, String>>()
+        .ok()
+}
+
+/// The text of an HTML fragment, with whitespace collapsed.
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use tiny_http::{Response, Server};
+
+    use super::*;
+
+    fn page(article: &str) -> String {
+        format!(
+            "<html><body><main><article><p>{}</p></article></main></body></html>",
+            article
+        )
+    }
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(
+            parse_response(&page("That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer.")),
+            Outcome::Correct
+        );
+        assert_eq!(
+            parse_response(&page(
+                "That's not the right answer; your answer is too high.  If you're stuck, ..."
+            )),
+            Outcome::TooHigh
+        );
+        assert_eq!(
+            parse_response(&page(
+                "That's not the right answer; your answer is too low."
+            )),
+            Outcome::TooLow
+        );
+        assert_eq!(
+            parse_response(&page("That's not the right answer.  If you're stuck, make sure you're using the full input data")),
+            Outcome::Wrong
+        );
+        assert_eq!(
+            parse_response(&page("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 37s left to wait. <a href=\"/2022/day/7\">[Return to Day 7]</a>")),
+            Outcome::RateLimited(Some(Duration::from_secs(97)))
+        );
+        assert_eq!(
+            parse_response(&page(
+                "You gave an answer too recently.  You have 12s left to wait."
+            )),
+            Outcome::RateLimited(Some(Duration::from_secs(12)))
+        );
+        assert_eq!(
+            parse_response(&page(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            )),
+            Outcome::WrongLevel
+        );
+        assert_eq!(
+            parse_response("<html>Please log in</html>"),
+            Outcome::Unrecognized("Please log in".to_string())
+        );
+    }
+
+    #[test]
+    fn test_submit_answer() {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", server.server_addr());
+
+        thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                let mut body = String::new();
+                request.as_reader().read_to_string(&mut body).unwrap();
+                let authorized = request
+                    .headers()
+                    .iter()
+                    .any(|h| h.field.equiv("Cookie") && h.value.as_str() == "session=secret");
+                let article = match body.as_str() {
+                    _ if !authorized => "Please log in",
+                    _ if request.url() != "/2022/day/7/answer" => "Not found",
+                    "level=2&answer=24933642" => "That's the right answer!",
+                    _ => "That's not the right answer; your answer is too low.",
+                };
+                request
+                    .respond(Response::from_string(page(article)))
+                    .unwrap();
+            }
+        });
+
+        let submit = |session, part, answer| {
+            submit_answer(&base_url, session, 2022, 7, part, answer).unwrap()
+        };
+        assert_eq!(submit("secret", Part::B, "24933642"), Outcome::Correct);
+        assert_eq!(submit("secret", Part::A, "24933642"), Outcome::TooLow);
+        assert_eq!(
+            submit("wrong", Part::B, "24933642"),
+            Outcome::Unrecognized("Please log in".to_string())
+        );
+    }
+}
@@ -0,0 +1,453 @@
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::cli::Visualize;
+use crate::AOCError;
+
+/// Frames kept per part, later ones are dropped so a long simulation cannot
+/// exhaust memory.
+pub(crate) const MAX_FRAMES: usize = 5000;
+
+/// How long each frame is shown, in the terminal and in GIFs.
+const FRAME_DELAY: Duration = Duration::from_millis(50);
+
+/// Images are scaled up until their longer side reaches this many pixels.
+const TARGET_SIZE: usize = 480;
+
+const MAX_SCALE: usize = 8;
+
+/// The colour of blank cells and of the canvas around smaller frames.
+const BACKGROUND: Color = Color(0x0f, 0x0f, 0x23);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+impl Color {
+    /// The colour a cell showing `ch` gets unless painted: walls are grey,
+    /// digits go from dark blue to bright yellow, anything else gets one of
+    /// a few colours picked by the character.
+    fn of(ch: char) -> Color {
+        const OTHERS: [Color; 6] = [
+            Color(0xff, 0xd7, 0x00),
+            Color(0x00, 0xcc, 0x66),
+            Color(0xff, 0x55, 0x55),
+            Color(0x55, 0xaa, 0xff),
+            Color(0xcc, 0x77, 0xff),
+            Color(0xff, 0x99, 0x33),
+        ];
+        match ch {
+            ' ' | '.' => BACKGROUND,
+            '#' => Color(0xb0, 0xb0, 0xb0),
+            '0'..='9' => {
+                let level = (ch as u32 - '0' as u32) as f64 / 9.0;
+                let mix =
+                    |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * level) as u8;
+                Color(mix(0x20, 0xff), mix(0x20, 0xff), mix(0x60, 0x80))
+            }
+            _ => OTHERS[ch as usize % OTHERS.len()],
+        }
+    }
+}
+
+/// One picture of a simulation: a grid of characters, optionally coloured,
+/// with lines of text shown underneath.
+///
+/// ```
+/// use aoc_core::{Color, Frame};
+///
+/// let mut frame = Frame::from_text("#..\n#.o");
+/// frame.set(1, 0, 'o');
+/// frame.paint(2, 1, Color(0xff, 0, 0));
+/// let frame = frame.annotate("2 grains");
+/// assert_eq!((frame.width(), frame.height()), (3, 2));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<(char, Option<Color>)>,
+    annotations: Vec<String>,
+}
+
+impl Frame {
+    /// A blank frame of `width` by `height` cells.
+    pub fn new(width: usize, height: usize) -> Self {
+        Frame {
+            width,
+            height,
+            cells: vec![(' ', None); width * height],
+            annotations: Vec::new(),
+        }
+    }
+
+    /// A frame with one row per line of `text`.
+    pub fn from_text(text: &str) -> Self {
+        let lines = text.lines().collect::<Vec<&str>>();
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let mut frame = Frame::new(width, lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            for (x, ch) in line.chars().enumerate() {
+                frame.set(x, y, ch);
+            }
+        }
+        frame
+    }
+
+    /// A frame just large enough to hold sparse `points`, such as a
+    /// `HashMap` grid. The top left point becomes `(0, 0)`.
+    pub fn from_points(points: impl IntoIterator<Item = ((isize, isize), char)>) -> Self {
+        let points = points.into_iter().collect::<Vec<_>>();
+        let (Some(min_x), Some(min_y)) = (
+            points.iter().map(|((x, _), _)| *x).min(),
+            points.iter().map(|((_, y), _)| *y).min(),
+        ) else {
+            return Frame::new(0, 0);
+        };
+        let max_x = points.iter().map(|((x, _), _)| *x).max().unwrap();
+        let max_y = points.iter().map(|((_, y), _)| *y).max().unwrap();
+
+        let width = (max_x - min_x + 1) as usize;
+        let mut frame = Frame::new(width, (max_y - min_y + 1) as usize);
+        for ((x, y), ch) in points {
+            frame.set((x - min_x) as usize, (y - min_y) as usize, ch);
+        }
+        frame
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Show `ch` at column `x` of row `y`, out of bounds cells are ignored.
+    pub fn set(&mut self, x: usize, y: usize, ch: char) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x].0 = ch;
+        }
+    }
+
+    /// Colour the cell at `(x, y)` instead of picking a colour by its
+    /// character.
+    pub fn paint(&mut self, x: usize, y: usize, color: Color) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x].1 = Some(color);
+        }
+    }
+
+    /// Add a line of text, such as the step or a running total.
+    pub fn annotate(mut self, text: impl fmt::Display) -> Self {
+        self.annotations.push(text.to_string());
+        self
+    }
+
+    pub fn annotations(&self) -> &[String] {
+        &self.annotations
+    }
+
+    fn cell(&self, x: usize, y: usize) -> (char, Color) {
+        let (ch, color) = self.cells[y * self.width + x];
+        (ch, color.unwrap_or_else(|| Color::of(ch)))
+    }
+}
+
+/// Play `frames` or write them to the `--visualize` directory as
+/// `name.gif` or `name/NNNNN.png`. Returns the path written, if any.
+pub(crate) fn export(
+    target: &Visualize,
+    name: &str,
+    frames: &[Frame],
+) -> Result<Option<PathBuf>, AOCError> {
+    let write_error =
+        |path: &Path, e: io::Error| AOCError::Visualize(format!("{}: {}", path.display(), e));
+    match target {
+        Visualize::Terminal => {
+            play(name, frames).map_err(|e| AOCError::Visualize(e.to_string()))?;
+            Ok(None)
+        }
+        Visualize::Gif(dir) => {
+            let path = dir.join(format!("{}.gif", name));
+            let gif = encode_gif(frames)?;
+            fs::create_dir_all(dir)
+                .and_then(|_| fs::write(&path, gif))
+                .map_err(|e| write_error(&path, e))?;
+            Ok(Some(path))
+        }
+        Visualize::Png(dir) => {
+            let dir = dir.join(name);
+            fs::create_dir_all(&dir).map_err(|e| write_error(&dir, e))?;
+            for (i, frame) in frames.iter().enumerate() {
+                let path = dir.join(format!("{:05}.png", i));
+                let png = encode_png(frame)?;
+                fs::write(&path, png).map_err(|e| write_error(&path, e))?;
+            }
+            Ok(Some(dir))
+        }
+    }
+}
+
+/// Draw each frame over the previous one on stdout, in 24-bit colour.
+fn play(name: &str, frames: &[Frame]) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    for (i, frame) in frames.iter().enumerate() {
+        let mut screen = format!("\x1b[H\x1b[2J{} frame {}/{}\n", name, i + 1, frames.len());
+        for y in 0..frame.height {
+            let mut last = None;
+            for x in 0..frame.width {
+                let (ch, color) = frame.cell(x, y);
+                if last != Some(color) {
+                    let Color(r, g, b) = color;
+                    let _ = write!(screen, "\x1b[38;2;{};{};{}m", r, g, b);
+                    last = Some(color);
+                }
+                screen.push(ch);
+            }
+            screen.push_str("\x1b[0m\n");
+        }
+        for annotation in &frame.annotations {
+            screen.push_str(annotation);
+            screen.push('\n');
+        }
+        stdout.write_all(screen.as_bytes())?;
+        stdout.flush()?;
+        thread::sleep(FRAME_DELAY);
+    }
+    Ok(())
+}
+
+/// The size in cells that fits every frame, and how many pixels each cell
+/// gets along a side.
+fn canvas(frames: &[Frame]) -> (usize, usize, usize) {
+    let width = frames.iter().map(|f| f.width).max().unwrap_or(0).max(1);
+    let height = frames.iter().map(|f| f.height).max().unwrap_or(0).max(1);
+    let scale = (TARGET_SIZE / width.max(height)).clamp(1, MAX_SCALE);
+    (width, height, scale)
+}
+
+/// The pixels of `frame` on a canvas of `width` by `height` cells, row by
+/// row. Frames smaller than the canvas sit in its top left corner.
+fn render(frame: &Frame, width: usize, height: usize, scale: usize) -> Vec<Color> {
+    let mut pixels = Vec::with_capacity(width * height * scale * scale);
+    for y in 0..height * scale {
+        for x in 0..width * scale {
+            let (x, y) = (x / scale, y / scale);
+            pixels.push(match x < frame.width && y < frame.height {
+                true => frame.cell(x, y).1,
+                false => BACKGROUND,
+            });
+        }
+    }
+    pixels
+}
+
+/// An animated GIF of `frames` that loops forever. Colours beyond the 256 a
+/// GIF palette holds are drawn with the closest one that fit. Fails if the
+/// canvas is wider or taller than a GIF can be.
+pub(crate) fn encode_gif(frames: &[Frame]) -> Result<Vec<u8>, AOCError> {
+    let (width, height, scale) = canvas(frames);
+
+    // Scaling and padding add no colours beyond the background, so the
+    // palette comes from the cells and each image is rendered only when it
+    // is encoded.
+    let mut palette = vec![BACKGROUND];
+    let colors = frames.iter().flat_map(|frame| {
+        (0..frame.height).flat_map(move |y| (0..frame.width).map(move |x| frame.cell(x, y).1))
+    });
+    for color in colors {
+        if palette.len() == 256 {
+            break;
+        }
+        if !palette.contains(&color) {
+            palette.push(color);
+        }
+    }
+    let mut indices = HashMap::new();
+    let mut index = |color: Color| {
+        *indices.entry(color).or_insert_with(|| {
+            let distance = |c: &Color| {
+                let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+                d(c.0, color.0) + d(c.1, color.1) + d(c.2, color.2)
+            };
+            (0..palette.len())
+                .min_by_key(|&i| distance(&palette[i]))
+                .unwrap() as u8
+        })
+    };
+
+    // The palette size is a power of two, and LZW codes start at 2 bits.
+    let bits = (usize::BITS - (palette.len() - 1).leading_zeros()).max(2) as u8;
+    let (Ok(image_width), Ok(image_height)) =
+        (u16::try_from(width * scale), u16::try_from(height * scale))
+    else {
+        return Err(AOCError::Visualize(format!(
+            "{}x{} pixels is too large for a GIF, at most {} fit on a side",
+            width * scale,
+            height * scale,
+            u16::MAX
+        )));
+    };
+    let delay = (FRAME_DELAY.as_millis() / 10) as u16;
+
+    let mut gif = b"GIF89a".to_vec();
+    gif.extend(image_width.to_le_bytes());
+    gif.extend(image_height.to_le_bytes());
+    gif.extend([0x80 | (bits - 1) << 4 | (bits - 1), 0, 0]);
+    for i in 0..1 << bits {
+        let Color(r, g, b) = palette.get(i).copied().unwrap_or(BACKGROUND);
+        gif.extend([r, g, b]);
+    }
+    // Loop forever.
+    gif.extend(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+    for frame in frames {
+        gif.extend([0x21, 0xf9, 4, 0]);
+        gif.extend(delay.to_le_bytes());
+        gif.extend([0, 0]);
+        if !frame.annotations.is_empty() {
+            gif.extend([0x21, 0xfe]);
+            push_blocks(&mut gif, frame.annotations.join("\n").as_bytes());
+        }
+
+        gif.push(0x2c);
+        gif.extend([0, 0, 0, 0]);
+        gif.extend(image_width.to_le_bytes());
+        gif.extend(image_height.to_le_bytes());
+        gif.push(0);
+        gif.push(bits);
+        let pixels = render(frame, width, height, scale)
+            .into_iter()
+            .map(&mut index)
+            .collect::<Vec<u8>>();
+        let data = weezl::encode::Encoder::new(weezl::BitOrder::Lsb, bits)
+            .encode(&pixels)
+            .expect("palette indices fit the code size");
+        push_blocks(&mut gif, &data);
+    }
+
+    gif.push(0x3b);
+    Ok(gif)
+}
+
+/// Append `data` as GIF sub-blocks of up to 255 bytes, and the terminator.
+fn push_blocks(gif: &mut Vec<u8>, data: &[u8]) {
+    for block in data.chunks(255) {
+        gif.push(block.len() as u8);
+        gif.extend(block);
+    }
+    gif.push(0);
+}
+
+/// A PNG of a single frame, with its annotations as a UTF-8 `Comment`.
+fn encode_png(frame: &Frame) -> Result<Vec<u8>, AOCError> {
+    let (width, height, scale) = canvas(std::slice::from_ref(frame));
+    let pixels = render(frame, width, height, scale)
+        .into_iter()
+        .flat_map(|Color(r, g, b)| [r, g, b])
+        .collect::<Vec<u8>>();
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, (width * scale) as u32, (height * scale) as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let encoding_error = |e: png::EncodingError| AOCError::Visualize(format!("PNG: {}", e));
+    if !frame.annotations.is_empty() {
+        encoder
+            .add_itxt_chunk("Comment".to_string(), frame.annotations.join("\n"))
+            .map_err(encoding_error)?;
+    }
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels))
+        .map_err(encoding_error)?;
+    Ok(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame() {
+        let frame = Frame::from_points([((-2, 5), '#'), ((1, 4), 'o')]).annotate("step 1");
+        assert_eq!((frame.width(), frame.height()), (4, 2));
+        assert_eq!(frame.cell(0, 1).0, '#');
+        assert_eq!(frame.cell(3, 0).0, 'o');
+        assert_eq!(frame.cell(1, 1), (' ', BACKGROUND));
+        assert_eq!(frame.annotations(), ["step 1"]);
+
+        let mut frame = Frame::from_text("#.\n#");
+        frame.set(5, 5, 'x');
+        frame.paint(1, 1, Color(1, 2, 3));
+        assert_eq!((frame.width(), frame.height()), (2, 2));
+        assert_eq!(frame.cell(1, 1), (' ', Color(1, 2, 3)));
+        assert_eq!(Frame::from_points([]), Frame::new(0, 0));
+    }
+
+    #[test]
+    fn test_encode_gif() {
+        let frames = vec![
+            Frame::from_text("#o").annotate("first"),
+            Frame::from_text("o#\n.."),
+        ];
+        let gif = encode_gif(&frames).unwrap();
+        // 2 by 2 cells of 8 by 8 pixels, a palette of background, wall
+        // and sand padded to 4 colours.
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(&gif[6..10], &[16, 0, 16, 0]);
+        assert_eq!(gif[10], 0x80 | 1 << 4 | 1);
+        assert_eq!(gif.last(), Some(&0x3b));
+
+        let comment = gif.windows(2).position(|w| w == [0x21, 0xfe]).unwrap();
+        assert_eq!(&gif[comment + 2..comment + 8], b"\x05first");
+
+        // The first image decodes back to a wall then sand on the top row
+        // and background below.
+        let descriptor = gif.iter().position(|&b| b == 0x2c).unwrap();
+        let start = descriptor + 10;
+        assert_eq!(gif[start], 2);
+        let mut data = Vec::new();
+        let mut i = start + 1;
+        while gif[i] != 0 {
+            data.extend(&gif[i + 1..i + 1 + gif[i] as usize]);
+            i += 1 + gif[i] as usize;
+        }
+        let pixels = weezl::decode::Decoder::new(weezl::BitOrder::Lsb, 2)
+            .decode(&data)
+            .unwrap();
+        assert_eq!(pixels.len(), 16 * 16);
+        assert_eq!(&pixels[..16], &[[1; 8], [2; 8]].concat()[..]);
+        assert!(pixels[16 * 8..].iter().all(|&p| p == 0));
+    }
+
+    #[test]
+    fn test_encode_gif_too_large() {
+        let frames = vec![Frame::new(1, u16::MAX as usize + 1)];
+        assert!(matches!(encode_gif(&frames), Err(AOCError::Visualize(_))));
+        // A canvas that just fits is written at one pixel per cell.
+        let gif = encode_gif(&[Frame::new(u16::MAX as usize, 1)]).unwrap();
+        assert_eq!(&gif[6..10], &[0xff, 0xff, 1, 0]);
+    }
+
+    #[test]
+    fn test_encode_png() {
+        let png = encode_png(&Frame::from_text("#o").annotate("2 grains → █")).unwrap();
+        let decoder = png::Decoder::new(png.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (16, 8));
+        assert_eq!(&pixels[..3], &[0xb0, 0xb0, 0xb0]);
+        let Color(r, g, b) = Color::of('o');
+        assert_eq!(&pixels[8 * 3..8 * 3 + 3], &[r, g, b]);
+        let text = &reader.info().utf8_text;
+        assert_eq!(text[0].keyword, "Comment");
+        assert_eq!(text[0].get_text().unwrap(), "2 grains → █");
+    }
+}
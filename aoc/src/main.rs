@@ -0,0 +1,3 @@
+pub fn main() {
+    aoc_core::main(&[aoc2021::YEAR, aoc2022::YEAR]);
+}
@@ -1,21 +1,29 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, ParseError, Problem};
 
 pub struct Solution {}
 
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
-        let depths: Vec<i32> = input.split('\n').flat_map(|x| x.parse()).collect();
+    type Input = Vec<i32>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        let depths = input
+            .lines()
+            .map(|x| ParseError::number(input, x))
+            .collect::<Result<Self::Input, ParseError>>()?;
+        Ok(depths)
+    }
+
+    fn part1(&self, depths: &Self::Input) -> Option<String> {
         let increases: i32 = depths
             .iter()
             .zip(&depths[1..])
             .map(|(cur, next)| if next - cur > 0 { 1 } else { 0 })
             .sum();
 
-        format!("{}", increases)
+        Some(format!("{}", increases))
     }
 
-    fn part2(&self, input: &str) -> String {
-        let depths: Vec<i32> = input.split('\n').flat_map(|x| x.parse()).collect();
+    fn part2(&self, depths: &Self::Input) -> Option<String> {
         let window_size = 3;
         let mut window: i32 = depths[0..window_size].iter().sum();
 
@@ -29,6 +37,17 @@ impl Problem for Solution {
             window = new_window;
         }
 
-        format!("{}", acc)
+        Some(format!("{}", acc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        let e = aoc_core::parse_error(&Solution {}, "199\n20o\n208");
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "20o"));
     }
 }
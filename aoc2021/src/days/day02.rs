@@ -1,12 +1,30 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, ParseError, Problem};
 
 pub struct Solution {}
 
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
-        let (hor, depth) = input.lines().map(|cmd| cmd.split_once(' ').unwrap()).fold(
+    type Input = Vec<(String, u32)>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        let commands = input
+            .lines()
+            .map(|l| {
+                let (cmd, val) = l.split_once(' ').ok_or_else(|| {
+                    ParseError::at(input, l, "expected a command like 'forward 5'")
+                })?;
+                if !matches!(cmd, "forward" | "down" | "up") {
+                    return Err(ParseError::at(input, cmd, "expected forward, down or up"));
+                }
+                Ok((cmd.to_string(), ParseError::number(input, val)?))
+            })
+            .collect::<Result<Self::Input, ParseError>>()?;
+        Ok(commands)
+    }
+
+    fn part1(&self, commands: &Self::Input) -> Option<String> {
+        let (hor, depth) = commands.iter().fold(
             (0, 0),
-            |(h, d), (cmd, val)| match (cmd, val.parse::<u32>().unwrap()) {
+            |(h, d), (cmd, val)| match (cmd.as_str(), *val) {
                 ("forward", v) => (h + v, d),
                 ("down", v) => (h, d + v),
                 ("up", v) => (h, d - v),
@@ -14,13 +32,13 @@ impl Problem for Solution {
             },
         );
 
-        format!("{:?}", hor * depth)
+        Some(format!("{:?}", hor * depth))
     }
 
-    fn part2(&self, input: &str) -> String {
-        let (hor, depth, _) = input.lines().map(|cmd| cmd.split_once(' ').unwrap()).fold(
+    fn part2(&self, commands: &Self::Input) -> Option<String> {
+        let (hor, depth, _) = commands.iter().fold(
             (0, 0, 0),
-            |(h, d, a), (cmd, val)| match (cmd, val.parse::<u32>().unwrap()) {
+            |(h, d, a), (cmd, val)| match (cmd.as_str(), *val) {
                 ("forward", v) => (h + v, d + a * v, a),
                 ("down", v) => (h, d, a + v),
                 ("up", v) => (h, d, a - v),
@@ -28,6 +46,19 @@ impl Problem for Solution {
             },
         );
 
-        format!("{}", hor * depth)
+        Some(format!("{}", hor * depth))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        let e = aoc_core::parse_error(&Solution {}, "forward 5\nback 3");
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "back"));
+        let e = aoc_core::parse_error(&Solution {}, "forward 5\ndown x");
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 6, "x"));
     }
 }
@@ -1,17 +1,16 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, ParseError, Problem};
 
 pub struct Solution {}
 
-fn search(numbers: &Vec<u32>, width: u32, sign: bool) -> u32 {
+fn search(numbers: &[u32], width: u32, sign: bool) -> u32 {
     (0..width)
         .rev()
-        .scan(numbers.clone(), |acc, i| {
-            let majority = acc.iter().filter(|&n| n & 1 << i > 0).count() >= (acc.len() + 1) / 2;
+        .scan(numbers.to_vec(), |acc, i| {
+            let majority = acc.iter().filter(|&n| n & 1 << i > 0).count() >= acc.len().div_ceil(2);
 
             *acc = acc
                 .iter()
-                .filter(|n| (*n & 1 << i > 0) == (majority ^ sign))
-                .map(|&n| n)
+                .filter(|n| (*n & 1 << i > 0) == (majority ^ sign)).copied()
                 .collect();
 
             acc.first().copied()
@@ -21,7 +20,26 @@ fn search(numbers: &Vec<u32>, width: u32, sign: bool) -> u32 {
 }
 
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
+    type Input = String;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        let width = input.lines().next().map_or(0, |l| l.len());
+        if !(1..=32).contains(&width) {
+            return Err(ParseError::at(input, input, "expected numbers of 1 to 32 bits").into());
+        }
+        for l in input.lines() {
+            if l.len() != width {
+                return Err(ParseError::at(input, l, format!("expected {} bits", width)).into());
+            }
+            if let Some((i, c)) = l.char_indices().find(|&(_, c)| c != '0' && c != '1') {
+                let text = &l[i..i + c.len_utf8()];
+                return Err(ParseError::at(input, text, "expected a 0 or 1").into());
+            }
+        }
+        Ok(input.to_string())
+    }
+
+    fn part1(&self, input: &Self::Input) -> Option<String> {
         let numbers: Vec<&str> = input.split('\n').collect();
 
         let n: usize = numbers.len();
@@ -50,18 +68,33 @@ impl Problem for Solution {
             }
         }
 
-        format!("{}", gamma_rate * epsilon_rate)
+        Some(format!("{}", gamma_rate * epsilon_rate))
     }
 
-    fn part2(&self, input: &str) -> String {
+    fn part2(&self, input: &Self::Input) -> Option<String> {
         let numbers = input
             .lines()
             .map(|line| u32::from_str_radix(line, 2).unwrap())
             .collect::<Vec<u32>>();
 
-        let oxygen = search(&numbers, 12, true);
-        let co2 = search(&numbers, 12, false);
+        let width = input.lines().next()?.len() as u32;
+        let oxygen = search(&numbers, width, true);
+        let co2 = search(&numbers, width, false);
+
+        Some(format!("{}", oxygen * co2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        format!("{}", oxygen * co2)
+    #[test]
+    fn test_parse_errors() {
+        let cases = [("00100\n1111\n10110", (2, 1, "1111")), ("00100\n11x10", (2, 3, "x"))];
+        for (input, expected) in cases {
+            let e = aoc_core::parse_error(&Solution {}, input);
+            assert_eq!((e.line, e.column, e.text.as_str()), expected);
+        }
     }
 }
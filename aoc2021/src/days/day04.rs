@@ -1,9 +1,10 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, ParseError, Problem};
 use std::collections::HashMap;
 
 pub struct Solution {}
 
-struct Bingo {
+#[derive(Clone)]
+pub struct Bingo {
     num_map: HashMap<u32, Vec<(usize, usize, usize)>>,
     numbers: Vec<u32>,
     boards: Vec<Board>,
@@ -19,10 +20,18 @@ struct Board {
 }
 
 impl Bingo {
-    fn parse_input(input: &str) -> Self {
-        let (nums, boards) = input.split_once("\n\n").unwrap();
-        let numbers = nums.split(',').map(|s| s.parse().unwrap()).collect();
-        let boards: Vec<Board> = boards.split("\n\n").map(Board::parse).collect();
+    fn parse_input(input: &str) -> Result<Self, ParseError> {
+        let (nums, boards) = input.split_once("\n\n").ok_or_else(|| {
+            ParseError::at(input, &input[input.len()..], "expected boards after the numbers")
+        })?;
+        let numbers = nums
+            .split(',')
+            .map(|s| ParseError::number(input, s))
+            .collect::<Result<Vec<u32>, ParseError>>()?;
+        let boards = boards
+            .split("\n\n")
+            .map(|b| Board::parse(input, b))
+            .collect::<Result<Vec<Board>, ParseError>>()?;
         let mut finished_boards = Vec::new();
         let mut num_map = HashMap::new();
 
@@ -38,17 +47,20 @@ impl Bingo {
             finished_boards.push(false)
         }
 
-        Bingo {
+        Ok(Bingo {
             num_map,
             numbers,
             boards,
             finished_boards,
-        }
+        })
     }
 
     fn run(mut self) -> (Option<Board>, u32) {
         for num in self.numbers {
-            for (board_id, row_i, col_i) in self.num_map[&num].iter() {
+            let Some(cells) = self.num_map.get(&num) else {
+                continue;
+            };
+            for (board_id, row_i, col_i) in cells.iter() {
                 let mut board = self.boards[*board_id].clone();
                 board.row_counts[*row_i] -= 1;
                 board.col_counts[*col_i] -= 1;
@@ -70,7 +82,10 @@ impl Bingo {
         let mut num_left = self.boards.len();
 
         for num in self.numbers {
-            for (board_id, row_i, col_i) in self.num_map[&num].iter() {
+            let Some(cells) = self.num_map.get(&num) else {
+                continue;
+            };
+            for (board_id, row_i, col_i) in cells.iter() {
                 if !self.finished_boards[*board_id] {
                     let mut board = self.boards[*board_id].clone();
                     board.row_counts[*row_i] -= 1;
@@ -95,51 +110,78 @@ impl Bingo {
 }
 
 impl Board {
-    fn parse(input: &str) -> Board {
+    fn parse(input: &str, raw: &str) -> Result<Board, ParseError> {
         let row_counts: [u32; 5] = [5; 5];
         let col_counts: [u32; 5] = [5; 5];
         let mut raw_board = Vec::new();
         let mut score: u32 = 0;
 
-        for line in input.lines() {
+        for line in raw.lines() {
             let nums: Vec<u32> = line
                 .split_ascii_whitespace()
-                .map(|n| n.parse().unwrap())
-                .collect::<Vec<u32>>();
+                .map(|n| ParseError::number(input, n))
+                .collect::<Result<Vec<u32>, ParseError>>()?;
+            if nums.len() != 5 {
+                return Err(ParseError::at(input, line, "expected a row of 5 numbers"));
+            }
 
             score = nums.iter().fold(score, |acc, val| acc + val);
             raw_board.push(nums.clone());
         }
+        if raw_board.len() != 5 {
+            return Err(ParseError::at(input, raw, "expected a board of 5 rows"));
+        }
 
-        Board {
+        Ok(Board {
             score,
             raw_board,
             row_counts,
             col_counts,
-        }
+        })
     }
 }
 
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
-        let bingo = Bingo::parse_input(input);
-        let (board, num) = bingo.run();
+    type Input = Bingo;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(Bingo::parse_input(input)?)
+    }
+
+    fn part1(&self, bingo: &Self::Input) -> Option<String> {
+        let (board, num) = bingo.clone().run();
         let solution = match board {
             Some(board) => board.score * num,
             None => 0,
         };
 
-        format!("{}", solution)
+        Some(format!("{}", solution))
     }
 
-    fn part2(&self, input: &str) -> String {
-        let bingo = Bingo::parse_input(input);
-        let (board, num) = bingo.find_loser();
+    fn part2(&self, bingo: &Self::Input) -> Option<String> {
+        let (board, num) = bingo.clone().find_loser();
         let solution = match board {
             Some(board) => board.score * num,
             None => 0,
         };
 
-        format!("{}", solution)
+        Some(format!("{}", solution))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        let rows = "22 13 17 11  0\n 8  2 23  4 24\n21  9 14 16  7\n 6 10  3 18  5\n 1 12 20 15 19";
+        assert!(Bingo::parse_input(&format!("7,4\n\n{}", rows)).is_ok());
+        let e = Bingo::parse_input(&format!("7,x\n\n{}", rows)).err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 3, "x"));
+        let e = Bingo::parse_input(&format!("7,4\n\n{}\n\n1 2 3", rows)).err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (9, 1, "1 2 3"));
+        let e = Bingo::parse_input("7,4").err().unwrap();
+        assert_eq!((e.line, e.column), (1, 4));
     }
 }
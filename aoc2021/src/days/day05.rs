@@ -1,16 +1,16 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, ParseError, Problem};
 use std::collections::HashMap;
 
 pub struct Solution {}
 
 #[derive(Debug)]
-struct Point {
+pub struct Point {
     x: i32,
     y: i32,
 }
 
 #[derive(Debug)]
-struct Line {
+pub struct Line {
     start: Point,
     end: Point,
 }
@@ -21,7 +21,7 @@ struct Grid {
 }
 
 impl Grid {
-    fn setup_grid(input: &str, skip_diagonals: bool) -> Self {
+    fn setup_grid(lines: &[Line], skip_diagonals: bool) -> Self {
         let cells = HashMap::new();
         let mut grid = Self {
             cells,
@@ -29,7 +29,6 @@ impl Grid {
         };
 
         // Add the lines
-        let lines: Vec<Line> = input.lines().map(parse_line).collect();
         for line in lines.iter() {
             grid.add_line(line);
         }
@@ -37,7 +36,6 @@ impl Grid {
     }
 
     fn add_line(&mut self, line: &Line) {
-        let line = &*line;
         let dx = (line.end.x - line.start.x).signum();
         let dy = (line.end.y - line.start.y).signum();
 
@@ -67,36 +65,70 @@ impl Grid {
     }
 }
 
-fn parse_line(l: &str) -> Line {
-    let points: Vec<Vec<i32>> = l
-        .split(" -> ")
-        .map(|p| p.split(',').map(|x| x.parse().unwrap()).collect())
-        .collect();
-
-    let start = Point {
-        x: points[0][0],
-        y: points[0][1],
-    };
-    let end = Point {
-        x: points[1][0],
-        y: points[1][1],
-    };
-
-    Line { start, end }
+fn parse_point(input: &str, p: &str) -> Result<Point, ParseError> {
+    let (x, y) = p
+        .split_once(',')
+        .ok_or_else(|| ParseError::at(input, p, "expected a point like '0,9'"))?;
+    Ok(Point {
+        x: ParseError::number(input, x)?,
+        y: ParseError::number(input, y)?,
+    })
+}
+
+fn parse_line(input: &str, l: &str) -> Result<Line, ParseError> {
+    let (start, end) = l
+        .split_once(" -> ")
+        .ok_or_else(|| ParseError::at(input, l, "expected a line like '0,9 -> 5,9'"))?;
+    let start = parse_point(input, start)?;
+    let end = parse_point(input, end)?;
+
+    // Walking from start to end only reaches it along a row, a column or a
+    // diagonal.
+    let (dx, dy) = (end.x - start.x, end.y - start.y);
+    if dx != 0 && dy != 0 && dx.abs() != dy.abs() {
+        return Err(ParseError::at(input, l, "expected a horizontal, vertical or diagonal line"));
+    }
+
+    Ok(Line { start, end })
 }
 
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
-        let grid = Grid::setup_grid(input, true);
+    type Input = Vec<Line>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        let lines = input
+            .lines()
+            .map(|l| parse_line(input, l))
+            .collect::<Result<Self::Input, ParseError>>()?;
+        Ok(lines)
+    }
+
+    fn part1(&self, lines: &Self::Input) -> Option<String> {
+        let grid = Grid::setup_grid(lines, true);
         let overlaps = grid.get_overlaps();
 
-        format!("{}", overlaps)
+        Some(format!("{}", overlaps))
     }
 
-    fn part2(&self, input: &str) -> String {
-        let grid = Grid::setup_grid(input, false);
+    fn part2(&self, lines: &Self::Input) -> Option<String> {
+        let grid = Grid::setup_grid(lines, false);
         let overlaps = grid.get_overlaps();
 
-        format!("{}", overlaps)
+        Some(format!("{}", overlaps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        let e = parse_line("0,9 -> 5,x", "0,9 -> 5,x").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 10, "x"));
+        let e = parse_line("0,9 - 5,9", "0,9 - 5,9").unwrap_err();
+        assert_eq!((e.line, e.column), (1, 1));
+        assert!(parse_line("8,0 -> 0,8", "8,0 -> 0,8").is_ok());
+        assert!(parse_line("0,0 -> 1,2", "0,0 -> 1,2").is_err());
     }
 }
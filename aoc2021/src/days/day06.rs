@@ -1,10 +1,10 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 use std::collections::VecDeque;
 
 pub struct Solution {}
 
-#[derive(Debug)]
-struct Fish {
+#[derive(Debug, Clone)]
+pub struct Fish {
     timer: u32,
 }
 
@@ -14,20 +14,22 @@ impl Fish {
     }
 }
 
-#[derive(Debug)]
-struct Simulation {
+#[derive(Debug, Clone)]
+pub struct Simulation {
     fishes: Vec<Fish>,
 }
 
 impl Simulation {
-    fn new(input: &str) -> Self {
-        let fishes: Vec<Fish> = input
-            .strip_suffix('\n')
-            .unwrap()
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let fishes = input
+            .trim_end()
             .split(',')
-            .map(|timer| Fish::new(timer.parse().unwrap()))
-            .collect();
-        Simulation { fishes }
+            .map(|timer| match ParseError::number(input, timer)? {
+                t @ 0..=8 => Ok(Fish::new(t)),
+                _ => Err(ParseError::at(input, timer, "expected a timer from 0 to 8")),
+            })
+            .collect::<Result<Vec<Fish>, ParseError>>()?;
+        Ok(Simulation { fishes })
     }
 
     fn tick(&mut self) {
@@ -53,22 +55,37 @@ impl Simulation {
     }
 }
 
+const EXAMPLE: &str = "3,4,3,1,2";
+
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
+    type Input = Simulation;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("5934"),
+            part2: Some("26984457539"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(Simulation::new(input)?)
+    }
+
+    fn part1(&self, sim: &Self::Input) -> Option<String> {
         // Slow, memory inefficient solution
-        let mut sim = Simulation::new(input);
+        let mut sim = sim.clone();
         sim.run(80);
 
-        format!("{}", sim.fishes.len())
+        Some(format!("{}", sim.fishes.len()))
     }
 
-    fn part2(&self, input: &str) -> String {
+    fn part2(&self, sim: &Self::Input) -> Option<String> {
         // Realize mistake, use cyclic buffer
-        let sim = Simulation::new(input);
         let time = 256;
 
         let mut fish_counts: VecDeque<u64> = VecDeque::from([0; 9]);
-        for fish in sim.fishes {
+        for fish in sim.fishes.iter() {
             fish_counts[fish.timer as usize] += 1;
         }
 
@@ -79,7 +96,7 @@ impl Problem for Solution {
 
         let population_size: u64 = fish_counts.iter().sum();
 
-        format!("{}", population_size)
+        Some(format!("{}", population_size))
     }
 }
 
@@ -87,13 +104,20 @@ impl Problem for Solution {
 mod tests {
     use super::*;
 
+    aoc_core::example_tests!(Solution {});
+
     #[test]
-    fn test_part1_example() {
-        let input = "3,4,3,1,2\n";
-        let mut sim = Simulation::new(input);
+    fn test_run() {
+        let mut sim = Simulation::new(EXAMPLE).unwrap();
         sim.run(18);
         assert_eq!(sim.fishes.len(), 26);
-        sim.run(62);
-        assert_eq!(sim.fishes.len(), 5934);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let e = Simulation::new("3,4,x,1").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 5, "x"));
+        let e = Simulation::new("3,4,9,1").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 5, "9"));
     }
 }
@@ -1,4 +1,4 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 use counter::Counter;
 use std::collections::HashMap;
 
@@ -6,13 +6,12 @@ pub struct Solution {}
 
 type BurnFunction<'a> = &'a dyn Fn(i32, i32, usize) -> usize;
 
-fn parse_input(input: &str) -> Vec<i32> {
+fn parse_input(input: &str) -> Result<Vec<i32>, ParseError> {
     input
-        .strip_suffix('\n')
-        .unwrap()
+        .trim_end()
         .split(',')
-        .map(|x| x.parse::<i32>().unwrap())
-        .collect::<Vec<i32>>()
+        .map(|x| ParseError::number(input, x))
+        .collect()
 }
 
 fn constant_fuel_burn_cost(target: i32, pos: i32, count: usize) -> usize {
@@ -31,13 +30,13 @@ fn compute_cost(counts: &Counter<&i32>, target: i32, burn_fn: BurnFunction) -> u
         .sum::<usize>()
 }
 
-fn optimize(positions: Vec<i32>, burn_fn: BurnFunction) -> (i32, usize) {
+fn optimize(positions: &[i32], burn_fn: BurnFunction) -> (i32, usize) {
     let counts = positions.iter().collect::<Counter<_>>();
     let xmin = positions.iter().min().unwrap();
     let xmax = positions.iter().max().unwrap();
     let mut costs: HashMap<i32, usize> = HashMap::new();
 
-    for target in *xmin..*xmax {
+    for target in *xmin..=*xmax {
         let t = costs.entry(target).or_insert(0);
         *t = compute_cost(&counts, target, burn_fn);
     }
@@ -46,17 +45,31 @@ fn optimize(positions: Vec<i32>, burn_fn: BurnFunction) -> (i32, usize) {
     (pos, cost)
 }
 
+const EXAMPLE: &str = "16,1,2,0,4,2,7,1,2,14";
+
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
-        let positions = parse_input(input);
+    type Input = Vec<i32>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("37"),
+            part2: Some("168"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(&self, positions: &Self::Input) -> Option<String> {
         let (_, fuel) = optimize(positions, &constant_fuel_burn_cost);
-        format!("{}", fuel)
+        Some(format!("{}", fuel))
     }
 
-    fn part2(&self, input: &str) -> String {
-        let positions = parse_input(input);
+    fn part2(&self, positions: &Self::Input) -> Option<String> {
         let (_, fuel) = optimize(positions, &growing_fuel_burn_cost);
-        format!("{}", fuel)
+        Some(format!("{}", fuel))
     }
 }
 
@@ -64,21 +77,18 @@ impl Problem for Solution {
 mod tests {
     use super::*;
 
+    aoc_core::example_tests!(Solution {});
+
     #[test]
-    fn test_part1_example() {
-        let input = "16,1,2,0,4,2,7,1,2,14\n";
-        let positions = parse_input(input);
-        let (optimal, fuel) = optimize(positions, &constant_fuel_burn_cost);
-        assert_eq!(optimal, 2);
-        assert_eq!(fuel, 37);
+    fn test_optimize() {
+        let positions = parse_input(EXAMPLE).unwrap();
+        assert_eq!(optimize(&positions, &constant_fuel_burn_cost).0, 2);
+        assert_eq!(optimize(&positions, &growing_fuel_burn_cost).0, 5);
     }
 
     #[test]
-    fn test_part2_example() {
-        let input = "16,1,2,0,4,2,7,1,2,14\n";
-        let positions = parse_input(input);
-        let (optimal, fuel) = optimize(positions, &growing_fuel_burn_cost);
-        assert_eq!(optimal, 5);
-        assert_eq!(fuel, 168);
+    fn test_parse_errors() {
+        let e = parse_input("16,1,,0").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 6, ""));
     }
 }
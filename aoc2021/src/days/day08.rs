@@ -1,12 +1,41 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, ParseError, Problem};
 
 pub struct Solution {}
 
-
+/// Check every line has ten patterns and four output digits of segments a-g,
+/// with the patterns for 1 and 4 among them.
+fn check_input(input: &str) -> Result<(), ParseError> {
+    for l in input.lines() {
+        let (codes, digits) = l.split_once(" | ").ok_or_else(|| {
+            ParseError::at(input, l, "expected patterns and digits split by ' | '")
+        })?;
+        let codes = codes.split(' ').collect::<Vec<&str>>();
+        let digits = digits.split(' ').collect::<Vec<&str>>();
+        if codes.len() != 10 || digits.len() != 4 {
+            return Err(ParseError::at(input, l, "expected 10 patterns and 4 digits"));
+        }
+        for d in codes.iter().chain(&digits) {
+            if !(2..=7).contains(&d.len()) || !d.bytes().all(|b| (b'a'..=b'g').contains(&b)) {
+                return Err(ParseError::at(input, d, "expected 2 to 7 segments a-g"));
+            }
+        }
+        if !codes.iter().any(|d| d.len() == 2) || !codes.iter().any(|d| d.len() == 4) {
+            return Err(ParseError::at(input, l, "expected patterns for 1 and 4"));
+        }
+    }
+    Ok(())
+}
 
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
-        let candidates = vec![2, 3, 4, 7];
+    type Input = String;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        check_input(input)?;
+        Ok(input.to_string())
+    }
+
+    fn part1(&self, input: &Self::Input) -> Option<String> {
+        let candidates = [2, 3, 4, 7];
         let answer = input
             .lines()
             .flat_map(|l| {
@@ -16,10 +45,10 @@ impl Problem for Solution {
                 })
             })
             .count();
-        format!("{}", answer)
+        Some(format!("{}", answer))
     }
 
-    fn part2(&self, input: &str) -> String {
+    fn part2(&self, input: &Self::Input) -> Option<String> {
         let answer = input.lines().map(|line| {
             let mut parts = line.split(" | ");
             let codes = parts.next().unwrap().split(' ');
@@ -29,7 +58,7 @@ impl Problem for Solution {
             parts.next()
                 .unwrap()
                 .split(' ')
-                .map(|d| match d.len() {
+                .map(|d| Some(match d.len() {
                     2 => 1,
                     3 => 7,
                     4 => 4,
@@ -45,14 +74,32 @@ impl Problem for Solution {
                         (6, 1, _) => 6,
                         (6, _, 3) => 0,
                         (6, _, 4) => 9,
-                        _ => unreachable!()
+                        _ => return None,
                     }
 
-                })
+                }))
                 .enumerate()
-                .fold(0, |acc, (i, n)| acc + n * 10_u32.pow(3 - i as u32))
-        }).sum::<u32>();
+                .try_fold(0, |acc, (i, n)| Some(acc + n? * 10_u32.pow(3 - i as u32)))
+        }).sum::<Option<u32>>()?;
+
+        Some(format!("{}", answer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        format!("{}", answer)
+    #[test]
+    fn test_parse_errors() {
+        let line = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+        assert!(check_input(line).is_ok());
+        assert_eq!(Solution {}.part2(&line.to_string()).as_deref(), Some("5353"));
+        let bad = line.replace("fcadb", "fcadx");
+        let e = check_input(&bad).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 68, "fcadx"));
+        let bad = line.replace(" | ", " ");
+        let e = check_input(&bad).unwrap_err();
+        assert_eq!((e.line, e.column), (1, 1));
     }
 }
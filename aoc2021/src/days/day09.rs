@@ -1,4 +1,4 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 use disjoint_sets::UnionFind;
 
 pub struct Solution {}
@@ -6,13 +6,23 @@ pub struct Solution {}
 type Matrix = Vec<Vec<u32>>;
 type Point = (usize, usize);
 
-fn parse_input(input: &str) -> Matrix {
+fn parse_input(input: &str) -> Result<Matrix, ParseError> {
+    let width = input.lines().next().map_or(0, |l| l.len());
+    if width == 0 {
+        return Err(ParseError::at(input, input, "expected a height map"));
+    }
     input
         .lines()
         .map(|l| {
-            l.chars()
-                .map(|c| c.to_digit(10).unwrap())
-                .collect::<Vec<u32>>()
+            if l.len() != width {
+                return Err(ParseError::at(input, l, format!("expected a row {} wide", width)));
+            }
+            l.char_indices()
+                .map(|(i, c)| {
+                    let text = &l[i..i + c.len_utf8()];
+                    c.to_digit(10).ok_or_else(|| ParseError::at(input, text, "expected a digit"))
+                })
+                .collect::<Result<Vec<u32>, ParseError>>()
         })
         .collect()
 }
@@ -105,23 +115,41 @@ fn two_pass(matrix: &Matrix) -> Vec<usize> {
     basin_sizes
 }
 
+const EXAMPLE: &str = "2199943210
+3987894921
+9856789892
+8767896789
+9899965678";
+
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
-        let matrix = parse_input(input);
-        let solution: u32 = find_minima_values(&matrix)
+    type Input = Matrix;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("15"),
+            part2: Some("1134"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(&self, matrix: &Self::Input) -> Option<String> {
+        let solution: u32 = find_minima_values(matrix)
             .iter()
             .fold(0, |acc, n| acc + 1 + n);
-        format!("{}", solution)
+        Some(format!("{}", solution))
     }
 
-    fn part2(&self, input: &str) -> String {
-        let matrix = parse_input(input);
-        let mut solution = two_pass(&matrix);
+    fn part2(&self, matrix: &Self::Input) -> Option<String> {
+        let mut solution = two_pass(matrix);
         solution.sort_unstable();
 
         let answer: usize = solution.iter().rev().take(3).product();
 
-        format!("{:?}", answer)
+        Some(format!("{:?}", answer))
     }
 }
 
@@ -129,34 +157,22 @@ impl Problem for Solution {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part1_example() {
-        let input = "2199943210
-3987894921
-9856789892
-8767896789
-9899965678";
+    aoc_core::example_tests!(Solution {});
 
-        let matrix = parse_input(input);
-        let solution = find_minima_values(&matrix)
-            .iter()
-            .fold(0, |acc, n| acc + 1 + n);
-        dbg!(solution);
-        assert_eq!(solution, 15);
-    }
     #[test]
-    fn test_part2_example() {
-        let input = "2199943210
-3987894921
-9856789892
-8767896789
-9899965678";
-        let matrix = parse_input(input);
-        let mut solution = two_pass(&matrix);
-        solution.sort_unstable();
+    fn test_two_pass() {
+        let mut basins = two_pass(&parse_input(EXAMPLE).unwrap());
+        basins.sort_unstable();
+        // Labels merged into another basin are left empty.
+        assert!(basins.ends_with(&[3, 9, 9, 14]));
+        assert!(basins[..basins.len() - 4].iter().all(|&n| n == 0));
+    }
 
-        dbg!(&solution);
-        let answer = solution.iter().rev().take(3).fold(1, |acc, val| acc * val);
-        assert_eq!(answer, 1134);
+    #[test]
+    fn test_parse_errors() {
+        let e = parse_input("2199\n3x87").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 2, "x"));
+        let e = parse_input("2199\n398").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "398"));
     }
 }
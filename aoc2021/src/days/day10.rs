@@ -1,4 +1,4 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 
 use std::collections::HashMap;
 
@@ -26,24 +26,24 @@ impl SyntaxChecker {
         }
     }
 
-    fn score_error_delim(&self, line: &str) -> u64 {
+    fn score_error_delim(&self, line: &[char]) -> u64 {
         let mut prev = vec![];
-        for c in line.chars() {
+        for &c in line {
             if self.openers.contains(&c) {
                 prev.push(c);
-            } else if prev.pop().unwrap() != *self.delim_map.get(&c).unwrap() {
+            } else if prev.pop() != self.delim_map.get(&c).copied() {
                 return *self.point_map.get(&c).unwrap();
             }
         }
         0
     }
 
-    fn score_completion(&self, line: &str) -> u64 {
+    fn score_completion(&self, line: &[char]) -> u64 {
         let mut prev = vec![];
-        for c in line.chars() {
+        for &c in line {
             if self.openers.contains(&c) {
                 prev.push(c);
-            } else if prev.pop().unwrap() != *self.delim_map.get(&c).unwrap() {
+            } else if prev.pop() != self.delim_map.get(&c).copied() {
                 panic!("corrupt input");
             }
         }
@@ -65,50 +65,103 @@ fn part2_pointmap() -> HashMap<char, u64> {
     HashMap::from([(')', 1), (']', 2), ('}', 3), ('>', 4)])
 }
 
+const EXAMPLE: &str = "[({(<(())[]>[[{[]{<()<>>
+[(()[<>])]({[<{<<[]>>(
+{([(<{}[<>[]}>{[]{[(<()>
+(((({<>}<{<{<>}{[]{[]{}
+[[<[([]))<([[{}[[()]]]
+[{[{({}]{}}([{[{{{}}([]
+{<[[]]>}<{[{[{[]{()[[[]
+[<(<(<(<{}))><([]([]()
+<{([([[(<>()){}]>(<<{{
+<{([{{}}[<[[[<>{}]]]>[]]";
+
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
+    type Input = Vec<Vec<char>>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("26397"),
+            part2: Some("288957"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        let lines = input
+            .lines()
+            .map(|l| {
+                l.char_indices()
+                    .map(|(i, c)| match c {
+                        '(' | ')' | '[' | ']' | '{' | '}' | '<' | '>' => Ok(c),
+                        _ => {
+                            let text = &l[i..i + c.len_utf8()];
+                            Err(ParseError::at(input, text, "expected one of ()[]{}<>"))
+                        }
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(lines)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Option<String> {
         let checker = SyntaxChecker::new(part1_pointmap());
         let solution: u64 = input
-            .lines()
+            .iter()
             .map(|line| checker.score_error_delim(line))
             .sum();
 
-        format!("{}", solution)
+        Some(format!("{}", solution))
     }
 
-    fn part2(&self, input: &str) -> String {
+    fn part2(&self, input: &Self::Input) -> Option<String> {
         let checker = SyntaxChecker::new(part2_pointmap());
         let mut solution: Vec<u64> = input
-            .lines()
+            .iter()
             .filter(|line| checker.score_error_delim(line) == 0)
             .map(|line| checker.score_completion(line))
             .collect();
 
         solution.sort();
 
-        format!("{}", solution[solution.len() / 2])
+        Some(format!("{}", solution.get(solution.len() / 2)?))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    aoc_core::example_tests!(Solution {});
+
+    fn chars(line: &str) -> Vec<char> {
+        line.chars().collect()
+    }
+
     #[test]
     fn test_part1_corrupt() {
         let checker = SyntaxChecker::new(part1_pointmap());
-        assert_eq!(checker.score_error_delim("{([(<{}[<>[]}>{[]{[(<()>"), 1197);
-        assert_eq!(checker.score_error_delim("[[<[([]))<([[{}[[()]]]"), 3);
-        assert_eq!(checker.score_error_delim("[{[{({}]{}}([{[{{{}}([]"), 57);
-        assert_eq!(checker.score_error_delim("[<(<(<(<{}))><([]([]()"), 3);
-        assert_eq!(checker.score_error_delim("<{([([[(<>()){}]>(<<{{"), 25137);
+        assert_eq!(checker.score_error_delim(&chars(")(")), 3);
+        assert_eq!(checker.score_error_delim(&chars("{([(<{}[<>[]}>{[]{[(<()>")), 1197);
+        assert_eq!(checker.score_error_delim(&chars("[[<[([]))<([[{}[[()]]]")), 3);
+        assert_eq!(checker.score_error_delim(&chars("[{[{({}]{}}([{[{{{}}([]")), 57);
+        assert_eq!(checker.score_error_delim(&chars("[<(<(<(<{}))><([]([]()")), 3);
+        assert_eq!(checker.score_error_delim(&chars("<{([([[(<>()){}]>(<<{{")), 25137);
     }
     #[test]
     fn test_part2_completion() {
         let checker = SyntaxChecker::new(part2_pointmap());
-        assert_eq!(checker.score_completion("[({(<(())[]>[[{[]{<()<>>"), 288957);
-        assert_eq!(checker.score_completion("[(()[<>])]({[<{<<[]>>("), 5566);
-        assert_eq!(checker.score_completion("(((({<>}<{<{<>}{[]{[]{}"), 1480781);
-        assert_eq!(checker.score_completion("{<[[]]>}<{[{[{[]{()[[[]"), 995444);
-        assert_eq!(checker.score_completion("<{([{{}}[<[[[<>{}]]]>[]]"), 294);
+        assert_eq!(checker.score_completion(&chars("[({(<(())[]>[[{[]{<()<>>")), 288957);
+        assert_eq!(checker.score_completion(&chars("[(()[<>])]({[<{<<[]>>(")), 5566);
+        assert_eq!(checker.score_completion(&chars("(((({<>}<{<{<>}{[]{[]{}")), 1480781);
+        assert_eq!(checker.score_completion(&chars("{<[[]]>}<{[{[{[]{()[[[]")), 995444);
+        assert_eq!(checker.score_completion(&chars("<{([{{}}[<[[[<>{}]]]>[]]")), 294);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let e = aoc_core::parse_error(&Solution {}, "[({(<(())[]>\n[(()[a>])]");
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 6, "a"));
     }
 }
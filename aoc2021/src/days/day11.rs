@@ -1,4 +1,4 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Color, Context, Example, Frame, ParseError, Problem};
 use std::collections::HashMap;
 
 pub struct Solution {}
@@ -17,7 +17,7 @@ const OFFSETS: [(isize, isize); 8] = [
 ];
 
 fn in_bounds(p: (isize, isize)) -> bool {
-    p.0 >= 0 && p.1 >= 0 && p.0 < SIZE as isize && p.1 < SIZE as isize
+    p.0 >= 0 && p.1 >= 0 && p.0 < SIZE && p.1 < SIZE
 }
 
 fn get_neighbors(center: (isize, isize)) -> Vec<(isize, isize)> {
@@ -29,19 +29,15 @@ fn get_neighbors(center: (isize, isize)) -> Vec<(isize, isize)> {
 }
 
 fn flash(grid: &mut HashMap<(isize, isize), u32>, p: (isize, isize)) -> u32 {
-    (*grid.get(&p).unwrap() > 9)
-        .then(|| {
+    if *grid.get(&p).unwrap() > 9 { {
             *grid.get_mut(&p).unwrap() = 0;
             get_neighbors(p).iter().fold(1, |acc, n| {
-                acc + (*grid.get(n).unwrap() > 0)
-                    .then(|| {
+                acc + if *grid.get(n).unwrap() > 0 { {
                         *grid.get_mut(n).unwrap() += 1;
                         flash(grid, *n)
-                    })
-                    .unwrap_or(0)
+                    } } else { 0 }
             })
-        })
-        .unwrap_or(0)
+        } } else { 0 }
 }
 
 fn tick(grid: &mut HashMap<(isize, isize), u32>) -> u32 {
@@ -51,38 +47,89 @@ fn tick(grid: &mut HashMap<(isize, isize), u32>) -> u32 {
     })
 }
 
-fn parse_input(input: &str) -> HashMap<(isize, isize), u32> {
-    input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, row)| {
-            row.chars()
-                .enumerate()
-                .map(move |(x, c)| ((x as isize, y as isize), c as u32 - 0x30))
-        })
-        .collect::<HashMap<(isize, isize), u32>>()
+/// The energy levels, with the octopuses that just flashed lit up.
+fn draw(grid: &HashMap<(isize, isize), u32>, step: usize) -> Frame {
+    let mut frame = Frame::new(SIZE as usize, SIZE as usize);
+    for (&(x, y), &energy) in grid {
+        frame.set(x as usize, y as usize, char::from_digit(energy.min(9), 10).unwrap());
+        if energy == 0 {
+            frame.paint(x as usize, y as usize, Color(0xff, 0xff, 0xff));
+        }
+    }
+    frame.annotate(format!("step {}", step))
+}
+
+fn parse_input(input: &str) -> Result<HashMap<(isize, isize), u32>, ParseError> {
+    let mut grid = HashMap::new();
+    for (y, row) in input.lines().enumerate() {
+        if y as isize >= SIZE {
+            return Err(ParseError::at(input, row, format!("expected {} rows", SIZE)));
+        }
+        if row.len() != SIZE as usize {
+            return Err(ParseError::at(input, row, format!("expected a row {} wide", SIZE)));
+        }
+        for (x, c) in row.char_indices() {
+            let energy = c.to_digit(10).ok_or_else(|| {
+                ParseError::at(input, &row[x..x + c.len_utf8()], "expected a digit")
+            })?;
+            grid.insert((x as isize, y as isize), energy);
+        }
+    }
+    if grid.len() != (SIZE * SIZE) as usize {
+        let end = &input[input.len()..];
+        return Err(ParseError::at(input, end, format!("expected {} rows", SIZE)));
+    }
+    Ok(grid)
 }
 
+const EXAMPLE: &str = "5483143223
+2745854711
+5264556173
+6141336146
+6357385478
+4167524645
+2176841721
+6882881134
+4846848554
+5283751526";
+
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
-        let mut grid = parse_input(input);
+    type Input = HashMap<(isize, isize), u32>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("1656"),
+            part2: Some("195"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(&self, grid: &Self::Input) -> Option<String> {
+        let mut grid = grid.clone();
         let n_flashes = (0..100).fold(0, |acc, _| acc + tick(&mut grid));
 
-        format!("{}", n_flashes)
+        Some(format!("{}", n_flashes))
     }
 
-    fn part2(&self, input: &str) -> String {
-        let mut grid = parse_input(input);
+    fn part2(&self, grid: &Self::Input) -> Option<String> {
+        let mut grid = grid.clone();
+        let ctx = Context::current();
 
         let mut iterations = 1;
         loop {
-            if tick(&mut grid) == (SIZE as u32).pow(2) {
+            let n_flashes = tick(&mut grid);
+            ctx.frame(|| draw(&grid, iterations));
+            if n_flashes == (SIZE as u32).pow(2) {
                 break;
             }
             iterations += 1;
         }
 
-        format!("{}", iterations)
+        Some(format!("{}", iterations))
     }
 }
 
@@ -90,23 +137,22 @@ impl Problem for Solution {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part1_example() {
-        let input = "5483143223
-2745854711
-5264556173
-6141336146
-6357385478
-4167524645
-2176841721
-6882881134
-4846848554
-5283751526";
+    aoc_core::example_tests!(Solution {});
 
-        let mut grid = parse_input(input);
-        let mut n_flashes = (0..10).fold(0, |acc, _| acc + tick(&mut grid));
+    #[test]
+    fn test_tick() {
+        let mut grid = parse_input(EXAMPLE).unwrap();
+        let n_flashes = (0..10).fold(0, |acc, _| acc + tick(&mut grid));
         assert_eq!(n_flashes, 204);
-        n_flashes += (0..90).fold(0, |acc, _| acc + tick(&mut grid));
-        assert_eq!(n_flashes, 1656);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let e = parse_input(&EXAMPLE.replace("6141336146", "614133614x")).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (4, 10, "x"));
+        let e = parse_input(&EXAMPLE[..48]).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (5, 1, "6357"));
+        let e = parse_input(&EXAMPLE[..43]).unwrap_err();
+        assert_eq!((e.line, e.column), (4, 11));
     }
 }
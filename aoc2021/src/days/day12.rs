@@ -1,18 +1,37 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, ParseError, Problem, Rng};
 use std::collections::{HashMap, HashSet, VecDeque};
 
 pub struct Solution {}
 
-type AdjacencyList<'a> = HashMap<&'a str, Vec<&'a str>>;
+type AdjacencyList = HashMap<String, Vec<String>>;
 
-fn create_adj_map(input: &str) -> AdjacencyList {
-    let mut edges: HashMap<&str, Vec<&str>> = HashMap::new();
+fn is_big(cave: &str) -> bool {
+    cave.bytes().all(|b| b.is_ascii_uppercase())
+}
+
+fn create_adj_map(input: &str) -> Result<AdjacencyList, ParseError> {
+    let mut edges: AdjacencyList = HashMap::new();
     for l in input.lines() {
-        let (a, b) = l.split_once('-').unwrap();
-        edges.entry(a).or_insert(vec![]).push(b);
-        edges.entry(b).or_insert(vec![]).push(a);
+        let (a, b) = l
+            .split_once('-')
+            .ok_or_else(|| ParseError::at(input, l, "expected a tunnel like 'start-A'"))?;
+        for cave in [a, b] {
+            if cave.is_empty() || !(is_big(cave) || cave.bytes().all(|b| b.is_ascii_lowercase())) {
+                let message = "expected a cave named in all upper or lower case";
+                return Err(ParseError::at(input, cave, message));
+            }
+        }
+        // Paths could bounce between two big caves forever.
+        if is_big(a) && is_big(b) {
+            return Err(ParseError::at(input, l, "expected a small cave at one end"));
+        }
+        edges.entry(a.to_string()).or_default().push(b.to_string());
+        edges.entry(b.to_string()).or_default().push(a.to_string());
+    }
+    if !edges.contains_key("start") {
+        return Err(ParseError::at(input, &input[input.len()..], "expected a tunnel from start"));
     }
-    edges
+    Ok(edges)
 }
 
 fn count_unique_paths(edges: &AdjacencyList, part2: bool) -> usize {
@@ -31,19 +50,19 @@ fn count_unique_paths(edges: &AdjacencyList, part2: bool) -> usize {
             continue;
         }
 
-        for v in edges[name].iter() {
+        for v in edges[name].iter().map(String::as_str) {
             if !small_set.contains(v) {
                 let mut new_small_set = small_set.clone();
-                if v.to_ascii_lowercase() == *v {
+                if v.to_ascii_lowercase() == v {
                     new_small_set.insert(v);
                 }
                 queue.push_back((v, new_small_set, twice));
             } else if small_set.contains(v)
-                && twice == None
-                && !["start", "end"].contains(v)
+                && twice.is_none()
+                && !["start", "end"].contains(&v)
                 && part2
             {
-                queue.push_back((v, small_set.clone(), Some(*v)));
+                queue.push_back((v, small_set.clone(), Some(v)));
             }
         }
     }
@@ -52,22 +71,99 @@ fn count_unique_paths(edges: &AdjacencyList, part2: bool) -> usize {
 }
 
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
-        let edges = create_adj_map(input);
-        let paths = count_unique_paths(&edges, false);
+    type Input = AdjacencyList;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(create_adj_map(input)?)
+    }
+
+    fn part1(&self, edges: &Self::Input) -> Option<String> {
+        let paths = count_unique_paths(edges, false);
+
+        Some(format!("{}", paths))
+    }
 
-        format!("{}", paths)
+    fn part2(&self, edges: &Self::Input) -> Option<String> {
+        let paths = count_unique_paths(edges, true);
+
+        Some(format!("{}", paths))
     }
 
-    fn part2(&self, input: &str) -> String {
-        let edges = create_adj_map(input);
-        let paths = count_unique_paths(&edges, true);
+    /// A cave system of `size` caves besides `start` and `end`, 10 like a
+    /// real input by default and between 1 and 600. A third of them are big, and
+    /// no two big caves are connected, so the number of paths stays finite.
+    fn generate(&self, rng: &mut Rng, size: Option<usize>) -> Option<String> {
+        let n = size.unwrap_or(10).clamp(1, 600);
+        let mut names = (b'a'..=b'z')
+            .flat_map(|a| (b'a'..=b'z').map(move |b| format!("{}{}", a as char, b as char)))
+            .collect::<Vec<String>>();
+        rng.shuffle(&mut names);
+        names.truncate(n);
+        names
+            .iter_mut()
+            .take(n.div_ceil(3))
+            .for_each(|name| *name = name.to_ascii_uppercase());
+        rng.shuffle(&mut names);
+        names.insert(0, "start".to_string());
+        names.push("end".to_string());
+
+        let big = |i: usize| names[i].chars().all(|c| c.is_ascii_uppercase());
+        let mut edges = vec![];
+        let connect = |a: usize, b: usize, edges: &mut Vec<(usize, usize)>| {
+            let (a, b) = (a.min(b), a.max(b));
+            let allowed = a != b && !(big(a) && big(b)) && (a, b) != (0, names.len() - 1);
+            if allowed && !edges.contains(&(a, b)) {
+                edges.push((a, b));
+                return true;
+            }
+            false
+        };
+        // Attach every cave to an earlier one, then add as many loops.
+        for i in 1..names.len() {
+            while !connect(i, rng.below(i), &mut edges) {}
+        }
+        for _ in 0..n {
+            connect(rng.below(names.len()), rng.below(names.len()), &mut edges);
+        }
 
-        format!("{}", paths)
+        rng.shuffle(&mut edges);
+        let lines = edges
+            .into_iter()
+            .map(|(a, b)| {
+                if rng.chance(0.5) {
+                    format!("{}-{}", names[a], names[b])
+                } else {
+                    format!("{}-{}", names[b], names[a])
+                }
+            })
+            .collect::<Vec<String>>();
+        Some(lines.join("\n"))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        let e = create_adj_map("start-A\nA-b2").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 3, "b2"));
+        let e = create_adj_map("start-A\nA-B").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "A-B"));
+        let e = create_adj_map("A-end").unwrap_err();
+        assert_eq!((e.line, e.column), (1, 6));
+    }
+
+    #[test]
+    fn test_generate() {
+        let input = Solution {}.generate(&mut Rng::new(1), Some(6)).unwrap();
+        let edges = Solution {}.parse(&input).unwrap();
+        assert_eq!(edges.len(), 8);
+        let big = |cave: &str| cave.chars().all(|c| c.is_ascii_uppercase());
+        for (cave, next) in &edges {
+            assert!(!big(cave) || next.iter().all(|n| !big(n)));
+        }
+        assert_ne!(Solution {}.part1(&edges), Some("0".to_string()));
+    }
 }
@@ -1,45 +1,52 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Context, Example, Frame, ParseError, Problem};
 
 type Point = (i32, i32);
+type Fold = (char, i32);
 
 pub struct Solution {}
-fn parse_input(input: &str) -> (Vec<Point>, Vec<(&str, i32)>) {
-    let (dots, instr) = input.split_once("\n\n").unwrap();
-    let points: Vec<Point> = dots
+fn parse_input(input: &str) -> Result<(Vec<Point>, Vec<Fold>), ParseError> {
+    let (dots, instr) = input.split_once("\n\n").ok_or_else(|| {
+        ParseError::at(input, &input[input.len()..], "expected folds after the dots")
+    })?;
+    let points = dots
         .lines()
         .map(|s| {
-            let (a, b) = s.split_once(',').unwrap();
-            (a.parse().unwrap(), b.parse().unwrap())
+            let (a, b) = s
+                .split_once(',')
+                .ok_or_else(|| ParseError::at(input, s, "expected a dot like '6,10'"))?;
+            let a: u16 = ParseError::number(input, a)?;
+            let b: u16 = ParseError::number(input, b)?;
+            Ok((a.into(), b.into()))
         })
-        .collect();
+        .collect::<Result<Vec<Point>, ParseError>>()?;
 
-    let folds: Vec<(&str, i32)> = instr
+    let folds = instr
         .lines()
         .map(|l| {
-            l.split_ascii_whitespace()
-                .last()
-                .unwrap()
-                .split_once('=')
-                .unwrap()
+            let (axis, val) = match l.strip_prefix("fold along ").and_then(|f| f.split_once('=')) {
+                Some(("x", val)) => ('x', val),
+                Some(("y", val)) => ('y', val),
+                _ => return Err(ParseError::at(input, l, "expected a fold like 'fold along y=7'")),
+            };
+            Ok((axis, ParseError::number(input, val)?))
         })
-        .map(|(axis, val)| (axis, val.parse::<i32>().unwrap()))
-        .collect();
+        .collect::<Result<Vec<Fold>, ParseError>>()?;
 
-    (points, folds)
+    Ok((points, folds))
 }
 
-fn fold(points: Vec<Point>, fold: (&str, i32)) -> Vec<Point> {
+fn fold(points: Vec<Point>, fold: (char, i32)) -> Vec<Point> {
     let mut new_points = points
         .iter()
         .map(|c| match fold {
-            ("x", v) => {
+            ('x', v) => {
                 if c.0 > v {
                     (2 * v - c.0, c.1)
                 } else {
                     *c
                 }
             }
-            ("y", v) => {
+            ('y', v) => {
                 if c.1 > v {
                     (c.0, 2 * v - c.1)
                 } else {
@@ -62,41 +69,27 @@ fn display_points(points: Vec<Point>) -> String {
     let height = 6;
     for _ in 0..height {
         chars.push('\n');
-        for _ in 0..width {
-            chars.push(' ');
-        }
+        chars.extend(std::iter::repeat_n(' ', width as usize));
     }
 
     for (x, y) in points {
-        chars[(y * (width + 1) + x + y) as usize] = '█';
+        chars[(y * (width + 1) + 1 + x) as usize] = '█';
     }
 
     chars.iter().collect()
 }
 
-impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
-        let (points, folds) = parse_input(input);
-        let answer = fold(points, folds[0]).len();
-
-        format!("{}", answer)
-    }
-
-    fn part2(&self, input: &str) -> String {
-        let (points, folds) = parse_input(input);
-        let answer: Vec<Point> = folds.iter().fold(points, |acc, &f| fold(acc, f));
-
-        format!("\n{}", display_points(answer))
+fn draw_paper(points: &[Point], n_folds: usize) -> Frame {
+    let width = points.iter().map(|p| p.0 + 1).max().unwrap_or(0);
+    let height = points.iter().map(|p| p.1 + 1).max().unwrap_or(0);
+    let mut frame = Frame::new(width as usize, height as usize);
+    for &(x, y) in points {
+        frame.set(x as usize, y as usize, '#');
     }
+    frame.annotate(format!("{} folds, {} dots", n_folds, points.len()))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_part1_example() {
-        let input = "6,10
+const EXAMPLE: &str = "6,10
 0,14
 9,10
 0,3
@@ -117,9 +110,62 @@ mod tests {
 
 fold along y=7
 fold along x=5";
-        let (points, folds) = parse_input(input);
+
+impl Problem for Solution {
+    type Input = (Vec<Point>, Vec<(char, i32)>);
+
+    // The second part draws a square, which is not a code to check.
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("17"),
+            part2: None,
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(&self, (points, folds): &Self::Input) -> Option<String> {
+        let answer = fold(points.clone(), *folds.first()?).len();
+
+        Some(format!("{}", answer))
+    }
+
+    fn part2(&self, (points, folds): &Self::Input) -> Option<String> {
+        let ctx = Context::current();
+        ctx.frame(|| draw_paper(points, 0));
+        let answer: Vec<Point> = folds.iter().enumerate().fold(points.clone(), |acc, (i, &f)| {
+            let folded = fold(acc, f);
+            ctx.frame(|| draw_paper(&folded, i + 1));
+            folded
+        });
+
+        Some(format!("\n{}", display_points(answer)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_core::example_tests!(Solution {}, part1);
+
+    #[test]
+    fn test_fold() {
+        let (points, folds) = parse_input(EXAMPLE).unwrap();
         let answer = fold(points, folds[0]);
-        dbg!(&answer);
-        assert_eq!(answer.len(), 17);
+        assert!(answer.iter().all(|&(_, y)| y < 7));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let e = parse_input("6,10\n0,x\n\nfold along y=7").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 3, "x"));
+        let e = parse_input("6,10\n\nfold along z=7").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (3, 1, "fold along z=7"));
+        let e = parse_input("6,10\n0,3").unwrap_err();
+        assert_eq!((e.line, e.column), (2, 4));
     }
 }
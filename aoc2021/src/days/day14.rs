@@ -1,20 +1,51 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 use counter::Counter;
 use std::collections::HashMap;
 
 pub struct Solution {}
 
-fn create_rule_map(input: &str) -> HashMap<String, String> {
-    input
-        .lines()
-        .map(|l| l.split_once(" -> ").unwrap())
-        .map(|(a, b)| (a.to_string(), b.to_string()))
-        .collect::<HashMap<String, String>>()
+type Rules = HashMap<String, String>;
+
+fn is_elements(s: &str) -> bool {
+    s.bytes().all(|b| b.is_ascii_uppercase())
+}
+
+fn create_rule_map(input: &str, rules: &str) -> Result<Rules, ParseError> {
+    let mut map = HashMap::new();
+    for l in rules.lines() {
+        match l.split_once(" -> ") {
+            Some((a, b)) if a.len() == 2 && b.len() == 1 && is_elements(a) && is_elements(b) => {
+                map.insert(a.to_string(), b.to_string());
+            }
+            _ => return Err(ParseError::at(input, l, "expected a rule like 'CH -> B'")),
+        }
+    }
+
+    // Every pair an insertion makes needs a rule of its own.
+    for l in rules.lines() {
+        let (a, b, c) = (&l[..1], &l[1..2], &l[6..]);
+        if !map.contains_key(&(a.to_string() + c)) || !map.contains_key(&(c.to_string() + b)) {
+            return Err(ParseError::at(input, l, "expected rules for the pairs this makes"));
+        }
+    }
+    Ok(map)
 }
 
-fn parse_input(input: &str) -> (String, HashMap<String, String>) {
-    let (template, rules) = input.split_once("\n\n").unwrap();
-    (template.to_string(), create_rule_map(rules))
+fn parse_input(input: &str) -> Result<(String, Rules), ParseError> {
+    let (template, rules) = input.split_once("\n\n").ok_or_else(|| {
+        ParseError::at(input, &input[input.len()..], "expected rules after the template")
+    })?;
+    if template.len() < 2 || !is_elements(template) {
+        let message = "expected a template of 2 or more elements A-Z";
+        return Err(ParseError::at(input, template, message));
+    }
+    let rules = create_rule_map(input, rules)?;
+    for i in 0..template.len() - 1 {
+        if !rules.contains_key(&template[i..i + 2]) {
+            return Err(ParseError::at(input, &template[i..i + 2], "expected a rule for this pair"));
+        }
+    }
+    Ok((template.to_string(), rules))
 }
 
 fn build_string_solution(template: String, rules: &HashMap<String, String>) -> String {
@@ -61,26 +92,58 @@ fn count_bigrams_solution(
     letter_counts
 }
 
+const EXAMPLE: &str = "NNCB
+
+CH -> B
+HH -> N
+CB -> H
+NH -> C
+HB -> C
+HC -> B
+HN -> C
+NN -> C
+BH -> H
+NC -> B
+NB -> B
+BN -> B
+BB -> N
+BC -> B
+CC -> N
+CN -> C";
+
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
-        let (mut template, rules) = parse_input(input);
+    type Input = (String, HashMap<String, String>);
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("1588"),
+            part2: Some("2188189693529"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(&self, (template, rules): &Self::Input) -> Option<String> {
+        let mut template = template.clone();
         for _ in 0..10 {
-            template = build_string_solution(template, &rules);
+            template = build_string_solution(template, rules);
         }
 
         let counts = template.chars().collect::<Counter<_>>();
         let common = counts.most_common();
         let answer = common[0].1 - common[common.len() - 1].1;
 
-        format!("{}", answer)
+        Some(format!("{}", answer))
     }
 
-    fn part2(&self, input: &str) -> String {
-        let (template, rules) = parse_input(input);
-        let counts = count_bigrams_solution(template, &rules, 40);
+    fn part2(&self, (template, rules): &Self::Input) -> Option<String> {
+        let counts = count_bigrams_solution(template.clone(), rules, 40);
         let common = counts.most_common();
         let answer = common[0].1 - common[common.len() - 1].1;
-        format!("{}", answer)
+        Some(format!("{}", answer))
     }
 }
 
@@ -88,28 +151,11 @@ impl Problem for Solution {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part1_example() {
-        let input = "NNCB
-
-CH -> B
-HH -> N
-CB -> H
-NH -> C
-HB -> C
-HC -> B
-HN -> C
-NN -> C
-BH -> H
-NC -> B
-NB -> B
-BN -> B
-BB -> N
-BC -> B
-CC -> N
-CN -> C";
+    aoc_core::example_tests!(Solution {});
 
-        let (mut template, rules) = parse_input(input);
+    #[test]
+    fn test_build_string() {
+        let (mut template, rules) = parse_input(EXAMPLE).unwrap();
         template = build_string_solution(template, &rules);
         assert_eq!("NCNBCHB", template);
         template = build_string_solution(template, &rules);
@@ -124,30 +170,12 @@ CN -> C";
     }
 
     #[test]
-    fn test_part2_example() {
-        let input = "NNCB
-
-CH -> B
-HH -> N
-CB -> H
-NH -> C
-HB -> C
-HC -> B
-HN -> C
-NN -> C
-BH -> H
-NC -> B
-NB -> B
-BN -> B
-BB -> N
-BC -> B
-CC -> N
-CN -> C";
-
-        let (template, rules) = parse_input(input);
-        let counts = count_bigrams_solution(template, &rules, 40);
-        let common = counts.most_common();
-        let answer = common[0].1 - common[common.len() - 1].1;
-        assert_eq!(answer, 2188189693529);
+    fn test_parse_errors() {
+        let e = parse_input("NNCB\n\nNN -> C\nNC > B").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (4, 1, "NC > B"));
+        let e = parse_input("NNCB\n\nNN -> C\nNC -> B").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (3, 1, "NN -> C"));
+        let e = parse_input("NCN\n\nNC -> N\nNN -> N").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 2, "CN"));
     }
 }
@@ -1,4 +1,4 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::fmt;
@@ -6,7 +6,7 @@ use std::ops::Add;
 
 pub struct Solution {}
 
-#[derive(Eq, Hash, Copy, Clone)]
+#[derive(PartialEq, Eq, Hash, Copy, Clone)]
 struct Point {
     x: isize,
     y: isize,
@@ -28,12 +28,6 @@ impl Add for Point {
     }
 }
 
-impl PartialEq for Point {
-    fn eq(&self, other: &Self) -> bool {
-        self.x == other.x && self.y == other.y
-    }
-}
-
 #[derive(Hash, Eq, PartialEq, Copy, Clone)]
 struct Vertex {
     point: Point,
@@ -56,7 +50,6 @@ impl Vertex {
     }
 }
 
-#[derive(Hash)]
 struct Visit {
     vertex: Vertex,
     dist: usize,
@@ -103,7 +96,10 @@ fn parse_tiled_point_costs(
 ) -> HashMap<Point, usize> {
     let (tw, th) = repeats;
 
-    let new_input: Vec<String> = input.repeat(th).lines().map(|l| l.repeat(tw)).collect();
+    let new_input: Vec<String> = (0..th)
+        .flat_map(|_| input.lines())
+        .map(|l| l.repeat(tw))
+        .collect();
 
     new_input
         .iter()
@@ -169,7 +165,7 @@ fn dijkstras(
         if let Some(neighbors) = adj_list.get(&vertex) {
             for (neighbor, cost) in neighbors {
                 let new_distance = dist + cost;
-                let is_shorter = distances.get(neighbor).map_or(true, |&d| new_distance < d);
+                let is_shorter = distances.get(neighbor).is_none_or(|&d| new_distance < d);
                 if is_shorter {
                     distances.insert(*neighbor, new_distance);
                     to_visit.push(Visit {
@@ -184,7 +180,27 @@ fn dijkstras(
     distances
 }
 
-fn find_risk(input: &str, size: (usize, usize), repeats: (usize, usize)) -> usize {
+/// Check the input is a rectangle of risk levels 1 to 9.
+fn check_input(input: &str) -> Result<(), ParseError> {
+    let width = input.lines().next().map_or(0, |l| l.len());
+    if width == 0 {
+        return Err(ParseError::at(input, input, "expected a map of risk levels"));
+    }
+    for l in input.lines() {
+        if l.len() != width {
+            return Err(ParseError::at(input, l, format!("expected a row {} wide", width)));
+        }
+        if let Some((i, c)) = l.char_indices().find(|(_, c)| !('1'..='9').contains(c)) {
+            let text = &l[i..i + c.len_utf8()];
+            return Err(ParseError::at(input, text, "expected a risk level from 1 to 9"));
+        }
+    }
+    Ok(())
+}
+
+fn find_risk(input: &str, repeats: (usize, usize)) -> usize {
+    let width = input.lines().next().map_or(0, |l| l.len());
+    let size = (width, input.lines().count());
     let point_cost_map = parse_tiled_point_costs(input, size, repeats);
     let adj_map = create_adj_list(&point_cost_map, (size.0 * repeats.0, size.1 * repeats.1));
     let start = Vertex::from_xy(0, 0);
@@ -196,25 +212,7 @@ fn find_risk(input: &str, size: (usize, usize), repeats: (usize, usize)) -> usiz
     distances[&end]
 }
 
-impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
-        let risk = find_risk(input, (100, 100), (1, 1));
-        format!("{}", risk)
-    }
-
-    fn part2(&self, input: &str) -> String {
-        let risk = find_risk(input, (100, 100), (5, 5));
-        format!("{}", risk)
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_part1_example() {
-        let input = "1163751742
+const EXAMPLE: &str = "1163751742
 1381373672
 2136511328
 3694931569
@@ -225,24 +223,44 @@ mod tests {
 1293138521
 2311944581";
 
-        let risk = find_risk(input, (10, 10), (1, 1));
-        assert_eq!(risk, 40);
+impl Problem for Solution {
+    type Input = String;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("40"),
+            part2: Some("315"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        check_input(input)?;
+        Ok(input.to_string())
     }
 
-    #[test]
-    fn test_part2_example() {
-        let input = "1163751742
-1381373672
-2136511328
-3694931569
-7463417111
-1319128137
-1359912421
-3125421639
-1293138521
-2311944581\n";
+    fn part1(&self, input: &Self::Input) -> Option<String> {
+        let risk = find_risk(input, (1, 1));
+        Some(format!("{}", risk))
+    }
+
+    fn part2(&self, input: &Self::Input) -> Option<String> {
+        let risk = find_risk(input, (5, 5));
+        Some(format!("{}", risk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let risk = find_risk(input, (10, 10), (5, 5));
-        assert_eq!(risk, 315);
+    aoc_core::example_tests!(Solution {});
+
+    #[test]
+    fn test_parse_errors() {
+        let e = check_input("1163\n1081").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 2, "0"));
+        let e = check_input("1163\n108").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "108"));
     }
 }
@@ -1,9 +1,9 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem, Rng};
 
 pub struct Solution {}
 
 #[derive(Clone, Debug)]
-enum Packet {
+pub enum Packet {
     Op(Operator),
     Lit(Literal),
 }
@@ -15,14 +15,14 @@ struct Header {
 }
 
 #[derive(Clone, Debug)]
-struct Literal {
+pub struct Literal {
     header: Header,
     value: u64,
     size: usize,
 }
 
 #[derive(Clone, Debug)]
-struct Operator {
+pub struct Operator {
     header: Header,
     children: Vec<Packet>,
     size: usize,
@@ -34,57 +34,89 @@ fn cast_to_u64(bits: &[u8]) -> u64 {
         .fold(0, |acc, (_, &b)| acc * 2 + (b as u64))
 }
 
-fn parse(message: &str) -> Packet {
-    parse_packet(&parse_message(message))
+/// Deeper operators than this are rejected rather than risk the stack.
+const MAX_DEPTH: usize = 256;
+
+/// A message about the bit at an offset into the packet being decoded.
+type BitError = (usize, String);
+
+/// Parse the transmission on the first line of `message`. Bits after the
+/// outermost packet are padding and ignored.
+pub fn parse(message: &str) -> Result<Packet, ParseError> {
+    let line = first_line(message);
+    parse_packet(&parse_message(message)?, 0).map_err(|(bit, e)| {
+        // Each hex character holds four bits, past the end for a message
+        // that stops short.
+        let at = (bit / 4).min(line.len());
+        ParseError::at(message, &line[at..(at + 1).min(line.len())], e)
+    })
 }
 
-fn parse_header(bits: &[u8]) -> Header {
-    Header {
-        version: cast_to_u64(&bits[0..3]),
-        type_id: cast_to_u64(&bits[3..6]),
-    }
+fn first_line(message: &str) -> &str {
+    message.lines().next().unwrap_or(message).trim_end()
+}
+
+/// The bits from `start` to `start + len`, as a number.
+fn read(bits: &[u8], start: usize, len: usize) -> Result<u64, BitError> {
+    bits.get(start..start + len)
+        .map(cast_to_u64)
+        .ok_or_else(|| (bits.len(), "message ends in the middle of a packet".to_string()))
 }
 
-fn parse_packet(bits: &[u8]) -> Packet {
-    match cast_to_u64(&bits[3..6]) {
+fn parse_header(bits: &[u8]) -> Result<Header, BitError> {
+    Ok(Header {
+        version: read(bits, 0, 3)?,
+        type_id: read(bits, 3, 3)?,
+    })
+}
+
+fn parse_packet(bits: &[u8], depth: usize) -> Result<Packet, BitError> {
+    match read(bits, 3, 3)? {
         4 => parse_literal(bits),
-        _ => parse_operator(bits),
+        _ => parse_operator(bits, depth),
     }
 }
 
-fn parse_literal(bits: &[u8]) -> Packet {
+fn parse_literal(bits: &[u8]) -> Result<Packet, BitError> {
     let mut value = vec![];
-    let mut index = 0;
-    let data = &bits[6..];
+    let mut index = 6;
 
-    while index <= data.len() - 5 {
-        let chunk = &data[index..index + 5];
-        let cont = chunk[0];
+    loop {
+        let chunk = bits
+            .get(index..index + 5)
+            .ok_or_else(|| (bits.len(), "message ends in the middle of a literal".to_string()))?;
         value.extend(&chunk[1..5]);
 
+        if value.len() > 64 {
+            return Err((index, "literal value does not fit in 64 bits".to_string()));
+        }
         index += 5;
-
-        if cont == 0 {
+        if chunk[0] == 0 {
             break;
         }
     }
 
-    Packet::Lit(Literal {
-        header: parse_header(bits),
+    Ok(Packet::Lit(Literal {
+        header: parse_header(bits)?,
         value: cast_to_u64(&value),
-        size: 6 + index,
-    })
+        size: index,
+    }))
 }
 
-fn parse_operator(bits: &[u8]) -> Packet {
-    let length_type_id = bits[6];
+fn parse_operator(bits: &[u8], depth: usize) -> Result<Packet, BitError> {
+    if depth >= MAX_DEPTH {
+        return Err((0, format!("operators nested more than {} deep", MAX_DEPTH)));
+    }
+    let header = parse_header(bits)?;
+    let length_type_id = read(bits, 6, 1)?;
     let offset = 7 + if length_type_id == 0 { 15 } else { 11 };
-    let size = cast_to_u64(&bits[7..offset]) as usize;
+    let size = read(bits, 7, offset - 7)? as usize;
 
     let mut index = 0;
     let mut children = vec![];
     while (length_type_id == 0 && index < size) || (length_type_id == 1 && children.len() < size) {
-        let packet = parse_packet(&bits[(offset + index)..]);
+        let start = offset + index;
+        let packet = parse_packet(&bits[start..], depth + 1).map_err(|(bit, e)| (start + bit, e))?;
         index += match &packet {
             Packet::Lit(data) => data.size,
             Packet::Op(data) => data.size,
@@ -93,39 +125,174 @@ fn parse_operator(bits: &[u8]) -> Packet {
         children.push(packet);
     }
 
-    Packet::Op(Operator {
-        header: parse_header(bits),
+    if length_type_id == 0 && index != size {
+        return Err((
+            7,
+            format!("sub-packets take {} bits, not the {} given", index, size),
+        ));
+    }
+    match (header.type_id, children.len()) {
+        (5..=7, 2) | (0..=3, 1..) => {}
+        (5..=7, n) => return Err((0, format!("comparisons need 2 sub-packets, not {}", n))),
+        _ => return Err((0, "operators need at least one sub-packet".to_string())),
+    }
+
+    Ok(Packet::Op(Operator {
+        header,
         children,
         size: offset + index,
-    })
+    }))
+}
+
+fn parse_message(message: &str) -> Result<Vec<u8>, ParseError> {
+    let line = first_line(message);
+    if line.is_empty() {
+        return Err(ParseError::at(message, line, "empty message"));
+    }
+
+    let mut bits = Vec::with_capacity(line.len() * 4);
+    for (i, c) in line.char_indices() {
+        let digit = match c {
+            '0'..='9' | 'A'..='F' => c.to_digit(16).unwrap() as u8,
+            c => {
+                let text = &line[i..i + c.len_utf8()];
+                return Err(ParseError::at(message, text, "expected a hex character"));
+            }
+        };
+        bits.extend((0..4).rev().map(|i| (digit >> i) & 1));
+    }
+    Ok(bits)
+}
+
+fn push_bits(bits: &mut Vec<u8>, value: u64, len: usize) {
+    bits.extend((0..len).rev().map(|i| ((value >> i) & 1) as u8));
+}
+
+fn encode_packet(packet: &Packet, bits: &mut Vec<u8>) {
+    match packet {
+        Packet::Lit(data) => {
+            push_bits(bits, data.header.version, 3);
+            push_bits(bits, data.header.type_id, 3);
+            let groups = (64 - data.value.leading_zeros() as usize).max(1).div_ceil(4);
+            for group in (0..groups).rev() {
+                push_bits(bits, (group > 0) as u64, 1);
+                push_bits(bits, data.value >> (group * 4), 4);
+            }
+        }
+        Packet::Op(data) => {
+            push_bits(bits, data.header.version, 3);
+            push_bits(bits, data.header.type_id, 3);
+            if data.children.len() < 1 << 11 {
+                push_bits(bits, 1, 1);
+                push_bits(bits, data.children.len() as u64, 11);
+                data.children.iter().for_each(|c| encode_packet(c, bits));
+            } else {
+                let mut children = vec![];
+                data.children
+                    .iter()
+                    .for_each(|c| encode_packet(c, &mut children));
+                assert!(children.len() < 1 << 15, "sub-packets too long to encode");
+                push_bits(bits, 0, 1);
+                push_bits(bits, children.len() as u64, 15);
+                bits.extend(children);
+            }
+        }
+    }
+}
+
+/// Print `packet` as a hex transmission, with literals in as few groups as
+/// possible and sub-packets counted rather than measured where they fit.
+pub fn encode(packet: &Packet) -> String {
+    let mut bits = vec![];
+    encode_packet(packet, &mut bits);
+    to_hex(&bits)
 }
 
-fn parse_message(message: &str) -> Vec<u8> {
-    message
-        .lines()
-        .next()
-        .unwrap()
-        .chars()
-        .flat_map(|c| match c {
-            '0' => [0, 0, 0, 0],
-            '1' => [0, 0, 0, 1],
-            '2' => [0, 0, 1, 0],
-            '3' => [0, 0, 1, 1],
-            '4' => [0, 1, 0, 0],
-            '5' => [0, 1, 0, 1],
-            '6' => [0, 1, 1, 0],
-            '7' => [0, 1, 1, 1],
-            '8' => [1, 0, 0, 0],
-            '9' => [1, 0, 0, 1],
-            'A' => [1, 0, 1, 0],
-            'B' => [1, 0, 1, 1],
-            'C' => [1, 1, 0, 0],
-            'D' => [1, 1, 0, 1],
-            'E' => [1, 1, 1, 0],
-            'F' => [1, 1, 1, 1],
-            c => panic!("expected hex character, found {}", c),
+/// The hex digits for `bits`, with the last one padded with zeros.
+fn to_hex(bits: &[u8]) -> String {
+    bits.chunks(4)
+        .map(|nibble| {
+            let digit = nibble.iter().fold(0, |acc, &b| acc * 2 + b as u32);
+            let digit = digit << (4 - nibble.len());
+            char::from_digit(digit, 16).unwrap().to_ascii_uppercase()
         })
-        .collect::<Vec<u8>>()
+        .collect()
+}
+
+/// Bounds generated values so that evaluating them cannot overflow.
+const MAX_VALUE: u64 = 1 << 48;
+
+/// A random packet of `size` packets in all, with its value. Operators only
+/// get a type whose result stays within [`MAX_VALUE`].
+fn generate_packet(rng: &mut Rng, size: usize, depth: usize) -> (Packet, u64) {
+    let version = rng.below(8) as u64;
+    if size == 1 {
+        let value = if rng.chance(0.9) {
+            rng.below(256) as u64
+        } else {
+            rng.next_u64() >> 32
+        };
+        let groups = (64 - value.leading_zeros() as usize).max(1).div_ceil(4);
+        let literal = Literal {
+            header: Header { version, type_id: 4 },
+            value,
+            size: 6 + 5 * groups,
+        };
+        return (Packet::Lit(literal), value);
+    }
+
+    // Share the other packets out between the sub-packets, spreading them
+    // wide once deep enough, within what a sub-packet count can hold.
+    let count = if depth >= 20 {
+        (size - 1).min(2047)
+    } else if size > 2 && rng.chance(0.5) {
+        2
+    } else {
+        1 + rng.below((size - 1).min(5))
+    };
+    let mut sizes = vec![1; count];
+    for _ in count..size - 1 {
+        sizes[rng.below(count)] += 1;
+    }
+    let (children, values): (Vec<Packet>, Vec<u64>) = sizes
+        .into_iter()
+        .map(|size| generate_packet(rng, size, depth + 1))
+        .unzip();
+
+    let bounded = |v: Option<u64>| v.filter(|&v| v <= MAX_VALUE);
+    let sum = bounded(values.iter().try_fold(0u64, |acc, &v| acc.checked_add(v)));
+    let product = bounded(values.iter().try_fold(1u64, |acc, &v| acc.checked_mul(v)));
+    let mut types = vec![
+        (0, sum),
+        (1, product),
+        (2, values.iter().min().copied()),
+        (3, values.iter().max().copied()),
+    ];
+    // The outermost packet combines values, as in real inputs.
+    if let (1.., &[a, b]) = (depth, &values[..]) {
+        types.push((5, Some((a > b) as u64)));
+        types.push((6, Some((a < b) as u64)));
+        types.push((7, Some((a == b) as u64)));
+    }
+    types.retain(|(_, value)| value.is_some());
+    let (type_id, value) = *rng.choose(&types);
+
+    // Encoded with the sub-packet count, as `encode` does.
+    let size = 7
+        + 11
+        + children
+            .iter()
+            .map(|child| match child {
+                Packet::Lit(data) => data.size,
+                Packet::Op(data) => data.size,
+            })
+            .sum::<usize>();
+    let operator = Operator {
+        header: Header { version, type_id },
+        children,
+        size,
+    };
+    (Packet::Op(operator), value.unwrap())
 }
 
 fn add_version_numbers(packet: &Packet) -> u64 {
@@ -184,16 +351,89 @@ fn evaluate(packet: &Packet) -> u64 {
 }
 
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
-        let packet = parse(input);
-        let answer = add_version_numbers(&packet);
-        format!("{}", answer)
+    type Input = Packet;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: "8A004A801A8002F478",
+            part1: Some("16"),
+            part2: None,
+        },
+        Example {
+            input: "620080001611562C8802118E34",
+            part1: Some("12"),
+            part2: None,
+        },
+        Example {
+            input: "C0015000016115A2E0802F182340",
+            part1: Some("23"),
+            part2: None,
+        },
+        Example {
+            input: "A0016C880162017C3686B18A3D4780",
+            part1: Some("31"),
+            part2: None,
+        },
+        Example {
+            input: "C200B40A82",
+            part1: None,
+            part2: Some("3"),
+        },
+        Example {
+            input: "04005AC33890",
+            part1: None,
+            part2: Some("54"),
+        },
+        Example {
+            input: "880086C3E88112",
+            part1: None,
+            part2: Some("7"),
+        },
+        Example {
+            input: "CE00C43D881120",
+            part1: None,
+            part2: Some("9"),
+        },
+        Example {
+            input: "D8005AC2A8F0",
+            part1: None,
+            part2: Some("1"),
+        },
+        Example {
+            input: "F600BC2D8F",
+            part1: None,
+            part2: Some("0"),
+        },
+        Example {
+            input: "9C005AC2F8F0",
+            part1: None,
+            part2: Some("0"),
+        },
+        Example {
+            input: "9C0141080250320F1802104A08",
+            part1: None,
+            part2: Some("1"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse(input)?)
     }
 
-    fn part2(&self, input: &str) -> String {
-        let packet = parse(input);
-        let answer = evaluate(&packet);
-        format!("{}", answer)
+    fn part1(&self, packet: &Self::Input) -> Option<String> {
+        let answer = add_version_numbers(packet);
+        Some(format!("{}", answer))
+    }
+
+    fn part2(&self, packet: &Self::Input) -> Option<String> {
+        let answer = evaluate(packet);
+        Some(format!("{}", answer))
+    }
+
+    /// A transmission of `size` packets, 250 like a real input by default.
+    fn generate(&self, rng: &mut Rng, size: Option<usize>) -> Option<String> {
+        let (packet, _) = generate_packet(rng, size.unwrap_or(250).max(1), 0);
+        Some(encode(&packet))
     }
 }
 
@@ -203,7 +443,7 @@ mod tests {
 
     #[test]
     fn test_hex_to_bin() {
-        assert_eq!(parse_message("ABC\n"), [1, 0, 1, 0, 1, 0, 1, 1, 1, 1, 0, 0]);
+        assert_eq!(parse_message("ABC\n").unwrap(), [1, 0, 1, 0, 1, 0, 1, 1, 1, 1, 0, 0]);
     }
 
     #[test]
@@ -212,55 +452,105 @@ mod tests {
         assert_eq!(cast_to_u64(&[0, 0, 0, 1, 0, 1]), 5);
     }
 
-    #[test]
-    fn test_part1_operator_packet_v4() {
-        let input = "8A004A801A8002F478";
-        let packet = parse(input);
-        assert_eq!(16, add_version_numbers(&packet));
-    }
+    aoc_core::example_tests!(Solution {});
 
     #[test]
-    fn test_part1_operator_packet_v3() {
-        let input = "620080001611562C8802118E34";
-        let packet = parse(input);
-        assert_eq!(12, add_version_numbers(&packet));
+    fn test_encode() {
+        assert_eq!(encode(&parse("D2FE28").unwrap()), "D2FE28");
+        for input in ["38006F45291200", "EE00D40C823060", "9C0141080250320F1802104A08"] {
+            let encoded = encode(&parse(input).unwrap());
+            assert_eq!(encode(&parse(&encoded).unwrap()), encoded);
+            assert_eq!(
+                evaluate(&parse(&encoded).unwrap()),
+                evaluate(&parse(input).unwrap())
+            );
+        }
     }
 
     #[test]
-    fn test_part1_operator_packet_length_type() {
-        let input = "C0015000016115A2E0802F182340";
-        let packet = parse(input);
-        assert_eq!(23, add_version_numbers(&packet));
+    fn test_parse_errors() {
+        let at = |message: &str| {
+            let e = parse(message).unwrap_err();
+            (e.line, e.column, e.text)
+        };
+        assert_eq!(at(""), (1, 1, "".to_string()));
+        assert_eq!(at("D2FE2G"), (1, 6, "G".to_string()));
+        // Truncated literal and operator.
+        assert_eq!(at("D2F"), (1, 4, "".to_string()));
+        assert_eq!(at("EE00D40C82\n"), (1, 11, "".to_string()));
+        // Seventeen literal groups, the last starting at bit 86.
+        let mut bits = vec![];
+        push_bits(&mut bits, 4, 6);
+        for _ in 0..16 {
+            push_bits(&mut bits, 0b11111, 5);
+        }
+        push_bits(&mut bits, 0, 5);
+        assert_eq!(at(&to_hex(&bits)), (1, 22, "C".to_string()));
+        // A comparison with a single sub-packet, alone and inside a sum that
+        // puts it at bit 18.
+        let comparison = |bits: &mut Vec<u8>| {
+            push_bits(bits, 5, 6);
+            push_bits(bits, 1, 1);
+            push_bits(bits, 1, 11);
+            push_bits(bits, 4, 6);
+            push_bits(bits, 1, 5);
+        };
+        let mut bits = vec![];
+        comparison(&mut bits);
+        assert_eq!(at(&to_hex(&bits)), (1, 1, "1".to_string()));
+        let mut bits = vec![];
+        push_bits(&mut bits, 0, 6);
+        push_bits(&mut bits, 1, 1);
+        push_bits(&mut bits, 1, 11);
+        comparison(&mut bits);
+        assert_eq!(at(&to_hex(&bits)), (1, 5, "4".to_string()));
+        // The first example without its last literal group.
+        assert_eq!(at("8A004A801A8002F4"), (1, 17, "".to_string()));
+        assert!(Solution {}.parse("8A004A801A8002F4").is_err());
     }
 
     #[test]
-    fn test_part1_operator_packet_nested() {
-        let input = "A0016C880162017C3686B18A3D4780";
-        let packet = parse(input);
-        assert_eq!(31, add_version_numbers(&packet));
+    fn test_parse_depth() {
+        // `levels` sum operators with one sub-packet each, around a literal.
+        let nested = |levels: usize| {
+            let mut bits = vec![];
+            for _ in 0..levels {
+                push_bits(&mut bits, 0, 6);
+                push_bits(&mut bits, 1, 1);
+                push_bits(&mut bits, 1, 11);
+            }
+            push_bits(&mut bits, 4, 6);
+            push_bits(&mut bits, 1, 5);
+            to_hex(&bits)
+        };
+        assert_eq!(evaluate(&parse(&nested(MAX_DEPTH)).unwrap()), 1);
+        assert!(parse(&nested(MAX_DEPTH + 1)).is_err());
+        // Far deeper than the stack could take.
+        assert!(parse(&nested(200_000)).is_err());
     }
 
     #[test]
-    fn test_part2_add() {
-        assert_eq!(3, evaluate(&parse("C200B40A82")), "Testing add: 1+2");
-        assert_eq!(54, evaluate(&parse("04005AC33890")), "Testing mul: 6*9");
-        assert_eq!(
-            7,
-            evaluate(&parse("880086C3E88112")),
-            "Testing min: min(7,8,9)"
-        );
-        assert_eq!(
-            9,
-            evaluate(&parse("CE00C43D881120")),
-            "Testing max: max(7,8,9)"
-        );
-        assert_eq!(1, evaluate(&parse("D8005AC2A8F0")), "Testing lt: 5<15");
-        assert_eq!(0, evaluate(&parse("F600BC2D8F")), "Testing gt: 5>15");
-        assert_eq!(0, evaluate(&parse("9C005AC2F8F0")), "Testing eq: 5==15");
-        assert_eq!(
-            1,
-            evaluate(&parse("9C0141080250320F1802104A08")),
-            "Testing all: 1*3==2*2"
-        );
+    fn test_generate() {
+        for seed in 0..50 {
+            let (packet, value) = generate_packet(&mut Rng::new(seed), 100, 0);
+            assert_eq!(evaluate(&packet), value);
+            fn count(packet: &Packet) -> usize {
+                match packet {
+                    Packet::Lit(_) => 1,
+                    Packet::Op(data) => 1 + data.children.iter().map(count).sum::<usize>(),
+                }
+            }
+            assert_eq!(count(&packet), 100);
+
+            let parsed = parse(&encode(&packet)).unwrap();
+            assert_eq!(encode(&parsed), encode(&packet));
+            let size = |p: &Packet| match p {
+                Packet::Lit(data) => data.size,
+                Packet::Op(data) => data.size,
+            };
+            assert_eq!(size(&parsed), size(&packet));
+        }
+        let input = Solution {}.generate(&mut Rng::new(1), None).unwrap();
+        assert!(Solution {}.parse(&input).is_ok());
     }
 }
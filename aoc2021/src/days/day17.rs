@@ -1,8 +1,8 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 
 pub struct Solution {}
 
-struct Region {
+pub struct Region {
     x0: isize,
     y0: isize,
     x1: isize,
@@ -19,18 +19,36 @@ impl Region {
     }
 }
 
-fn parse_target(input: &str) -> Region {
-    let bounds: Vec<isize> = input.lines().next().unwrap()[13..]
-        .split(", ")
-        .flat_map(|s| {
-            s[2..]
-                .split("..")
-                .map(|n| n.parse::<isize>().expect("could not parse target value"))
-                .collect::<Vec<isize>>()
-        })
-        .collect();
-
-    Region::new(bounds[0], bounds[2], bounds[1], bounds[3])
+fn parse_range(input: &str, text: &str) -> Result<(isize, isize), ParseError> {
+    let (a, b) = text
+        .split_once("..")
+        .ok_or_else(|| ParseError::at(input, text, "expected a range like '20..30'"))?;
+    let (a, b) = (ParseError::number(input, a)?, ParseError::number(input, b)?);
+    if a > b {
+        return Err(ParseError::at(input, text, "expected the lower bound first"));
+    }
+    Ok((a, b))
+}
+
+fn parse_target(input: &str) -> Result<Region, ParseError> {
+    let (xs, ys) = input
+        .strip_prefix("target area: x=")
+        .and_then(|t| t.split_once(", y="))
+        .ok_or_else(|| {
+            let message = "expected a target like 'target area: x=20..30, y=-10..-5'";
+            ParseError::at(input, input, message)
+        })?;
+    let (x0, x1) = parse_range(input, xs)?;
+    let (y0, y1) = parse_range(input, ys)?;
+
+    // The search only aims right and down.
+    if x0 <= 0 {
+        return Err(ParseError::at(input, xs, "expected a target to the right"));
+    }
+    if y1 >= 0 {
+        return Err(ParseError::at(input, ys, "expected a target below"));
+    }
+    Ok(Region::new(x0, y0, x1, y1))
 }
 
 fn compute_initial_x_velocities(target: &Region) -> Vec<isize> {
@@ -69,16 +87,15 @@ fn evaluate_candidate_trajectory(
     }
 }
 
-fn get_optimal_initial_velocity(input: &str) -> ((isize, isize), (isize, isize)) {
-    let target = parse_target(input);
-    let vxs = compute_initial_x_velocities(&target);
+fn get_optimal_initial_velocity(target: &Region) -> ((isize, isize), (isize, isize)) {
+    let vxs = compute_initial_x_velocities(target);
 
     let mut best = ((isize::MIN, isize::MIN), (0, 0));
     for vx in vxs {
         let mut vy = target.y1;
         let mut iterations = 0;
         while (iterations + 1) / 2 < -target.y0 {
-            best = match evaluate_candidate_trajectory(&target, vx, vy) {
+            best = match evaluate_candidate_trajectory(target, vx, vy) {
                 Some((p, v)) => {
                     if p.1 > best.0 .1 {
                         (p, v)
@@ -96,9 +113,8 @@ fn get_optimal_initial_velocity(input: &str) -> ((isize, isize), (isize, isize))
     best
 }
 
-fn get_all_initial_velocities(input: &str) -> Vec<((isize, isize), (isize, isize))> {
-    let target = parse_target(input);
-    let vxs = compute_initial_x_velocities(&target);
+fn get_all_initial_velocities(target: &Region) -> Vec<((isize, isize), (isize, isize))> {
+    let vxs = compute_initial_x_velocities(target);
 
     let mut candidates = vec![];
     for vx0 in vxs {
@@ -108,7 +124,7 @@ fn get_all_initial_velocities(input: &str) -> Vec<((isize, isize), (isize, isize
             || (vy0 < 0 && (iterations <= -target.y0))
             || (vy0 == 0 && (iterations + 1) <= -target.y0)
         {
-            if let Some(c) = evaluate_candidate_trajectory(&target, vx0, vy0) {
+            if let Some(c) = evaluate_candidate_trajectory(target, vx0, vy0) {
                 candidates.push(c);
             }
 
@@ -119,15 +135,31 @@ fn get_all_initial_velocities(input: &str) -> Vec<((isize, isize), (isize, isize
     candidates
 }
 
+const EXAMPLE: &str = "target area: x=20..30, y=-10..-5";
+
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
-        let highest_point = get_optimal_initial_velocity(input);
-        format!("{}", highest_point.0 .1)
+    type Input = Region;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("45"),
+            part2: Some("112"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_target(input)?)
+    }
+
+    fn part1(&self, target: &Self::Input) -> Option<String> {
+        let highest_point = get_optimal_initial_velocity(target);
+        Some(format!("{}", highest_point.0 .1))
     }
 
-    fn part2(&self, input: &str) -> String {
-        let candidates = get_all_initial_velocities(input);
-        format!("{}", candidates.len())
+    fn part2(&self, target: &Self::Input) -> Option<String> {
+        let candidates = get_all_initial_velocities(target);
+        Some(format!("{}", candidates.len()))
     }
 }
 
@@ -135,17 +167,21 @@ impl Problem for Solution {
 mod tests {
     use super::*;
 
+    aoc_core::example_tests!(Solution {});
+
     #[test]
-    fn test_part1_example() {
-        let input = "target area: x=20..30, y=-10..-5";
-        let (p, v) = get_optimal_initial_velocity(input);
-        assert_eq!(45, p.1, "incorrect height");
+    fn test_optimal_velocity() {
+        let (_, v) = get_optimal_initial_velocity(&parse_target(EXAMPLE).unwrap());
         assert_eq!((6, 9), v, "incorrect velocity");
     }
+
     #[test]
-    fn test_part2_example() {
-        let input = "target area: x=20..30, y=-10..-5";
-        let candidates = get_all_initial_velocities(input);
-        assert_eq!(112, candidates.len(), "incorrect number of candidates");
+    fn test_parse_errors() {
+        let e = parse_target("target area: x=20..3o, y=-10..-5").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 20, "3o"));
+        let e = parse_target("target area: x=20..30, y=-10..5").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 26, "-10..5"));
+        let e = parse_target("target area: x=20..30").err().unwrap();
+        assert_eq!((e.line, e.column), (1, 1));
     }
 }
@@ -1,23 +1,85 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, ParseError, Problem};
 use std::cmp;
 
 pub struct Solution {}
 
-type Expr = Vec<(usize, u32)>;
-
-fn parse_expr(expr: &str) -> Expr {
-    expr.as_bytes()
-        .iter()
-        .fold((0, vec![]), |(mut d, mut numbers), b| {
-            match b {
-                b'[' => d += 1,
-                b']' => d -= 1,
-                b'0'..=b'9' => numbers.push((d, (b - b'0') as u32)),
-                _ => {}
+pub type Expr = Vec<(usize, u32)>;
+
+/// Numbers in the input are reduced, so pairs nest at most this deep.
+const MAX_DEPTH: usize = 4;
+
+/// The byte offset of a parse error and what was expected there.
+type Error = (usize, &'static str);
+
+fn parse_element(
+    expr: &[u8],
+    pos: &mut usize,
+    depth: usize,
+    numbers: &mut Expr,
+) -> Result<(), Error> {
+    match expr.get(*pos) {
+        Some(b'[') if depth == MAX_DEPTH => Err((*pos, "pairs nested more than 4 deep")),
+        Some(b'[') => {
+            *pos += 1;
+            parse_element(expr, pos, depth + 1, numbers)?;
+            if expr.get(*pos) != Some(&b',') {
+                return Err((*pos, "expected ','"));
             }
-            (d, numbers)
-        })
-        .1
+            *pos += 1;
+            parse_element(expr, pos, depth + 1, numbers)?;
+            if expr.get(*pos) != Some(&b']') {
+                return Err((*pos, "expected ']'"));
+            }
+            *pos += 1;
+            Ok(())
+        }
+        Some(b @ b'0'..=b'9') => {
+            numbers.push((depth, (b - b'0') as u32));
+            *pos += 1;
+            Ok(())
+        }
+        _ => Err((*pos, "expected '[' or a digit")),
+    }
+}
+
+/// Parse a snailfish number into its regular numbers and their depths.
+pub fn parse_expr(expr: &str) -> Result<Expr, Error> {
+    let bytes = expr.as_bytes();
+    let mut numbers = vec![];
+    let mut pos = 0;
+    if bytes.first() != Some(&b'[') {
+        return Err((0, "expected '['"));
+    }
+    parse_element(bytes, &mut pos, 0, &mut numbers)?;
+    if pos < bytes.len() {
+        return Err((pos, "unexpected data after the number"));
+    }
+    Ok(numbers)
+}
+
+#[cfg(any(test, feature = "fuzzing"))]
+fn format_element(expr: &Expr, i: &mut usize, depth: usize, out: &mut String) {
+    match expr.get(*i) {
+        Some(&(d, value)) if d == depth => {
+            out.push_str(&value.to_string());
+            *i += 1;
+        }
+        _ => {
+            out.push('[');
+            format_element(expr, i, depth + 1, out);
+            out.push(',');
+            format_element(expr, i, depth + 1, out);
+            out.push(']');
+        }
+    }
+}
+
+/// Print `expr` as the snailfish number it was parsed from.
+#[cfg(any(test, feature = "fuzzing"))]
+pub fn format_expr(expr: &Expr) -> String {
+    let mut out = String::new();
+    format_element(expr, &mut 0, 0, &mut out);
+    out
 }
 
 fn reduce_expr(expr: &mut Expr, prev_i: usize) {
@@ -47,7 +109,7 @@ fn reduce_expr(expr: &mut Expr, prev_i: usize) {
             // split
             let (_, r) = expr[i];
             *expr.get_mut(i).unwrap() = (d + 1, l / 2);
-            expr.insert(i + 1, (d + 1, (r + 1) / 2));
+            expr.insert(i + 1, (d + 1, r.div_ceil(2)));
             reduce_expr(expr, i) // restart
         }
     }
@@ -78,8 +140,30 @@ fn magnitude(expr: &Expr) -> u32 {
 }
 
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
-        let expressions: Vec<Expr> = input.lines().map(parse_expr).collect();
+    type Input = Vec<Expr>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        let expressions: Vec<Expr> = input
+            .lines()
+            .map(|l| {
+                parse_expr(l).map_err(|(pos, message)| {
+                    // The offending byte, or the rest of the line if it starts a
+                    // wider character.
+                    let text = l.get(pos..pos + 1).unwrap_or(&l[pos.min(l.len())..]);
+                    ParseError::at(input, text, message)
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        // Part 1 starts from the first number and part 2 adds two different ones.
+        if expressions.len() < 2 {
+            let end = &input[input.len()..];
+            let message = "expected at least two snailfish numbers";
+            return Err(ParseError::at(input, end, message).into());
+        }
+        Ok(expressions)
+    }
+
+    fn part1(&self, expressions: &Self::Input) -> Option<String> {
         let mut expr = expressions[0].clone();
         for e in expressions.iter().skip(1) {
             add_expr(&mut expr, e);
@@ -88,11 +172,10 @@ impl Problem for Solution {
 
         let answer = magnitude(&expr);
 
-        format!("{}", answer)
+        Some(format!("{}", answer))
     }
 
-    fn part2(&self, input: &str) -> String {
-        let expressions: Vec<Expr> = input.lines().map(parse_expr).collect();
+    fn part2(&self, expressions: &Self::Input) -> Option<String> {
         let mut max = u32::MIN;
         for l in 0..expressions.len() {
             for r in 0..expressions.len() {
@@ -108,17 +191,19 @@ impl Problem for Solution {
             }
         }
 
-        format!("{}", max)
+        Some(format!("{}", max))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_part1_reduce() {
-        let mut expr = parse_expr("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]");
+        let mut expr = parse_expr("[[[[4,3],4],4],[7,[[8,4],9]]]").unwrap();
+        add_expr(&mut expr, &parse_expr("[1,1]").unwrap());
         reduce_expr(&mut expr, 0);
         assert_eq!(
             expr,
@@ -135,4 +220,47 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_parse_expr() {
+        for input in ["[1,2]", "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", "[9,[8,7]]"] {
+            assert_eq!(format_expr(&parse_expr(input).unwrap()), input);
+        }
+        assert_eq!(parse_expr("1"), Err((0, "expected '['")));
+        assert_eq!(parse_expr("[1,2"), Err((4, "expected ']'")));
+        assert_eq!(parse_expr("[1;2]"), Err((2, "expected ','")));
+        assert_eq!(parse_expr("[10,2]"), Err((2, "expected ','")));
+        assert_eq!(parse_expr("[1,2]]"), Err((5, "unexpected data after the number")));
+        assert_eq!(
+            parse_expr("[[[[[1,2],3],4],5],6]"),
+            Err((4, "pairs nested more than 4 deep"))
+        );
+        assert!(Solution {}.parse("[1,2]\n[3,x]\n").is_err());
+        for (input, expected) in [("", (1, 1)), ("[1,2]\n", (2, 1))] {
+            let e = aoc_core::parse_error(&Solution {}, input);
+            assert_eq!((e.line, e.column), expected);
+        }
+    }
+
+    /// Reduced snailfish numbers: pairs nested at most 4 deep, holding digits.
+    fn snailfish() -> impl Strategy<Value = Expr> {
+        let element = (0u32..10)
+            .prop_map(|n| n.to_string())
+            .prop_recursive(3, 16, 2, |inner| {
+                (inner.clone(), inner).prop_map(|(l, r)| format!("[{},{}]", l, r))
+            });
+        (element.clone(), element)
+            .prop_map(|(l, r)| parse_expr(&format!("[{},{}]", l, r)).unwrap())
+    }
+
+    proptest! {
+        #[test]
+        fn test_reduce_is_reduced(mut left in snailfish(), right in snailfish()) {
+            add_expr(&mut left, &right);
+            reduce_expr(&mut left, 0);
+            prop_assert!(left.iter().all(|&(d, n)| d <= 4 && n <= 9), "{:?}", left);
+            // Still a well-formed number.
+            prop_assert_eq!(parse_expr(&format_expr(&left)), Ok(left));
+        }
+    }
 }
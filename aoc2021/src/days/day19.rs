@@ -1,11 +1,11 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Context, ParseError, Problem};
 use std::fmt;
 use std::ops::{Add, Sub};
 use std::collections::HashSet;
 
 pub struct Solution {}
 
-#[derive(Copy, Clone, Eq, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
 struct Point3 {
     x: isize,
     y: isize,
@@ -111,20 +111,17 @@ impl Point3 {
         }
     }
 
+    #[allow(dead_code)]
     fn dist(&self, o: &Point3) -> isize {
         (self.x - o.x).pow(2) + (self.x-o.x).pow(2) + (self.x-o.x).pow(2)
     }
 }
 
-impl PartialEq for Point3 {
-    fn eq(&self, other: &Self) -> bool {
-        self.x == other.x && self.y == other.y && self.z == other.z
-    }
-}
 
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
-struct Scanner {
+pub struct Scanner {
     id: usize,
     position: Point3,
     rotation: Point3,
@@ -150,7 +147,7 @@ impl Scanner {
 
         for beacon in other.relative_beacons.iter() {
             for target in other.relative_beacons.iter() {
-                if set.contains(&(beacon.clone() - target.clone())) {
+                if set.contains(&(*beacon - *target)) {
                     points.replace(beacon);
                     points.replace(target);
                 }
@@ -168,9 +165,10 @@ impl Scanner {
         for r in ROTATION_MAP {
             let mut copy = self.clone();
             copy.rotate(&r);
-            let n_overlaps = copy.count_beacon_overlaps(&other);
+            let n_overlaps = copy.count_beacon_overlaps(other);
+            Context::current().count("rotations tried");
             if n_overlaps > 0 {
-                dbg!(n_overlaps);
+                Context::current().debug(format_args!("{} overlapping beacons", n_overlaps));
             }
             if n_overlaps >= 12 {
                 self.rotate(&r);
@@ -181,7 +179,8 @@ impl Scanner {
     }
 }
 
-fn align_scanners(scanners: &mut Vec<Scanner>) {
+fn align_scanners(scanners: &mut [Scanner]) {
+    let ctx = Context::current();
     // Fix origin
     scanners[0].aligned = true;
     let mut last_aligned = 0;
@@ -197,59 +196,79 @@ fn align_scanners(scanners: &mut Vec<Scanner>) {
 
             let last_aligned_scanner = &scanners[last_aligned].clone();
             if scanners[i].align(last_aligned_scanner) {
-                println!("aligned {} to {}", &scanners[i].id, &scanners[last_aligned].id);
+                ctx.info(format_args!("aligned {} to {}", scanners[i].id, scanners[last_aligned].id));
                 last_aligned = i;
                 scanners[i].aligned = true;
                 n_aligned += 1;
+                ctx.progress(n_aligned as u64, n_scanners as u64);
             }
 
         }
     }
 }
 
-fn parse_report(full_report: &str) -> Vec<Scanner> {
+fn parse_report(full_report: &str) -> Result<Vec<Scanner>, ParseError> {
     full_report
         .split("\n\n")
+        .filter(|report| !report.trim().is_empty())
         .map(|report| {
-            let id = report
-                .lines()
-                .next()
-                .unwrap()
-                .split(' ')
-                .nth(2)
-                .unwrap()
-                .parse::<usize>()
-                .expect("could not parse scanner id");
+            let header = report.lines().next().unwrap_or(report);
+            let id = match header.split_whitespace().collect::<Vec<&str>>()[..] {
+                ["---", "scanner", id, "---"] => id
+                    .parse::<usize>()
+                    .map_err(|_| ParseError::at(full_report, id, "could not parse scanner id"))?,
+                _ => {
+                    return Err(ParseError::at(
+                        full_report,
+                        header,
+                        "expected '--- scanner <id> ---'",
+                    ))
+                }
+            };
 
             let position = Point3::new(0,0,0);
             let rotation = Point3::new(0,0,0);
             
-            let relative_beacons: Vec<Point3> = report
+            let relative_beacons = report
                 .lines()
                 .skip(1)
                 .map(|l| {
-                    l.split(',')
-                        .map(|n| n.parse::<isize>().expect("expected isize number"))
-                        .collect()
+                    let v = l
+                        .trim_end()
+                        .split(',')
+                        .map(|n| {
+                            n.parse::<isize>()
+                                .map_err(|_| ParseError::at(full_report, n, "expected isize number"))
+                        })
+                        .collect::<Result<Vec<isize>, ParseError>>()?;
+                    match v[..] {
+                        [x, y, z] => Ok(Point3::new(x, y, z)),
+                        _ => Err(ParseError::at(full_report, l, "expected 'x,y,z'")),
+                    }
                 })
-                .map(|v: Vec<isize>| Point3::new(v[0], v[1], v[2]))
-                .collect();
+                .collect::<Result<Vec<Point3>, ParseError>>()?;
 
             let n_beacons = relative_beacons.len();
-            Scanner {
+            Ok(Scanner {
                 id,
                 position,
                 rotation,
                 n_beacons,
                 relative_beacons,
                 aligned: false,
-            }
+            })
         })
         .collect()
 }
 
 impl Problem for Solution {
-    fn part1(&self, input: &str) -> String {
+    type Input = Vec<Scanner>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_report(input)?)
+    }
+
+    fn part1(&self, scanners: &Self::Input) -> Option<String> {
 //         let input = "--- scanner 0 ---
 // -1,-1,1
 // -2,-2,2
@@ -289,13 +308,13 @@ impl Problem for Solution {
 // 3,1,2
 // -6,-4,-5
 // 0,7,-8";
-        let mut scanners = parse_report(input);
+        let mut scanners = scanners.clone();
         align_scanners(&mut scanners);
-        format!("{}", "undefined")
+        None
     }
 
-    fn part2(&self, input: &str) -> String {
-        format!("{}", "undefined")
+    fn part2(&self, _input: &Self::Input) -> Option<String> {
+        None
     }
 }
 
@@ -346,7 +365,23 @@ mod tests {
 -6,-4,-5
 0,7,-8";
 
-        let mut scanners = parse_report(input);
+        let mut scanners = parse_report(input).unwrap();
         align_scanners(&mut scanners);
     }
+
+    #[test]
+    fn test_parse_report_errors() {
+        let scanners = parse_report("--- scanner 0 ---\n1,2,3\n-4,5,6\n\n--- scanner 1 ---\n7,8,9\n\n\n").unwrap();
+        assert_eq!(scanners.len(), 2);
+        assert_eq!(scanners[0].n_beacons, 2);
+
+        let e = parse_report("--- scanner 0 ---\n1,2,3\n4,x,6\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (3, 3, "x"));
+
+        let e = parse_report("--- scanner 0 ---\n1,2\n").unwrap_err();
+        assert_eq!((e.line, e.column), (2, 1));
+
+        let e = parse_report("scanner zero\n1,2,3\n").unwrap_err();
+        assert_eq!(e.text, "scanner zero");
+    }
 }
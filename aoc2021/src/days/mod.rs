@@ -1,19 +1,2 @@
-pub mod day01;
-pub mod day02;
-pub mod day03;
-pub mod day04;
-pub mod day05;
-pub mod day06;
-pub mod day07;
-pub mod day08;
-pub mod day09;
-pub mod day10;
-pub mod day11;
-pub mod day12;
-pub mod day13;
-pub mod day14;
-pub mod day15;
-pub mod day16;
-pub mod day17;
-pub mod day18;
-pub mod day19;
+// Modules and `get_solver` are generated from the `dayNN.rs` files by `build.rs`.
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
@@ -0,0 +1,19 @@
+use aoc_core::Year;
+
+mod days;
+
+/// This year's solutions, for the `aoc2021` binary and the multi-year `aoc`.
+pub const YEAR: Year = Year {
+    year: 2021,
+    days: days::DAYS,
+    get_solver: days::get_solver,
+    root: env!("CARGO_MANIFEST_DIR"),
+    embedded_input: days::embedded_input,
+};
+
+/// The parsers exercised by the fuzz targets in `../fuzz`.
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing {
+    pub use crate::days::day16::{encode as encode_packet, parse as parse_packet, Packet};
+    pub use crate::days::day18::{format_expr, parse_expr, Expr};
+}
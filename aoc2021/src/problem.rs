@@ -1,4 +0,0 @@
-pub trait Problem {
-    fn part1(&self, input: &str) -> String;
-    fn part2(&self, input: &str) -> String;
-}
@@ -1,26 +1,67 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 use std::collections::BinaryHeap;
 
 pub struct Solution {}
 
-fn parse(input: &str) -> BinaryHeap<i32> {
+fn parse(input: &str) -> Result<BinaryHeap<i32>, ParseError> {
     input
         .split("\n\n")
-        .map(|x| x.lines().fold(0, |acc, n| acc + n.parse::<i32>().unwrap()))
-        .collect::<BinaryHeap<i32>>()
+        .map(|x| x.lines().map(|n| ParseError::number::<i32>(input, n)).sum())
+        .collect()
 }
 
+const EXAMPLE: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000";
+
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let weights = parse(&_input);
+    type Input = BinaryHeap<i32>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("24000"),
+            part2: Some("45000"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse(input)?)
+    }
+
+    fn part1(&self, weights: &Self::Input) -> Option<String> {
         let max = weights.peek().unwrap();
 
         Some(format!("{}", max))
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
-        let mut weights = parse(&_input);
+    fn part2(&self, weights: &Self::Input) -> Option<String> {
+        let mut weights = weights.clone();
         let answer: i32 = weights.pop().unwrap() + weights.pop().unwrap() + weights.peek().unwrap();
         Some(format!("{}", answer))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_core::example_tests!(Solution {});
+
+    #[test]
+    fn test_parse_errors() {
+        let e = parse("1000\n\n20O0\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (3, 1, "20O0"));
+    }
+}
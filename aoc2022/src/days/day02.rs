@@ -1,4 +1,4 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 use std::collections::HashMap;
 
 pub struct Solution {}
@@ -10,7 +10,26 @@ fn solve(input: &str, pointmap: &HashMap<&str, u32>) -> u32 {
 }
 
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
+    type Input = String;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: "A Y\nB X\nC Z",
+            part1: Some("15"),
+            part2: Some("12"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        for l in input.lines() {
+            if !matches!(l.as_bytes(), [b'A'..=b'C', b' ', b'X'..=b'Z']) {
+                return Err(ParseError::at(input, l, "expected a round like 'A Y'").into());
+            }
+        }
+        Ok(input.to_string())
+    }
+
+    fn part1(&self, _input: &Self::Input) -> Option<String> {
         let pointmap = HashMap::from([
             ("A X", 4),
             ("B X", 1),
@@ -23,12 +42,12 @@ impl Problem for Solution {
             ("C Z", 6),
         ]);
 
-        let answer = solve(&_input, &pointmap);
+        let answer = solve(_input, &pointmap);
 
         Some(format!("{}", answer))
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
+    fn part2(&self, _input: &Self::Input) -> Option<String> {
         let pointmap = HashMap::from([
             ("A X", 3),
             ("B X", 1),
@@ -41,8 +60,21 @@ impl Problem for Solution {
             ("C Z", 7),
         ]);
 
-        let answer = solve(&_input, &pointmap);
+        let answer = solve(_input, &pointmap);
 
         Some(format!("{}", answer))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_core::example_tests!(Solution {});
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Solution {}.parse("A Y\nB W").is_err());
+        assert!(Solution {}.parse("A Y\nB").is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 
 use itertools::Itertools;
 use std::collections::HashMap;
@@ -6,26 +6,61 @@ use std::collections::HashSet;
 
 pub struct Solution {}
 
-fn get_priority(c: char) -> u32 {
+fn get_priority(c: char) -> Option<u8> {
     match c {
-        'a'..='z' => c as u32 - 'a' as u32 + 1,
-        'A'..='Z' => c as u32 - 'A' as u32 + 27,
-        _ => unreachable!(),
+        'a'..='z' => Some(c as u8 - b'a' + 1),
+        'A'..='Z' => Some(c as u8 - b'A' + 27),
+        _ => None,
     }
 }
 
+const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw";
+
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let mut set: HashSet<char> = HashSet::new();
-        let sum = _input
+    /// The priority of each item, per rucksack.
+    type Input = Vec<Vec<u8>>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("157"),
+            part2: Some("70"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        let rucksacks = input
             .lines()
             .map(|l| {
-                let (left, right) = l.split_at(l.len() / 2);
-                set.extend(left.chars());
+                l.char_indices()
+                    .map(|(i, c)| {
+                        get_priority(c).ok_or_else(|| {
+                            let text = &l[i..i + c.len_utf8()];
+                            ParseError::at(input, text, "expected an item a-z or A-Z")
+                        })
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(rucksacks)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Option<String> {
+        let mut set: HashSet<u8> = HashSet::new();
+        let sum = input
+            .iter()
+            .map(|items| {
+                let (left, right) = items.split_at(items.len() / 2);
+                set.extend(left);
                 let out = match right
-                    .chars()
+                    .iter()
                     .find(|c| set.contains(c)) {
-                        Some(c) => get_priority(c),
+                        Some(&c) => c as u32,
                         None => 0,
                     };
                 set.clear();
@@ -36,22 +71,20 @@ impl Problem for Solution {
         Some(format!("{}", sum))
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
-        let mut freq: HashMap<char, u32> = HashMap::new();
-        let sum = &_input
-            .lines()
+    fn part2(&self, input: &Self::Input) -> Option<String> {
+        let mut freq: HashMap<u8, u32> = HashMap::new();
+        let sum = &input
             .chunks(3)
-            .into_iter()
             .map(|chunk| {
-                chunk.for_each(|l| {
-                    l.chars().unique().for_each(|c| {
+                chunk.iter().for_each(|items| {
+                    items.iter().unique().for_each(|&c| {
                         *freq.entry(c).or_insert(0) += 1;
                     })
                 });
                 let out = freq
                     .iter()
                     .filter(|(_, &v)| v >= 3)
-                    .fold(0, |acc, (&k, _)| acc + get_priority(k));
+                    .fold(0, |acc, (&k, _)| acc + k as u32);
 
                 // Clear for next group rather than reinit
                 freq.clear();
@@ -67,24 +100,11 @@ impl Problem for Solution {
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &'static str = "vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw";
-
-    #[test]
-    fn test_day03a() {
-        let solver = Solution {};
-        let answer = solver.part1(TEST_INPUT);
-        assert_eq!(answer, Some("157".to_string()));
-    }
+    aoc_core::example_tests!(Solution {});
 
     #[test]
-    fn test_day03b() {
-        let solver = Solution {};
-        let answer = solver.part2(TEST_INPUT);
-        assert_eq!(answer, Some("70".to_string()));
+    fn test_parse_errors() {
+        let e = aoc_core::parse_error(&Solution {}, "vJrwpWtwJgWrhcsFMMfFFhFp\njqHRN qRjq");
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 6, " "));
     }
 }
@@ -1,12 +1,32 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 
 pub struct Solution {}
 
 type Range = Vec<isize>;
 
+const EXAMPLE: &str = "2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8";
+
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let pairs = parse_input(_input);
+    type Input = Vec<Range>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("2"),
+            part2: Some("4"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(&self, pairs: &Self::Input) -> Option<String> {
         let answer = pairs.chunks(2).fold(0, |acc, r| {
             acc + if range_len(&r[0]) >= range_len(&r[1]) {
                 range_contains(&r[0], &r[1]) as usize
@@ -17,8 +37,7 @@ impl Problem for Solution {
         Some(format!("{}", answer))
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
-        let pairs = parse_input(_input);
+    fn part2(&self, pairs: &Self::Input) -> Option<String> {
         let answer = pairs
             .chunks(2)
             .fold(0, |acc, r| acc + range_overlaps(&r[0], &r[1]) as usize);
@@ -26,14 +45,23 @@ impl Problem for Solution {
     }
 }
 
-fn parse_input(input: &str) -> Vec<Range> {
-    input
-        .lines()
-        .flat_map(|s| {
-            s.split(',')
-                .map(|c| c.split('-').map(|c| c.parse().unwrap()).collect())
-        })
-        .collect()
+fn parse_input(input: &str) -> Result<Vec<Range>, ParseError> {
+    let mut ranges = Vec::new();
+    for l in input.lines() {
+        let (left, right) = l
+            .split_once(',')
+            .ok_or_else(|| ParseError::at(input, l, "expected two ranges like 2-4,6-8"))?;
+        for r in [left, right] {
+            let (start, end) = r
+                .split_once('-')
+                .ok_or_else(|| ParseError::at(input, r, "expected a range like 2-4"))?;
+            ranges.push(vec![
+                ParseError::number(input, start)?,
+                ParseError::number(input, end)?,
+            ]);
+        }
+    }
+    Ok(ranges)
 }
 
 fn range_contains(a: &Range, b: &Range) -> bool {
@@ -54,25 +82,26 @@ fn range_len(a: &Range) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
-    const TEST_INPUT: &'static str = "2-4,6-8
-2-3,4-5
-5-7,7-9
-2-8,3-7
-6-6,4-6
-2-6,4-8";
+    use proptest::prelude::*;
+
+    aoc_core::example_tests!(Solution {});
 
     #[test]
-    fn test_day04a() {
-        let solver = Solution {};
-        let answer = solver.part1(TEST_INPUT).unwrap();
-        assert_eq!(answer, "2");
+    fn test_parse_errors() {
+        let e = parse_input("2-4,6-8\n2-3;4-5").unwrap_err();
+        assert_eq!((e.line, e.column), (2, 1));
+        let e = parse_input("2-4,6-x").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 7, "x"));
     }
 
-    #[test]
-    fn test_day04b() {
-        let solver = Solution {};
-        let answer = solver.part2(TEST_INPUT).unwrap();
+    fn range() -> impl Strategy<Value = Range> {
+        (1isize..100, 0isize..20).prop_map(|(start, len)| vec![start, start + len])
+    }
 
-        assert_eq!(answer, "4");
+    proptest! {
+        #[test]
+        fn test_overlaps_symmetric(a in range(), b in range()) {
+            prop_assert_eq!(range_overlaps(&a, &b), range_overlaps(&b, &a));
+        }
     }
 }
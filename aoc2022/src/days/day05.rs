@@ -1,5 +1,4 @@
-use crate::problem::Problem;
-use regex::Regex;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 use std::collections::VecDeque;
 
 pub struct Solution {}
@@ -7,47 +6,54 @@ pub struct Solution {}
 type Instr = Vec<usize>;
 type Lanes<T> = Vec<VecDeque<T>>;
 
-fn parse_input(input: &str) -> (Lanes<&str>, Vec<Instr>) {
-    let (lanes, instr) = input.split_once("\n\n").unwrap();
-    let n_lanes = lanes
-        .lines()
-        .last()
-        .unwrap()
-        .chars()
-        .fold(0, |acc, c| acc + !c.is_whitespace() as usize);
-    (parse_lanes(lanes, n_lanes), parse_instr(instr))
+fn parse_input(input: &str) -> Result<(Lanes<char>, Vec<Instr>), ParseError> {
+    let (lanes, instr) = input.split_once("\n\n").ok_or_else(|| {
+        let end = &input[input.len()..];
+        ParseError::at(input, end, "expected a blank line between the stacks and the moves")
+    })?;
+    let n_lanes = lanes.lines().last().unwrap_or(lanes).split_whitespace().count();
+    Ok((
+        parse_lanes(input, lanes, n_lanes)?,
+        parse_instr(input, instr, n_lanes)?,
+    ))
 }
 
-fn parse_instr(input: &str) -> Vec<Instr> {
-    let re = Regex::new(r"\w*\s(\d*)\s\w*\s(\w*)\s\w*\s(\d*)").unwrap();
-    input
+fn parse_instr(input: &str, instr: &str, n_lanes: usize) -> Result<Vec<Instr>, ParseError> {
+    let lane = |n: &str| match ParseError::number(input, n)? {
+        lane @ 1.. if lane <= n_lanes => Ok(lane),
+        _ => Err(ParseError::at(input, n, format!("expected a stack from 1 to {}", n_lanes))),
+    };
+    instr
         .lines()
-        .map(|l| {
-            re.captures(l)
-                .unwrap()
-                .iter()
-                .skip(1)
-                .map(|m| m.unwrap().as_str().parse::<usize>().unwrap())
-                .collect::<Instr>()
+        .map(|l| match l.split_whitespace().collect::<Vec<&str>>()[..] {
+            ["move", num, "from", from, "to", to] => {
+                Ok(vec![ParseError::number(input, num)?, lane(from)?, lane(to)?])
+            }
+            _ => Err(ParseError::at(input, l, "expected 'move <n> from <stack> to <stack>'")),
         })
-        .collect::<Vec<Instr>>()
+        .collect()
 }
 
-fn parse_lanes(input: &str, n_lanes: usize) -> Lanes<&str> {
-    let re = Regex::new(r"(?:\[|\s)(?P<crate>([A-Z]|\s))(\]|\s)\s?").unwrap();
-    let mut lanes = vec![VecDeque::new(); n_lanes];
-    input.lines().rev().skip(1).for_each(|l| {
-        re.captures_iter(l).enumerate().for_each(|(i, c)| {
-            let c = c.name("crate").unwrap().as_str();
-            if c != " " {
-                lanes[i].push_back(c);
+/// `lanes` is the drawing of the stacks, a slice of `input`. Crate letters
+/// sit every four columns, starting at the second.
+fn parse_lanes(input: &str, lanes: &str, n_lanes: usize) -> Result<Lanes<char>, ParseError> {
+    let mut stacks = vec![VecDeque::new(); n_lanes];
+    for l in lanes.lines().rev().skip(1) {
+        for (lane, (i, c)) in l.char_indices().skip(1).step_by(4).enumerate() {
+            match c {
+                ' ' => (),
+                'A'..='Z' if lane < n_lanes => stacks[lane].push_back(c),
+                _ => {
+                    let text = &l[i..i + c.len_utf8()];
+                    return Err(ParseError::at(input, text, "expected a crate on a numbered stack"));
+                }
             }
-        })
-    });
-    lanes
+        }
+    }
+    Ok(stacks)
 }
 
-fn execute_9000(lanes: &mut Lanes<&str>, instr: Vec<Instr>) {
+fn execute_9000(lanes: &mut Lanes<char>, instr: &[Instr]) {
     for i in instr {
         assert!(i.len() == 3); // remove bounds checks
         let (num, from, to) = (i[0], i[1] - 1, i[2] - 1);
@@ -58,7 +64,7 @@ fn execute_9000(lanes: &mut Lanes<&str>, instr: Vec<Instr>) {
     }
 }
 
-fn execute_9001(lanes: &mut Lanes<&str>, instr: Vec<Instr>) {
+fn execute_9001(lanes: &mut Lanes<char>, instr: &[Instr]) {
     let mut queue = VecDeque::new();
     for i in instr {
         assert!(i.len() == 3); // remove bounds checks
@@ -72,29 +78,11 @@ fn execute_9001(lanes: &mut Lanes<&str>, instr: Vec<Instr>) {
     }
 }
 
-fn get_tops(lanes: &Lanes<&str>) -> String {
+fn get_tops(lanes: &Lanes<char>) -> String {
     lanes.iter().map(|l| *l.back().unwrap()).collect()
 }
 
-impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let (mut lanes, instr) = parse_input(_input);
-        execute_9000(&mut lanes, instr);
-        Some(get_tops(&lanes).to_string())
-    }
-
-    fn part2(&self, _input: &str) -> Option<String> {
-        let (mut lanes, instr) = parse_input(_input);
-        execute_9001(&mut lanes, instr);
-        Some(get_tops(&lanes).to_string())
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "    [D]    
+const EXAMPLE: &str = "    [D]    
 [N] [C]    
 [Z] [M] [P]
  1   2   3 
@@ -105,19 +93,46 @@ move 2 from 2 to 1
 move 1 from 1 to 2
 ";
 
-    #[test]
-    fn test_day05a() {
-        let (mut lanes, instr) = parse_input(INPUT);
+impl Problem for Solution {
+    type Input = (Lanes<char>, Vec<Instr>);
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("CMZ"),
+            part2: Some("MCD"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(&self, (lanes, instr): &Self::Input) -> Option<String> {
+        let mut lanes = lanes.clone();
         execute_9000(&mut lanes, instr);
-        let answer = get_tops(&lanes);
-        assert_eq!("CMZ", answer)
+        Some(get_tops(&lanes))
     }
 
-    #[test]
-    fn test_day05b() {
-        let (mut lanes, instr) = parse_input(INPUT);
+    fn part2(&self, (lanes, instr): &Self::Input) -> Option<String> {
+        let mut lanes = lanes.clone();
         execute_9001(&mut lanes, instr);
-        let answer = get_tops(&lanes);
-        assert_eq!("MCD", answer)
+        Some(get_tops(&lanes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_core::example_tests!(Solution {});
+
+    #[test]
+    fn test_parse_errors() {
+        let e = parse_input(&EXAMPLE.replace("from 1 to 3", "from 1 to 4")).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (7, 18, "4"));
+        let e = parse_input(&EXAMPLE.replace("[C]", "[c]")).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 6, "c"));
+        assert!(parse_input(&EXAMPLE.replace("\n\n", "\n")).is_err());
     }
 }
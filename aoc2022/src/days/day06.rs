@@ -1,6 +1,4 @@
-extern crate test;
-
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, Problem};
 use std::collections::HashMap;
 
 pub struct Solution {}
@@ -19,7 +17,7 @@ fn find_first_unique_group_vec_clone(_input: &str, group_size: usize) -> Option<
         .collect::<Vec<char>>()
         .windows(group_size)
         .into_iter()
-        .position(|w| unique_chars(w))
+        .position(unique_chars)
         .unwrap()
         + group_size;
 
@@ -31,8 +29,8 @@ fn find_first_unique_group_hashmap(_input: &str, group_size: usize) -> Option<us
     let mut freqs: HashMap<u8, usize> = HashMap::new();
 
     // Initial fill
-    for i in 0..group_size {
-        *freqs.entry(stream[i]).or_insert(0) += 1;
+    for &c in stream.iter().take(group_size) {
+        *freqs.entry(c).or_insert(0) += 1;
     }
 
     for i in group_size.._input.len(){
@@ -51,100 +49,66 @@ fn find_first_unique_group_hashmap(_input: &str, group_size: usize) -> Option<us
 }
 
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
+    type Input = String;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: "mjqjpqmgbljsphdztnvjfqwrcgsmlb",
+            part1: Some("7"),
+            part2: Some("19"),
+        },
+        Example {
+            input: "bvwbjplbgvbhsrlpgdmjqwftvncz",
+            part1: Some("5"),
+            part2: Some("23"),
+        },
+        Example {
+            input: "nppdvjthqldpwncqszvftbrmjlhg",
+            part1: Some("6"),
+            part2: Some("23"),
+        },
+        Example {
+            input: "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg",
+            part1: Some("10"),
+            part2: Some("29"),
+        },
+        Example {
+            input: "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw",
+            part1: Some("11"),
+            part2: Some("26"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(input.to_string())
+    }
+
+    fn part1(&self, _input: &Self::Input) -> Option<String> {
         let answer = find_first_unique_group_vec_clone(_input, 4).unwrap();
         Some(answer.to_string())
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
+    fn part2(&self, _input: &Self::Input) -> Option<String> {
         let answer = find_first_unique_group_hashmap(_input, 14).unwrap();
         Some(answer.to_string())
     }
 }
 
-#[cfg(test)]
-mod benches {
-    use super::*;
-    use test::Bencher;
-
-    static BENCH_SIZE: usize = 20;
-    static BENCH_INPUT: &str = include_str!("../../inputs/06.in");
-
-    #[bench]
-    fn copy_vector_approach_small(b: &mut Bencher) {
-        b.iter(|| {
-            (0..BENCH_SIZE)
-                .map(|_| find_first_unique_group_vec_clone(BENCH_INPUT, 4).unwrap())
-                .collect::<Vec<_>>()
-        })
-    }
-
-    #[bench]
-    fn hashmap_approach_small(b: &mut Bencher) {
-        b.iter(|| {
-            (0..BENCH_SIZE)
-                .map(|_| find_first_unique_group_hashmap(BENCH_INPUT, 4).unwrap())
-                .collect::<Vec<_>>()
-        })
-    }
-
-    #[bench]
-    fn copy_vector_approach_large(b: &mut Bencher) {
-        b.iter(|| {
-            (0..BENCH_SIZE)
-                .map(|_| find_first_unique_group_vec_clone(BENCH_INPUT, 14).unwrap())
-                .collect::<Vec<_>>()
-        })
-    }
-
-    #[bench]
-    fn hashmap_approach_large(b: &mut Bencher) {
-        b.iter(|| {
-            (0..BENCH_SIZE)
-                .map(|_| find_first_unique_group_hashmap(BENCH_INPUT, 14).unwrap())
-                .collect::<Vec<_>>()
-        })
-    }
-
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT1: &str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
-    const INPUT2: &str = "bvwbjplbgvbhsrlpgdmjqwftvncz";
-    const INPUT3: &str = "nppdvjthqldpwncqszvftbrmjlhg";
-    const INPUT4: &str = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
-    const INPUT5: &str = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
+    aoc_core::example_tests!(Solution {});
 
     #[test]
-    fn test_day06a() {
-        assert_eq!(7, find_first_unique_group_vec_clone(INPUT1, 4).unwrap());
-        assert_eq!(5, find_first_unique_group_vec_clone(INPUT2, 4).unwrap());
-        assert_eq!(6, find_first_unique_group_vec_clone(INPUT3, 4).unwrap());
-        assert_eq!(10, find_first_unique_group_vec_clone(INPUT4, 4).unwrap());
-        assert_eq!(11, find_first_unique_group_vec_clone(INPUT5, 4).unwrap());
-
-        assert_eq!(7, find_first_unique_group_hashmap(INPUT1, 4).unwrap());
-        assert_eq!(5, find_first_unique_group_hashmap(INPUT2, 4).unwrap());
-        assert_eq!(6, find_first_unique_group_hashmap(INPUT3, 4).unwrap());
-        assert_eq!(10, find_first_unique_group_hashmap(INPUT4, 4).unwrap());
-        assert_eq!(11, find_first_unique_group_hashmap(INPUT5, 4).unwrap());
-    }
-
-    #[test]
-    fn test_day06b() {
-        assert_eq!(19, find_first_unique_group_vec_clone(INPUT1, 14).unwrap());
-        assert_eq!(23, find_first_unique_group_vec_clone(INPUT2, 14).unwrap());
-        assert_eq!(23, find_first_unique_group_vec_clone(INPUT3, 14).unwrap());
-        assert_eq!(29, find_first_unique_group_vec_clone(INPUT4, 14).unwrap());
-        assert_eq!(26, find_first_unique_group_vec_clone(INPUT5, 14).unwrap());
-
-        assert_eq!(19, find_first_unique_group_hashmap(INPUT1, 14).unwrap());
-        assert_eq!(23, find_first_unique_group_hashmap(INPUT2, 14).unwrap());
-        assert_eq!(23, find_first_unique_group_hashmap(INPUT3, 14).unwrap());
-        assert_eq!(29, find_first_unique_group_hashmap(INPUT4, 14).unwrap());
-        assert_eq!(26, find_first_unique_group_hashmap(INPUT5, 14).unwrap());
+    fn test_implementations_agree() {
+        for example in Solution::EXAMPLES {
+            for size in [4, 14] {
+                assert_eq!(
+                    find_first_unique_group_vec_clone(example.input, size),
+                    find_first_unique_group_hashmap(example.input, size)
+                );
+            }
+        }
     }
 }
@@ -1,4 +1,4 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 
 use std::cell::RefCell;
 use std::fmt;
@@ -11,7 +11,7 @@ enum NodeValue {
     File(u32),
 }
 
-struct Node {
+pub struct Node {
     value: Option<NodeValue>,
     children: Vec<Rc<RefCell<Node>>>,
     parent: Option<Rc<RefCell<Node>>>,
@@ -20,7 +20,7 @@ struct Node {
 impl fmt::Display for Node {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self.value {
-            Some(v) => write!(f, "{}", v.to_string()),
+            Some(v) => write!(f, "{}", v),
             None => write!(
                 f,
                 "[{:?}]",
@@ -45,11 +45,11 @@ impl fmt::Display for NodeValue {
 
 impl Node {
     fn new(value: NodeValue) -> Node {
-        return Node {
+        Node {
             value: Some(value),
             children: vec![],
             parent: None,
-        };
+        }
     }
 
     fn post_traverse(&self, dir_sizes: &mut Vec<u32>) -> u32 {
@@ -69,17 +69,19 @@ impl Node {
     }
 }
 
-fn parse_to_tree(input: &str) -> Rc<RefCell<Node>> {
+fn parse_to_tree(input: &str) -> Result<Rc<RefCell<Node>>, ParseError> {
     // Parse the input to a tree structure.
     let root = Rc::new(RefCell::new(Node::new(NodeValue::Dir("root".to_string()))));
     let mut path = vec![Rc::clone(&root)];
     path.push(Rc::clone(&root));
-    input
-        .lines()
-        .for_each(|l| match l.split(" ").collect::<Vec<&str>>()[..] {
+    for l in input.lines() {
+        match l.split(" ").collect::<Vec<&str>>()[..] {
             ["$", "cd", ".."] => {
                 // Up
-                path.pop().unwrap();
+                if path.len() == 1 {
+                    return Err(ParseError::at(input, l, "cannot go up from the root"));
+                }
+                path.pop();
             }
             ["$", "cd", name] => {
                 // cd into dir
@@ -88,65 +90,34 @@ fn parse_to_tree(input: &str) -> Rc<RefCell<Node>> {
                 curr.borrow_mut().children.push(Rc::clone(&child));
                 {
                     let mut mut_child = child.borrow_mut();
-                    mut_child.parent = Some(Rc::clone(&path.last().unwrap()));
+                    mut_child.parent = Some(Rc::clone(path.last().unwrap()));
                 }
                 path.push(Rc::clone(&child));
             }
             ["$", "ls"] => (), // continue
             ["dir", _] => (),
             [size, _] => {
+                let size = ParseError::number(input, size)?;
                 let curr = path.last().unwrap();
-                let child = Rc::new(RefCell::new(Node::new(NodeValue::File(
-                    size.parse::<u32>().unwrap(),
-                ))));
+                let child = Rc::new(RefCell::new(Node::new(NodeValue::File(size))));
                 curr.borrow_mut().children.push(Rc::clone(&child));
                 {
                     let mut mut_child = child.borrow_mut();
-                    mut_child.parent = Some(Rc::clone(&curr));
-                    mut_child.value = Some(NodeValue::File(size.parse().unwrap()));
+                    mut_child.parent = Some(Rc::clone(curr));
                 }
             }
-            _ => (),
-        });
+            _ => return Err(ParseError::at(input, l, "expected a command, a directory or a file")),
+        }
+    }
 
-    root
+    Ok(root)
 }
 
 const MAX_SIZE: u32 = 100_000;
 const MIN_REQUIRED: u32 = 30_000_000;
 const MAX_SPACE: u32 = 70_000_000;
 
-impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let tree = parse_to_tree(_input);
-        let mut sizes = vec![];
-        tree.borrow().post_traverse(&mut sizes);
-        let total = sizes.iter().filter(|&s| *s <= MAX_SIZE).sum::<u32>();
-
-        Some(total.to_string())
-    }
-
-    fn part2(&self, _input: &str) -> Option<String> {
-        let tree = parse_to_tree(_input);
-        let mut sizes = vec![];
-        tree.borrow().post_traverse(&mut sizes);
-
-        let total = sizes.pop().unwrap();
-        let answer = *sizes
-            .iter()
-            .filter(|&s| MAX_SPACE >= MIN_REQUIRED + total - s)
-            .min()
-            .unwrap();
-
-        Some(answer.to_string())
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "$ cd /
+const EXAMPLE: &str = "$ cd /
 $ ls
 dir a
 14848514 b.txt
@@ -171,19 +142,30 @@ $ ls
 7214296 k
 ";
 
-    #[test]
-    fn test_day07a() {
-        let tree = parse_to_tree(INPUT);
+impl Problem for Solution {
+    type Input = Rc<RefCell<Node>>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("95437"),
+            part2: Some("24933642"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_to_tree(input)?)
+    }
+
+    fn part1(&self, tree: &Self::Input) -> Option<String> {
         let mut sizes = vec![];
         tree.borrow().post_traverse(&mut sizes);
         let total = sizes.iter().filter(|&s| *s <= MAX_SIZE).sum::<u32>();
 
-        assert_eq!(total, 95437);
+        Some(total.to_string())
     }
 
-    #[test]
-    fn test_day07b() {
-        let tree = parse_to_tree(INPUT);
+    fn part2(&self, tree: &Self::Input) -> Option<String> {
         let mut sizes = vec![];
         tree.borrow().post_traverse(&mut sizes);
 
@@ -194,7 +176,21 @@ $ ls
             .min()
             .unwrap();
 
-        assert_eq!(answer, 24933642);
-        // let total = sizes.iter().filter(|&s| *s <= MAX_SIZE).sum::<u32>();
+        Some(answer.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_core::example_tests!(Solution {});
+
+    #[test]
+    fn test_parse_errors() {
+        let e = parse_to_tree("$ cd /\n$ ls\n12x a.txt").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (3, 1, "12x"));
+        assert!(parse_to_tree("$ cd /\n$ cd ..\n$ cd ..\n$ cd ..").is_err());
+        assert!(parse_to_tree("$ cd /\n$ rm -rf").is_err());
     }
 }
@@ -1,26 +1,34 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 use itertools::FoldWhile::{Continue, Done};
 use itertools::Itertools;
 
 pub struct Solution {}
 
-struct Grid {
+pub struct Grid {
     data: Vec<u32>,
     w: usize,
     h: usize,
 }
 
 impl Grid {
-    fn new(input: &str) -> Self {
+    fn new(input: &str) -> Result<Self, ParseError> {
         let rows: Vec<&str> = input.lines().collect();
         let h = rows.len();
-        let w = rows[0].len();
-        let data = rows
-            .iter()
-            .flat_map(|s| s.chars().map(|c| c.to_digit(10).unwrap()))
-            .collect();
+        let w = rows.first().map_or(0, |r| r.len());
+        let mut data = Vec::with_capacity(w * h);
+        for row in rows {
+            if row.len() != w {
+                return Err(ParseError::at(input, row, format!("expected a row of {} trees", w)));
+            }
+            for (i, c) in row.char_indices() {
+                let height = c.to_digit(10).ok_or_else(|| {
+                    ParseError::at(input, &row[i..i + c.len_utf8()], "expected a digit")
+                })?;
+                data.push(height);
+            }
+        }
 
-        Self { data, w, h }
+        Ok(Self { data, w, h })
     }
     fn get(&self, (x, y): (usize, usize)) -> Option<&u32> {
         if x < self.w && y < self.h {
@@ -110,15 +118,34 @@ impl Grid {
     }
 }
 
+const EXAMPLE: &str = "30373
+25512
+65332
+33549
+35390
+";
+
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let grid = Grid::new(_input);
+    type Input = Grid;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("21"),
+            part2: Some("8"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(Grid::new(input)?)
+    }
+
+    fn part1(&self, grid: &Self::Input) -> Option<String> {
         let answer = grid.count_visible();
         Some(answer.to_string())
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
-        let grid = Grid::new(_input);
+    fn part2(&self, grid: &Self::Input) -> Option<String> {
         let answer = grid.best_tree();
         Some(answer.to_string())
     }
@@ -128,26 +155,13 @@ impl Problem for Solution {
 mod tests {
     use super::*;
 
-    const INPUT: &str = "30373
-25512
-65332
-33549
-35390
-";
-
-    // const INPUT: &str = include_str!("../../inputs/08.in");
+    aoc_core::example_tests!(Solution {});
 
     #[test]
-    fn test_day08a() {
-        let grid = Grid::new(INPUT);
-        let answer = grid.count_visible();
-        assert_eq!(answer, 21);
-    }
-
-    #[test]
-    fn test_day08b() {
-        let grid = Grid::new(INPUT);
-        let answer = grid.best_tree();
-        assert_eq!(answer, 8);
+    fn test_parse_errors() {
+        let e = Grid::new("303\n2x5\n").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 2, "x"));
+        let e = Grid::new("303\n25\n").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "25"));
     }
 }
@@ -1,26 +1,78 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Context, Example, Frame, ParseError, Problem};
 use std::collections::HashMap;
 
 pub struct Solution {}
 
+/// A step direction and how many steps to take.
+type Move = ((i32, i32), u32);
+
+fn parse_moves(input: &str) -> Result<Vec<Move>, ParseError> {
+    input
+        .lines()
+        .map(|l| {
+            let (dir, mag) = l
+                .split_once(" ")
+                .ok_or_else(|| ParseError::at(input, l, "expected a move like 'R 4'"))?;
+            let dp = match dir {
+                "U" => (0, 1),
+                "D" => (0, -1),
+                "L" => (-1, 0),
+                "R" => (1, 0),
+                _ => return Err(ParseError::at(input, dir, "expected U, D, L or R")),
+            };
+            Ok((dp, ParseError::number(input, mag)?))
+        })
+        .collect()
+}
+
+const EXAMPLE: &str = "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2
+";
+
+const LARGER_EXAMPLE: &str = "R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20
+";
+
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
+    type Input = Vec<Move>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("13"),
+            part2: Some("1"),
+        },
+        Example {
+            input: LARGER_EXAMPLE,
+            part1: None,
+            part2: Some("36"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_moves(input)?)
+    }
+
+    fn part1(&self, moves: &Self::Input) -> Option<String> {
         let mut head_pos: (i32, i32) = (0, 0);
         let mut tail_pos: (i32, i32) = (0, 0);
         let mut tail_locations: HashMap<(i32, i32), u32> = HashMap::new();
-        _input
-            .lines()
-            .map(|l| l.split_once(" ").unwrap())
-            .for_each(|(dir, mag)| {
-                let dp = match dir {
-                    "U" => (0, 1),
-                    "D" => (0, -1),
-                    "L" => (-1, 0),
-                    "R" => (1, 0),
-                    _ => unreachable!(),
-                };
-
-                (0..mag.parse::<u32>().unwrap()).for_each(|_| {
+        moves
+            .iter()
+            .for_each(|&(dp, mag)| {
+                (0..mag).for_each(|_| {
                     let new_head_pos = (head_pos.0 + dp.0, head_pos.1 + dp.1);
                     if chebychev_dist(tail_pos, new_head_pos) > 1 {
                         tail_pos = head_pos;
@@ -30,57 +82,69 @@ impl Problem for Solution {
                 });
             });
 
-        let answer = tail_locations.iter().count();
+        let answer = tail_locations.len();
         Some(answer.to_string())
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
+    fn part2(&self, moves: &Self::Input) -> Option<String> {
         let knots = 9;
         let mut head_pos: (i32, i32) = (0, 0);
         let mut tail_pos: Vec<(i32, i32)> = vec![(0, 0); knots];
         let mut tail_locations: HashMap<(i32, i32), u32> = HashMap::new();
         tail_locations.insert((0, 0), 1);
-        _input
-            .lines()
-            .map(|l| l.split_once(" ").unwrap())
-            .for_each(|(dir, mag)| {
-                let dp = match dir {
-                    "U" => (0, 1),
-                    "D" => (0, -1),
-                    "L" => (-1, 0),
-                    "R" => (1, 0),
-                    _ => unreachable!(),
-                };
-
-                for _ in 0..mag.parse().unwrap() {
+        let ctx = Context::current();
+        moves
+            .iter()
+            .for_each(|&(dp, mag)| {
+                for _ in 0..mag {
                     head_pos = (head_pos.0 + dp.0, head_pos.1 + dp.1);
                     let mut parent = head_pos;
-                    for i in 0..knots {
-                        if chebychev_dist(parent, tail_pos[i]) > 1 {
-                            let dx = tail_pos[i].0 - parent.0;
-                            let dy = tail_pos[i].1 - parent.1;
+                    for knot in tail_pos.iter_mut() {
+                        if chebychev_dist(parent, *knot) > 1 {
+                            let dx = knot.0 - parent.0;
+                            let dy = knot.1 - parent.1;
                             if dx.abs() > 1 && dy.abs() > 1 {
-                                tail_pos[i].0 -= dx.signum();
-                                tail_pos[i].1 -= dy.signum();
+                                knot.0 -= dx.signum();
+                                knot.1 -= dy.signum();
                             } else if dx.abs() > 1 {
-                                tail_pos[i].0 -= dx.signum();
-                                tail_pos[i].1 = parent.1;
+                                knot.0 -= dx.signum();
+                                knot.1 = parent.1;
                             } else if dy.abs() > 1 {
-                                tail_pos[i].0 = parent.0;
-                                tail_pos[i].1 -= dy.signum();
+                                knot.0 = parent.0;
+                                knot.1 -= dy.signum();
                             }
                         }
-                        parent = tail_pos[i];
+                        parent = *knot;
                     }
                     *tail_locations.entry(tail_pos[knots - 1]).or_insert(0) += 1;
                 }
+                ctx.frame(|| draw_rope(head_pos, &tail_pos, &tail_locations));
             });
 
-        let answer = tail_locations.iter().count();
+        let answer = tail_locations.len();
         Some(answer.to_string())
     }
 }
 
+/// The visited squares with the rope on top, north up.
+fn draw_rope(
+    head_pos: (i32, i32),
+    tail_pos: &[(i32, i32)],
+    tail_locations: &HashMap<(i32, i32), u32>,
+) -> Frame {
+    let visited = tail_locations.keys().map(|&p| (p, '#'));
+    let knots = tail_pos
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, &p)| (p, char::from_digit(i as u32 + 1, 10).unwrap()));
+    let points = visited
+        .chain(knots)
+        .chain([(head_pos, 'H')])
+        .map(|((x, y), c)| ((x as isize, -y as isize), c));
+    Frame::from_points(points).annotate(format!("{} squares visited", tail_locations.len()))
+}
+
 fn chebychev_dist(a: (i32, i32), b: (i32, i32)) -> u32 {
     (a.0 - b.0).abs().max((a.1 - b.1).abs()) as u32
 }
@@ -89,36 +153,13 @@ fn chebychev_dist(a: (i32, i32), b: (i32, i32)) -> u32 {
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2
-";
-
-    const TEST_INPUT2: &str = "R 5
-U 8
-L 8
-D 3
-R 17
-D 10
-L 25
-U 20
-";
-    #[test]
-    fn test_day09a() {
-        let solver = Solution {};
-        let answer = solver.part1(TEST_INPUT).unwrap();
-        assert_eq!(answer, "13");
-    }
+    aoc_core::example_tests!(Solution {});
 
     #[test]
-    fn test_day09b() {
-        let solver = Solution {};
-        assert_eq!(solver.part2(TEST_INPUT).unwrap(), "1");
-        assert_eq!(solver.part2(TEST_INPUT2).unwrap(), "36");
+    fn test_parse_errors() {
+        let e = parse_moves("R 4\nX 4").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "X"));
+        let e = parse_moves("R 4\nU -1").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 3, "-1"));
     }
 }
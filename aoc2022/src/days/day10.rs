@@ -2,12 +2,12 @@ use std::fmt;
 
 use itertools::Itertools;
 
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 
 pub struct Solution {}
 
 #[derive(Copy, Clone)]
-enum Opcode {
+pub enum Opcode {
     NoOp,
     Add(i32),
 }
@@ -52,7 +52,7 @@ impl fmt::Display for CRTScreen {
     }
 }
 
-struct CPU {
+struct Cpu {
     instructions: Vec<Opcode>,
     register_file: Vec<i32>,
     program_counter: usize,
@@ -60,7 +60,7 @@ struct CPU {
     wait: usize,
 }
 
-impl CPU {
+impl Cpu {
     fn new(instructions: Vec<Opcode>, n_registers: usize, initial_value: i32) -> Self {
         Self {
             instructions,
@@ -96,54 +96,22 @@ impl CPU {
     }
 }
 
-fn parse_input(input: &str) -> Vec<Opcode> {
+fn parse_input(input: &str) -> Result<Vec<Opcode>, ParseError> {
     input
         .lines()
-        .map(|l| match l.split(" ").collect::<Vec<&str>>()[..] {
-            ["addx", n] => Opcode::Add(n.parse().unwrap()),
-            ["noop"] => Opcode::NoOp,
-            _ => unreachable!(),
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| match l.split_whitespace().collect::<Vec<&str>>()[..] {
+            ["addx", n] => n
+                .parse()
+                .map(Opcode::Add)
+                .map_err(|_| ParseError::at(input, n, "expected a number")),
+            ["noop"] => Ok(Opcode::NoOp),
+            _ => Err(ParseError::at(input, l, "expected 'noop' or 'addx <n>'")),
         })
         .collect()
 }
 
-impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let instructions = parse_input(_input);
-        let mut cpu = CPU::new(instructions, 1, 1);
-
-        let mut answer = 0;
-        for i in 0..40 * 6 {
-            if (i - 20) % 40 == 0 {
-                answer += i as i32 * cpu.get(0);
-            }
-            cpu.tick();
-        }
-        Some(answer.to_string())
-    }
-
-    fn part2(&self, _input: &str) -> Option<String> {
-        let instructions = parse_input(_input);
-        let mut cpu = CPU::new(instructions, 1, 1);
-        let mut display = CRTScreen::new(40, 6);
-
-        let in_bounds = |c: i32, p| (p - 1) <= (c % 40) && (c % 40) <= (p + 1);
-
-        for i in 0..40 * 6 {
-            cpu.tick();
-            if in_bounds(i as i32, cpu.get(0)) {
-                display.buffer[i] = '#';
-            }
-        }
-        Some(format!("\n{}", display))
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_INPUT: &str = "addx 15
+const EXAMPLE: &str = "addx 15
 addx -11
 addx 6
 addx -3
@@ -291,23 +259,61 @@ noop
 noop
 ";
 
-    #[test]
-    fn test_day10a() {
-        let solver = Solution {};
-        assert_eq!(solver.part1(TEST_INPUT).unwrap(), "13140");
-    }
-    #[test]
-    fn test_day10b() {
-        let solver = Solution {};
-        let answer = solver.part2(TEST_INPUT).unwrap();
-
-        let expected = "
+const EXAMPLE_SCREEN: &str = "
 ##..##..##..##..##..##..##..##..##..##..
 ###...###...###...###...###...###...###.
 ####....####....####....####....####....
 #####.....#####.....#####.....#####.....
 ######......######......######......####
 #######.......#######.......#######.....";
-        assert_eq!(answer, expected);
+
+impl Problem for Solution {
+    type Input = Vec<Opcode>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("13140"),
+            part2: Some(EXAMPLE_SCREEN),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(&self, instructions: &Self::Input) -> Option<String> {
+        let mut cpu = Cpu::new(instructions.clone(), 1, 1);
+
+        let mut answer = 0;
+        for i in 0..40 * 6 {
+            if (i - 20) % 40 == 0 {
+                answer += i * cpu.get(0);
+            }
+            cpu.tick();
+        }
+        Some(answer.to_string())
+    }
+
+    fn part2(&self, instructions: &Self::Input) -> Option<String> {
+        let mut cpu = Cpu::new(instructions.clone(), 1, 1);
+        let mut display = CRTScreen::new(40, 6);
+
+        let in_bounds = |c: i32, p| (p - 1) <= (c % 40) && (c % 40) <= (p + 1);
+
+        for i in 0..40 * 6 {
+            cpu.tick();
+            if in_bounds(i as i32, cpu.get(0)) {
+                display.buffer[i] = '#';
+            }
+        }
+        Some(format!("\n{}", display))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_core::example_tests!(Solution {});
+}
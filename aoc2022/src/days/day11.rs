@@ -1,23 +1,27 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 use std::collections::VecDeque;
 
 pub struct Solution {}
 
+#[derive(Clone)]
 enum Value {
     Old,
     Const(i64),
 }
 
+#[derive(Clone)]
 enum Op {
     Multiply(Value, Value),
     Add(Value, Value),
 }
 
+#[derive(Clone)]
 enum Cond {
     IfDivisibleBy(i64, usize, usize),
 }
 
-struct Monkey {
+#[derive(Clone)]
+pub struct Monkey {
     _id: usize,
     items: VecDeque<i64>,
     op: Op,
@@ -26,14 +30,11 @@ struct Monkey {
 
 impl Monkey {
     fn inspect(&mut self, denom: i64, use_mod: bool) -> Option<i64> {
-        match self.next() {
-            Some(item) => Some(if use_mod {
+        self.next().map(|item| if use_mod {
                 self.op(item) % denom
             } else {
                 self.op(item) / denom
-            }),
-            None => None,
-        }
+            })
     }
 
     fn test(&self, item: i64) -> usize {
@@ -81,65 +82,91 @@ impl Monkey {
     }
 }
 
-fn parse_input(raw: &str) -> Vec<Monkey> {
-    raw.split("\n\n")
-        .map(|m| parse_monkey(m))
-        .collect::<Vec<Monkey>>()
+fn parse_input(input: &str) -> Result<Vec<Monkey>, ParseError> {
+    let monkeys = input
+        .split("\n\n")
+        .map(|raw| parse_monkey(input, raw))
+        .collect::<Result<Vec<Monkey>, ParseError>>()?;
+
+    for (m, raw) in monkeys.iter().zip(input.split("\n\n")) {
+        let Cond::IfDivisibleBy(_, t, f) = m.cond;
+        if t.max(f) >= monkeys.len() {
+            return Err(ParseError::at(
+                input,
+                raw,
+                format!("throws to a monkey past the last, {}", monkeys.len() - 1),
+            ));
+        }
+    }
+    Ok(monkeys)
 }
 
-fn parse_monkey(raw: &str) -> Monkey {
-    let lines: Vec<&str> = raw.lines().collect();
-
-    Monkey {
-        _id: parse_id(lines[0]),
-        items: parse_items(lines[1]),
-        op: parse_op(lines[2]),
-        cond: parse_cond(lines[3], lines[4], lines[5]),
-    }
+/// The rest of the next line of `raw` after `prefix`, ignoring indentation.
+fn field<'a>(
+    input: &str,
+    raw: &'a str,
+    lines: &mut std::str::Lines<'a>,
+    prefix: &str,
+) -> Result<&'a str, ParseError> {
+    let Some(line) = lines.next() else {
+        return Err(ParseError::at(input, &raw[raw.len()..], format!("expected '{}'", prefix)));
+    };
+    line.trim_start()
+        .strip_prefix(prefix)
+        .ok_or_else(|| ParseError::at(input, line, format!("expected '{}'", prefix)))
 }
 
-fn parse_items(raw: &str) -> VecDeque<i64> {
-    raw.split(": ")
-        .last()
-        .unwrap()
-        .split(", ")
-        .map(|n| n.parse::<i64>().unwrap())
-        .collect::<VecDeque<i64>>()
+fn parse_monkey(input: &str, raw: &str) -> Result<Monkey, ParseError> {
+    let mut lines = raw.lines();
+    let id = field(input, raw, &mut lines, "Monkey ")?;
+    let id = id
+        .strip_suffix(":")
+        .ok_or_else(|| ParseError::at(input, id, "expected a monkey id like '0:'"))?;
+    let _id = ParseError::number(input, id)?;
+    let items = parse_items(input, field(input, raw, &mut lines, "Starting items: ")?)?;
+    let op = parse_op(input, field(input, raw, &mut lines, "Operation: new = ")?)?;
+    let c = field(input, raw, &mut lines, "Test: divisible by ")?;
+    let t = field(input, raw, &mut lines, "If true: throw to monkey ")?;
+    let f = field(input, raw, &mut lines, "If false: throw to monkey ")?;
+
+    Ok(Monkey {
+        _id,
+        items,
+        op,
+        cond: parse_cond(input, c, t, f)?,
+    })
 }
 
-fn parse_id(raw: &str) -> usize {
-    raw.split(" ")
-        .last()
-        .unwrap()
-        .replace(":", "")
-        .parse::<usize>()
-        .unwrap()
+fn parse_items(input: &str, raw: &str) -> Result<VecDeque<i64>, ParseError> {
+    raw.split(", ")
+        .map(|n| ParseError::number(input, n))
+        .collect()
 }
 
-fn parse_value(raw: &str) -> Value {
+fn parse_value(input: &str, raw: &str) -> Result<Value, ParseError> {
     match raw {
-        "old" => Value::Old,
-        n => Value::Const(n.parse().unwrap()),
+        "old" => Ok(Value::Old),
+        n => Ok(Value::Const(ParseError::number(input, n)?)),
     }
 }
 
-fn parse_op(raw: &str) -> Op {
-    let (r1, op, r2) = match raw.split(" ").collect::<Vec<&str>>()[5..] {
+fn parse_op(input: &str, raw: &str) -> Result<Op, ParseError> {
+    let (r1, op, r2) = match raw.split(" ").collect::<Vec<&str>>()[..] {
         [r1, op, r2] => (r1, op, r2),
-        _ => unreachable!(),
+        _ => return Err(ParseError::at(input, raw, "expected an operation like 'old * 19'")),
     };
-    let r1 = parse_value(r1);
-    let r2 = parse_value(r2);
+    let r1 = parse_value(input, r1)?;
+    let r2 = parse_value(input, r2)?;
 
     match op {
-        "*" => Op::Multiply(r1, r2),
-        "+" => Op::Add(r1, r2),
-        _ => unreachable!(),
+        "*" => Ok(Op::Multiply(r1, r2)),
+        "+" => Ok(Op::Add(r1, r2)),
+        _ => Err(ParseError::at(input, op, "expected '*' or '+'")),
     }
 }
 
 fn play_rounds(
-    monkeys: &mut Vec<Monkey>,
+    monkeys: &mut [Monkey],
     n_rounds: usize,
     worry_denom: i64,
     use_mod: bool,
@@ -173,44 +200,19 @@ fn play_rounds(
     inspection_counter
 }
 
-fn parse_cond(c: &str, t: &str, f: &str) -> Cond {
-    let c = c.split(" ").last().unwrap().parse::<i64>().unwrap();
-    let t = t.split(" ").last().unwrap().parse::<usize>().unwrap();
-    let f = f.split(" ").last().unwrap().parse::<usize>().unwrap();
-    Cond::IfDivisibleBy(c, t, f)
-}
-
-impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let mut monkeys = parse_input(_input);
-        let inspection_counter = play_rounds(&mut monkeys, 20, 3, false);
-        let answer = inspection_counter
-            .iter()
-            .rev()
-            .take(2)
-            .fold(1, |acc, &n| acc * n);
-
-        Some(answer.to_string())
-    }
-
-    fn part2(&self, _input: &str) -> Option<String> {
-        let mut monkeys = parse_input(_input);
-        let inspection_counter = play_rounds(&mut monkeys, 10000, 0, true);
-        let answer = inspection_counter
-            .iter()
-            .rev()
-            .take(2)
-            .fold(1, |acc, &n| acc * n);
-
-        Some(answer.to_string())
+fn parse_cond(input: &str, c: &str, t: &str, f: &str) -> Result<Cond, ParseError> {
+    let denom = ParseError::number(input, c)?;
+    if denom <= 0 {
+        return Err(ParseError::at(input, c, "expected a positive divisor"));
     }
+    Ok(Cond::IfDivisibleBy(
+        denom,
+        ParseError::number(input, t)?,
+        ParseError::number(input, f)?,
+    ))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_INPUT: &str = "Monkey 0:
+const EXAMPLE: &str = "Monkey 0:
   Starting items: 79, 98
   Operation: new = old * 19
   Test: divisible by 23
@@ -239,14 +241,61 @@ Monkey 3:
     If false: throw to monkey 1
 ";
 
-    #[test]
-    fn test_day11a() {
-        let solver = Solution {};
-        assert_eq!(solver.part1(TEST_INPUT).unwrap(), "10605");
+impl Problem for Solution {
+    type Input = Vec<Monkey>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("10605"),
+            part2: Some("2713310158"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(&self, monkeys: &Self::Input) -> Option<String> {
+        let mut monkeys = monkeys.clone();
+        let inspection_counter = play_rounds(&mut monkeys, 20, 3, false);
+        let answer = inspection_counter
+            .iter()
+            .rev()
+            .take(2)
+            .product::<usize>();
+
+        Some(answer.to_string())
+    }
+
+    fn part2(&self, monkeys: &Self::Input) -> Option<String> {
+        let mut monkeys = monkeys.clone();
+        let inspection_counter = play_rounds(&mut monkeys, 10000, 0, true);
+        let answer = inspection_counter
+            .iter()
+            .rev()
+            .take(2)
+            .product::<usize>();
+
+        Some(answer.to_string())
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_core::example_tests!(Solution {});
+
     #[test]
-    fn test_day11b() {
-        let solver = Solution {};
-        assert_eq!(solver.part2(TEST_INPUT).unwrap(), "2713310158");
+    fn test_parse_errors() {
+        let bad = EXAMPLE.replace("old * 19", "old / 19");
+        let e = parse_input(&bad).err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (3, 24, "/"));
+        let bad = EXAMPLE.replace("monkey 3", "monkey 4");
+        let e = parse_input(&bad).err().unwrap();
+        assert_eq!((e.line, e.column), (1, 1));
+        let e = parse_input("Monkey 0:\n  Starting items: 1, x").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 22, "x"));
     }
 }
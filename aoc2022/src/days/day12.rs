@@ -1,6 +1,6 @@
 use std::collections::{BinaryHeap, HashMap, HashSet};
 
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 
 pub struct Solution {}
 
@@ -8,7 +8,7 @@ const OFFSETS: [(isize, isize); 4] = [(1, 0), (0, -1), (-1, 0), (0, 1)];
 
 type Vertex = (isize, isize);
 
-struct Environment {
+pub struct Environment {
     cost_map: HashMap<Vertex, usize>,
     start: Vertex,
     end: Vertex,
@@ -53,27 +53,34 @@ fn get_neighbors(center: &Vertex, width: isize, height: isize) -> Vec<Vertex> {
         .collect()
 }
 
-fn parse_input(input: &str) -> Environment {
+fn parse_input(input: &str) -> Result<Environment, ParseError> {
     let mut cost_map = HashMap::new();
-    let mut start = (0, 0);
-    let mut end = (0, 0);
+    let mut start = None;
+    let mut end = None;
     let height = input.lines().count();
-    let width = input.lines().next().unwrap().chars().count();
+    let width = input.lines().next().map_or(0, |r| r.len());
 
     for (y, row) in input.lines().enumerate() {
-        for (x, c) in row.chars().enumerate() {
+        if row.len() != width {
+            return Err(ParseError::at(input, row, format!("expected a row {} wide", width)));
+        }
+        for (x, c) in row.char_indices() {
             let (x, y) = (x as isize, y as isize);
 
             let cost = match c {
-                'S' => {
-                    start = (x, y);
+                'S' if start.is_none() => {
+                    start = Some((x, y));
                     'a'
                 }
-                'E' => {
-                    end = (x, y);
+                'E' if end.is_none() => {
+                    end = Some((x, y));
                     'z'
                 }
-                _ => c,
+                'a'..='z' => c,
+                _ => {
+                    let text = &row[x as usize..x as usize + c.len_utf8()];
+                    return Err(ParseError::at(input, text, "expected a-z or one each of S and E"));
+                }
             } as usize
                 - 'a' as usize;
 
@@ -81,13 +88,17 @@ fn parse_input(input: &str) -> Environment {
         }
     }
 
-    Environment {
+    let (Some(start), Some(end)) = (start, end) else {
+        return Err(ParseError::at(input, &input[input.len()..], "expected a start S and an end E"));
+    };
+
+    Ok(Environment {
         cost_map,
         start,
         end,
         width,
         height,
-    }
+    })
 }
 
 fn create_adj_list(
@@ -137,7 +148,7 @@ fn dijkstras(
         if let Some(neighbors) = adj_list.get(&vertex) {
             for (neighbor, cost) in neighbors {
                 let new_distance = dist + cost;
-                let is_shorter = distances.get(neighbor).map_or(true, |&d| new_distance < d);
+                let is_shorter = distances.get(neighbor).is_none_or(|&d| new_distance < d);
                 if is_shorter {
                     distances.insert(*neighbor, new_distance);
                     to_visit.push(Visit {
@@ -152,18 +163,36 @@ fn dijkstras(
     distances
 }
 
+const EXAMPLE: &str = "Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi";
+
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let goal = parse_input(_input);
+    type Input = Environment;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("31"),
+            part2: Some("29"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(&self, goal: &Self::Input) -> Option<String> {
         let adj_list = create_adj_list(&goal.cost_map, (goal.width, goal.height), true);
         let distances = dijkstras(vec![goal.start], &adj_list);
-        let answer = *distances.get(&goal.end).unwrap();
+        let answer = *distances.get(&goal.end)?;
 
         Some(answer.to_string())
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
-        let goal = parse_input(_input);
+    fn part2(&self, goal: &Self::Input) -> Option<String> {
         let adj_list = create_adj_list(&goal.cost_map, (goal.width, goal.height), true);
         let starts = goal
             .cost_map
@@ -172,7 +201,7 @@ impl Problem for Solution {
             .map(|(&k, _)| k)
             .collect::<Vec<Vertex>>();
         let distances = dijkstras(starts, &adj_list);
-        let answer = *distances.get(&goal.end).unwrap();
+        let answer = *distances.get(&goal.end)?;
 
         Some(answer.to_string())
     }
@@ -182,21 +211,15 @@ impl Problem for Solution {
 mod tests {
     use super::*;
 
-    const INPUT: &str = "Sabqponm
-abcryxxl
-accszExk
-acctuvwj
-abdefghi";
-
-    #[test]
-    fn test_day12a() {
-        let solver = Solution {};
-        assert_eq!(solver.part1(INPUT).unwrap(), "31");
-    }
+    aoc_core::example_tests!(Solution {});
 
     #[test]
-    fn test_day12b() {
-        let solver = Solution {};
-        assert_eq!(solver.part2(INPUT).unwrap(), "29");
+    fn test_parse_errors() {
+        let e = parse_input("Sab\naSE").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 2, "S"));
+        let e = parse_input("Sab\nab").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "ab"));
+        let e = parse_input("Sab\nabc").err().unwrap();
+        assert_eq!((e.line, e.column), (2, 4));
     }
 }
@@ -1,21 +1,38 @@
 use std::cmp::Ordering;
+use std::fmt;
 
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 
 pub struct Solution {}
 
-#[derive(Debug, Eq, PartialEq, Clone)]
-enum Packet {
+#[derive(Debug, Clone)]
+pub enum Packet {
     List(Vec<Packet>),
     Item(u8),
 }
 
+// Packets are equal when they are in order either way round, so `1` and
+// `[1]` are the same packet.
+impl PartialEq for Packet {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Packet {}
+
 impl PartialOrd for Packet {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
         use Packet::*;
         match (self, other) {
-            (Item(ref a), Item(ref b)) => a.partial_cmp(b),
-            (List(ref lists), List(ref other_lists)) => lists
+            (Item(a), Item(b)) => a.cmp(b),
+            (List(lists), List(other_lists)) => lists
                 .iter()
                 .zip(other_lists)
                 .fold(None, |ord, (i1, i2)| {
@@ -25,76 +42,152 @@ impl PartialOrd for Packet {
                         Ordering::Greater => Some(Ordering::Greater),
                     })
                 })
-                .or_else(|| lists.len().partial_cmp(&other_lists.len())),
-            (List(_), Item(_)) => self.partial_cmp(&List(vec![other.clone()])),
-            (Item(_), List(_)) => List(vec![self.clone()]).partial_cmp(other),
+                .unwrap_or_else(|| lists.len().cmp(&other_lists.len())),
+            (List(_), Item(_)) => self.cmp(&List(vec![other.clone()])),
+            (Item(_), List(_)) => List(vec![self.clone()]).cmp(other),
         }
     }
 }
 
-impl Ord for Packet {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Packet::Item(n) => write!(f, "{}", n),
+            Packet::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+        }
     }
 }
-fn parse_list_members(line: &[u8], pos: &mut usize) -> Packet {
+
+/// Deeper lists than this are rejected rather than risk the stack.
+const MAX_DEPTH: usize = 256;
+
+/// The byte offset of a parse error and what was expected there.
+type Error = (usize, &'static str);
+
+fn parse_list_members(line: &[u8], pos: &mut usize, depth: usize) -> Result<Packet, Error> {
     let mut result = vec![];
-    while line[*pos] != b']' {
-        if line[*pos] == b'[' {
-            result.push(parse_bytes(line, pos));
-            if line[*pos] == b',' {
-                *pos += 1;
-                continue;
-            }
-        } else if line[*pos].is_ascii_digit() {
-            result.push(parse_number(line, pos));
-            if line[*pos] == b',' {
-                *pos += 1;
-                continue;
-            }
+    if line.get(*pos) == Some(&b']') {
+        return Ok(Packet::List(result));
+    }
+    loop {
+        result.push(parse_bytes(line, pos, depth)?);
+        match line.get(*pos) {
+            Some(b',') => *pos += 1,
+            Some(b']') => return Ok(Packet::List(result)),
+            _ => return Err((*pos, "expected ',' or ']'")),
         }
     }
-    Packet::List(result)
 }
 
-fn parse_number(line: &[u8], pos: &mut usize) -> Packet {
-    let mut result = 0;
-    while line[*pos].is_ascii_digit() {
-        result = result * 10 + (line[*pos] - b'0');
+fn parse_number(line: &[u8], pos: &mut usize) -> Result<Packet, Error> {
+    let start = *pos;
+    let mut result: u8 = 0;
+    while let Some(digit) = line.get(*pos).filter(|b| b.is_ascii_digit()) {
+        result = result
+            .checked_mul(10)
+            .and_then(|r| r.checked_add(digit - b'0'))
+            .ok_or((start, "number too large"))?;
         *pos += 1;
     }
-    Packet::Item(result)
+    Ok(Packet::Item(result))
 }
 
-fn parse_bytes(line: &[u8], pos: &mut usize) -> Packet {
-    if line[*pos] == b'[' {
-        *pos += 1;
-        let result = parse_list_members(line, pos);
-        *pos += 1;
-        result
-    } else if line[*pos].is_ascii_digit() {
-        parse_number(line, pos)
-    } else {
-        panic!("Unknown data {}", line[*pos]);
+fn parse_bytes(line: &[u8], pos: &mut usize, depth: usize) -> Result<Packet, Error> {
+    match line.get(*pos) {
+        Some(b'[') if depth >= MAX_DEPTH => Err((*pos, "lists nested too deeply")),
+        Some(b'[') => {
+            *pos += 1;
+            let result = parse_list_members(line, pos, depth + 1)?;
+            *pos += 1;
+            Ok(result)
+        }
+        Some(b) if b.is_ascii_digit() => parse_number(line, pos),
+        _ => Err((*pos, "expected '[' or a number")),
     }
 }
 
-fn parse_line(line: &str) -> Packet {
-    let line = line.as_bytes();
-    parse_bytes(line, &mut 0)
+/// Parse a single packet, which must make up the whole line.
+pub fn parse_packet(line: &str) -> Result<Packet, Error> {
+    let bytes = line.as_bytes();
+    let mut pos = 0;
+    let packet = parse_bytes(bytes, &mut pos, 0)?;
+    if pos < bytes.len() {
+        return Err((pos, "unexpected data after the packet"));
+    }
+    Ok(packet)
 }
 
+const EXAMPLE: &str = "[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]
+";
+
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let answer: usize = _input
-            .split("\n\n")
-            .enumerate()
-            .map(|(ind, pair)| {
-                let (left, right) = pair.split_once('\n').unwrap();
-                let left_list = parse_line(left);
-                let right_list = parse_line(right);
-                (ind, left_list.cmp(&right_list))
+    type Input = Vec<Packet>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("13"),
+            part2: Some("140"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        let packets: Vec<Packet> = input
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                parse_packet(l).map_err(|(pos, message)| {
+                    // The offending byte, or the rest of the line if it starts a
+                    // wider character.
+                    let text = l.get(pos..pos + 1).unwrap_or(&l[pos.min(l.len())..]);
+                    ParseError::at(input, text, message)
+                })
             })
+            .collect::<Result<_, _>>()?;
+        // Part 1 compares the packets in pairs.
+        if !packets.len().is_multiple_of(2) {
+            let end = &input[input.len()..];
+            return Err(ParseError::at(input, end, "expected the last packet's pair").into());
+        }
+        Ok(packets)
+    }
+
+    fn part1(&self, packets: &Self::Input) -> Option<String> {
+        let answer: usize = packets
+            .chunks(2)
+            .enumerate()
+            .map(|(ind, pair)| (ind, pair[0].cmp(&pair[1])))
             .filter(|(_, o)| *o == Ordering::Less || *o == Ordering::Equal)
             .map(|(ind, _)| ind + 1)
             .sum::<usize>();
@@ -102,11 +195,9 @@ impl Problem for Solution {
         Some(answer.to_string())
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
-        let div1 = parse_line("[[2]]");
-        let div2 = parse_line("[[6]]");
-        let new_input = _input.replace("\n\n", "\n");
-        let packets = new_input.lines().map(|l| parse_line(l)).collect::<Vec<Packet>>();
+    fn part2(&self, packets: &Self::Input) -> Option<String> {
+        let div1 = parse_packet("[[2]]").unwrap();
+        let div2 = parse_packet("[[6]]").unwrap();
 
         let p1 = packets.iter().filter(|&p| p < &div1).count() + 1;
         let p2 = packets.iter().filter(|&p| p < &div2).count() + 2;
@@ -118,10 +209,13 @@ impl Problem for Solution {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    aoc_core::example_tests!(Solution {});
 
     fn check_ordering(input: &str, expected_ordering: Ordering) {
         let (left, right) = input.split_once("\n").unwrap();
-        let (left, right) = (parse_line(left), parse_line(right));
+        let (left, right) = (parse_packet(left).unwrap(), parse_packet(right).unwrap());
         let ordering = left.cmp(&right);
         assert_eq!(ordering, expected_ordering);
     }
@@ -145,43 +239,46 @@ mod tests {
         check_ordering("[[],4]\n[[],3]", Ordering::Greater);
         check_ordering("[4,[],4]\n[4,[],3]", Ordering::Greater);
         check_ordering("[1]\n[[1,2,3]]", Ordering::Less);
+        assert_eq!(Packet::Item(1), Packet::List(vec![Packet::Item(1)]));
     }
 
     #[test]
-    fn test_day13a_full() {
-        let input = vec![
-            "[1,1,3,1,1]\n[1,1,5,1,1]",
-            "[[1],[2,3,4]]\n[[1],4]",
-            "[9]\n[[8,7,6]]",
-            "[[4,4],4,4]\n[[4,4],4,4,4]",
-            "[7,7,7,7]\n[7,7,7]",
-            "[]\n[3]",
-            "[[[]]]\n[[]]",
-            "[1,[2,[3,[4,[5,6,7]]]],8,9]\n[1,[2,[3,[4,[5,6,0]]]],8,9]",
-        ]
-        .join("\n\n");
-
-        let solution = Solution {};
-        let answer = solution.part1(&input).unwrap();
-        assert_eq!(answer, "13");
+    fn test_day13_parse_packet() {
+        for line in EXAMPLE.lines().filter(|l| !l.is_empty()) {
+            assert_eq!(parse_packet(line).unwrap().to_string(), line);
+        }
+        assert_eq!(parse_packet("[1,"), Err((3, "expected '[' or a number")));
+        assert_eq!(parse_packet("[1 2]"), Err((2, "expected ',' or ']'")));
+        assert_eq!(parse_packet("[256]"), Err((1, "number too large")));
+        assert_eq!(parse_packet("[]]"), Err((2, "unexpected data after the packet")));
+        assert!(parse_packet("").is_err());
+        assert!(parse_packet(&"[".repeat(100_000)).is_err());
+        assert!(Solution {}.parse("[1]\n[[x]]\n").is_err());
+        let e = aoc_core::parse_error(&Solution {}, "[1]\n[2]\n\n[3]\n");
+        assert_eq!((e.line, e.column), (5, 1));
     }
 
-    #[test]
-    fn test_day13b() {
-        let input = vec![
-            "[1,1,3,1,1]\n[1,1,5,1,1]",
-            "[[1],[2,3,4]]\n[[1],4]",
-            "[9]\n[[8,7,6]]",
-            "[[4,4],4,4]\n[[4,4],4,4,4]",
-            "[7,7,7,7]\n[7,7,7]",
-            "[]\n[3]",
-            "[[[]]]\n[[]]",
-            "[1,[2,[3,[4,[5,6,7]]]],8,9]\n[1,[2,[3,[4,[5,6,0]]]],8,9]",
-        ]
-        .join("\n\n");
+    /// Packets with few distinct numbers, so that equal items and lists are
+    /// common.
+    fn packet() -> impl Strategy<Value = Packet> {
+        let item = (0u8..4).prop_map(Packet::Item);
+        item.prop_recursive(4, 32, 4, |inner| {
+            prop::collection::vec(inner, 0..4).prop_map(Packet::List)
+        })
+    }
 
-        let solution = Solution {};
-        let answer = solution.part2(&input).unwrap();
-        assert_eq!(answer, "140");
+    proptest! {
+        #[test]
+        fn test_day13_total_order(a in packet(), b in packet(), c in packet()) {
+            prop_assert_eq!(a.cmp(&a), Ordering::Equal);
+            prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+            prop_assert_eq!(a.cmp(&b) == Ordering::Equal, a == b);
+            let orders = [(&a, &b, &c), (&a, &c, &b), (&b, &a, &c), (&b, &c, &a), (&c, &a, &b), (&c, &b, &a)];
+            for (x, y, z) in orders {
+                if x <= y && y <= z {
+                    prop_assert!(x <= z, "{} <= {} <= {} but not {} <= {}", x, y, z, x, z);
+                }
+            }
+        }
     }
 }
@@ -2,7 +2,7 @@ use std::{
     collections::HashMap,
 };
 
-use crate::problem::Problem;
+use aoc_core::{AOCError, Context, Example, Frame, ParseError, Problem};
 
 pub struct Solution {}
 
@@ -11,15 +11,21 @@ type Point = (isize, isize);
 struct Line(Vec<Point>);
 
 impl Line {
-    fn from_input(input: &str) -> Self {
-        let out = input
-            .split(" -> ")
-            .map(|p| match p.split_once(',') {
-                Some((x, y)) => (x.parse().unwrap(), y.parse().unwrap()),
-                None => panic!("Failed interpreting line."),
-            })
-            .collect::<Vec<Point>>();
-        Line(out)
+    fn from_input(input: &str, line: &str) -> Result<Self, ParseError> {
+        let mut out: Vec<Point> = Vec::new();
+        for p in line.split(" -> ") {
+            let point = match p.split_once(',') {
+                Some((x, y)) => (ParseError::number(input, x)?, ParseError::number(input, y)?),
+                None => return Err(ParseError::at(input, p, "expected a point like '498,4'")),
+            };
+            if let Some(&(px, py)) = out.last() {
+                if px != point.0 && py != point.1 {
+                    return Err(ParseError::at(input, p, "expected a horizontal or vertical line"));
+                }
+            }
+            out.push(point);
+        }
+        Ok(Line(out))
     }
 
     fn points(&self) -> Vec<Point> {
@@ -49,17 +55,17 @@ impl Line {
     }
 }
 
-fn create_walls(input: &str) -> HashMap<Point, char> {
+fn create_walls(input: &str) -> Result<HashMap<Point, char>, ParseError> {
     let mut blocks: HashMap<Point, char> = HashMap::new();
 
-    input.lines().for_each(|l| {
-        Line::from_input(l)
+    for l in input.lines() {
+        Line::from_input(input, l)?
             .points()
             .iter()
             .for_each(|p| *blocks.entry(*p).or_insert('#') = '#')
-    });
+    }
 
-    blocks
+    Ok(blocks)
 }
 
 fn drop_sand(
@@ -96,25 +102,54 @@ fn drop_sand(
     ((x, y), false)
 }
 
+fn draw(blocks: &HashMap<Point, char>, n_blocks: usize) -> Frame {
+    let source = std::iter::once(((500, 0), '+'));
+    Frame::from_points(source.chain(blocks.iter().map(|(p, c)| (*p, *c))))
+        .annotate(format!("{} units of sand", n_blocks))
+}
+
+const EXAMPLE: &str = "498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9";
+
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let mut blocks = create_walls(_input);
-        let abyss_level = blocks.keys().map(|(_, y)| *y).max().unwrap() + 1;
+    type Input = HashMap<Point, char>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("24"),
+            part2: Some("93"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(create_walls(input)?)
+    }
+
+    fn part1(&self, walls: &Self::Input) -> Option<String> {
+        let mut blocks = walls.clone();
+        let abyss_level = blocks.keys().map(|(_, y)| *y).max()? + 1;
         let mut n_blocks = 0;
+        let ctx = Context::current();
         while drop_sand(&mut blocks, (500, 0), abyss_level, false).1 {
             n_blocks += 1;
+            ctx.frame(|| draw(&blocks, n_blocks));
         }
 
         Some(n_blocks.to_string())
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
-        let mut blocks = create_walls(_input);
-        let abyss_level = blocks.keys().map(|(_, y)| *y).max().unwrap() + 0;
+    fn part2(&self, walls: &Self::Input) -> Option<String> {
+        let mut blocks = walls.clone();
+        let abyss_level = blocks.keys().map(|(_, y)| *y).max()?;
         let mut n_blocks = 0;
+        let ctx = Context::current();
         loop {
             let (p, _) = drop_sand(&mut blocks, (500, 0), abyss_level, true);
             n_blocks += 1;
+            if n_blocks % 50 == 0 || p == (500, 0) {
+                ctx.frame(|| draw(&blocks, n_blocks));
+            }
             if p == (500, 0) {
                 break;
             }
@@ -128,20 +163,13 @@ impl Problem for Solution {
 mod tests {
     use super::*;
 
-    const INPUT: &str = "498,4 -> 498,6 -> 496,6
-503,4 -> 502,4 -> 502,9 -> 494,9";
-
-    #[test]
-    fn test_day14a() {
-        let solution = Solution {};
-        let answer = solution.part1(INPUT).unwrap();
-        assert_eq!(answer, "24");
-    }
+    aoc_core::example_tests!(Solution {});
 
     #[test]
-    fn test_day14b() {
-        let solution = Solution {};
-        let answer = solution.part2(INPUT).unwrap();
-        assert_eq!(answer, "93");
+    fn test_parse_errors() {
+        let e = create_walls("498,4 -> 498,6\n503,4 -> 502,x").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 14, "x"));
+        let e = create_walls("498,4 -> 497,6").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 10, "497,6"));
     }
 }
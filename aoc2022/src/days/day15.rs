@@ -1,39 +1,50 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, ParseError, Problem, Rng};
 use itertools::Itertools;
-use regex::Regex;
 
 pub struct Solution {}
 
 type Point = (isize, isize);
 
 #[derive(Debug)]
-struct Sensor {
+pub struct Sensor {
     pos: Point,
     beacon_pos: Point,
     dist: isize,
 }
 
 impl Sensor {
-    fn from_log(log: &str) -> Self {
-        let re = Regex::new(r"[x|y]=(?P<num>[-\d]*)").unwrap();
-        let c = re
-            .captures_iter(log)
-            .filter_map(|digits| digits["num"].parse().ok())
-            .collect::<Vec<isize>>();
-
-        Sensor {
-            pos: (c[0], c[1]),
-            beacon_pos: (c[2], c[3]),
-            dist: manhattan(&(c[0], c[1]), &(c[2], c[3])),
-        }
+    fn from_log(input: &str, log: &str) -> Result<Self, ParseError> {
+        let (pos, beacon_pos) = log
+            .strip_prefix("Sensor at ")
+            .and_then(|l| l.split_once(": closest beacon is at "))
+            .ok_or_else(|| {
+                ParseError::at(input, log, "expected 'Sensor at ..: closest beacon is at ..'")
+            })?;
+        let pos = parse_point(input, pos)?;
+        let beacon_pos = parse_point(input, beacon_pos)?;
+
+        Ok(Sensor {
+            pos,
+            beacon_pos,
+            dist: manhattan(&pos, &beacon_pos),
+        })
     }
 }
 
+/// Parse `text`, a slice of `input` like `x=2, y=18`.
+fn parse_point(input: &str, text: &str) -> Result<Point, ParseError> {
+    let (x, y) = text
+        .strip_prefix("x=")
+        .and_then(|t| t.split_once(", y="))
+        .ok_or_else(|| ParseError::at(input, text, "expected a position like 'x=2, y=18'"))?;
+    Ok((ParseError::number(input, x)?, ParseError::number(input, y)?))
+}
+
 fn manhattan((ax, ay): &Point, (bx, by): &Point) -> isize {
     (ax - bx).abs() + (ay - by).abs()
 }
 
-fn get_bounds(sensors: &Vec<Sensor>) -> Point {
+fn get_bounds(sensors: &[Sensor]) -> Point {
     sensors.iter().fold((0, 0), |(l, r), s| {
         let l = if s.pos.0 - s.dist < l {
             s.pos.0 - s.dist
@@ -49,32 +60,31 @@ fn get_bounds(sensors: &Vec<Sensor>) -> Point {
     })
 }
 
-fn check_coverage(point: &Point, sensors: &Vec<Sensor>) -> bool {
+fn check_coverage(point: &Point, sensors: &[Sensor]) -> bool {
     sensors.iter().any(|s| {
         if s.beacon_pos == *point {
             true
         } else {
             // get dist p->s
-            let dist = manhattan(&point, &s.pos);
+            let dist = manhattan(point, &s.pos);
             // cmp dist with s.dist
             dist <= s.dist
         }
     })
 }
 
-fn parse_input(input: &str) -> Vec<Sensor> {
+fn parse_input(input: &str) -> Result<Vec<Sensor>, ParseError> {
     input
         .lines()
-        .map(|l| Sensor::from_log(l))
-        .collect::<Vec<Sensor>>()
+        .map(|l| Sensor::from_log(input, l))
+        .collect()
 }
 
-fn count_covered_positions(input: &str, y: isize) -> isize {
-    let sensors = parse_input(input);
-    let (x0, x1) = get_bounds(&sensors);
+fn count_covered_positions(sensors: &[Sensor], y: isize) -> isize {
+    let (x0, x1) = get_bounds(sensors);
 
     (x0..=x1).fold(0, |acc, x| {
-        acc + if check_coverage(&(x, y), &sensors) {
+        acc + if check_coverage(&(x, y), sensors) {
             1
         } else {
             0
@@ -86,7 +96,7 @@ fn good_sensor_pair(s1: &Sensor, s2: &Sensor) -> bool {
     manhattan(&s1.pos, &s2.pos) == s1.dist + s2.dist + 2
 }
 
-fn get_candidates(sensors: &Vec<Sensor>, bounds: (isize, isize)) -> Vec<Point> {
+fn get_candidates(sensors: &[Sensor], bounds: (isize, isize)) -> Vec<Point> {
     sensors
         .iter()
         .cartesian_product(sensors.iter())
@@ -107,7 +117,7 @@ fn get_candidates(sensors: &Vec<Sensor>, bounds: (isize, isize)) -> Vec<Point> {
         .collect::<Vec<Point>>()
 }
 
-// fn get_candidates2(sensors: &Vec<Sensor>, (min, max): (isize, isize)) -> Vec<Point> {
+// fn get_candidates2(sensors: &[Sensor], (min, max): (isize, isize)) -> Vec<Point> {
 //     sensors
 //         .iter()
 //         .flat_map(|s| {
@@ -121,27 +131,110 @@ fn get_candidates(sensors: &Vec<Sensor>, bounds: (isize, isize)) -> Vec<Point> {
 //         .collect::<Vec<Point>>()
 // }
 
-fn pinpoint_beacon(input: &str, (min, max): Point) -> Point {
-    let sensors = parse_input(input);
-
-    *get_candidates(&sensors, (min, max))
+fn pinpoint_beacon(sensors: &[Sensor], (min, max): Point) -> Point {
+    *get_candidates(sensors, (min, max))
         .iter()
-        .find(|&p| !(check_coverage(&p, &sensors)))
+        .find(|&p| !(check_coverage(p, sensors)))
         .unwrap()
 }
 
+/// The side of the square part 2 searches.
+const SEARCH: isize = 4_000_000;
+
+fn sensor(pos: Point, beacon_pos: Point) -> Sensor {
+    Sensor {
+        pos,
+        beacon_pos,
+        dist: manhattan(&pos, &beacon_pos),
+    }
+}
+
+/// About `n` sensors that cover the square from 0 to `search` except for one
+/// position, which is returned too. A checkerboard of diamonds of radius `r`
+/// covers the plane; the ones covering the distress beacon make way for a
+/// ring of eight sensors around it, which report beacons on its four
+/// neighbours. Every beacon is on the edge of its sensor's range and inside
+/// no other.
+fn generate_sensors(rng: &mut Rng, n: usize, search: isize) -> (Vec<Sensor>, Point) {
+    let r = ((search as f64 / (2.0 * n as f64).sqrt()) as isize).max(2);
+    let hidden = (
+        rng.range(0..=search as i64 - 1) as isize,
+        rng.range(0..=search as i64 - 1) as isize,
+    );
+    let (hx, hy) = hidden;
+
+    let (ring, axis, diagonal) = (2 * r + 1, 2 * r + 2, r + 1);
+    let mut sensors = vec![
+        sensor((hx + axis, hy), (hx + 1, hy)),
+        sensor((hx - axis, hy), (hx - 1, hy)),
+        sensor((hx, hy + axis), (hx, hy + 1)),
+        sensor((hx, hy - axis), (hx, hy - 1)),
+        sensor((hx + diagonal, hy + diagonal), (hx + 1, hy)),
+        sensor((hx + diagonal, hy - diagonal), (hx + 1, hy)),
+        sensor((hx - diagonal, hy + diagonal), (hx - 1, hy)),
+        sensor((hx - diagonal, hy - diagonal), (hx - 1, hy)),
+    ];
+    debug_assert!(sensors.iter().all(|s| s.dist == ring));
+
+    let inside = |p: &Point| sensors.iter().any(|s| manhattan(p, &s.pos) < s.dist);
+    let ox = rng.range(0..=2 * r as i64) as isize;
+    let oy = rng.range(0..=2 * r as i64) as isize;
+    let steps = |o: isize| (-(o / r) - 2)..=((search - o) / r + 2);
+    let mut lattice = vec![];
+    for p in steps(ox) {
+        for q in steps(oy).filter(|q| (p + q) % 2 == 0) {
+            let c = (ox + p * r, oy + q * r);
+            if manhattan(&c, &hidden) <= r {
+                continue;
+            }
+            // The corners touch the neighbouring diamonds without entering
+            // them, so one clear of the ring makes a valid beacon. A diamond
+            // with none is inside the ring and not needed.
+            let corners = [(c.0 + r, c.1), (c.0 - r, c.1), (c.0, c.1 + r), (c.0, c.1 - r)];
+            if let Some(&corner) = corners.iter().find(|corner| !inside(corner)) {
+                lattice.push(sensor(c, corner));
+            }
+        }
+    }
+    sensors.extend(lattice);
+    rng.shuffle(&mut sensors);
+    (sensors, hidden)
+}
+
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let answer = count_covered_positions(_input, 2000000);
+    type Input = Vec<Sensor>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(&self, sensors: &Self::Input) -> Option<String> {
+        let answer = count_covered_positions(sensors, 2000000);
         Some(answer.to_string())
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
-        let (min, max) = (0, 4000000);
-        let (x, y) = pinpoint_beacon(_input, (min, max));
+    fn part2(&self, sensors: &Self::Input) -> Option<String> {
+        let (min, max) = (0, SEARCH);
+        let (x, y) = pinpoint_beacon(sensors, (min, max));
         let answer = x * 4000000 + y;
         Some(answer.to_string())
     }
+
+    /// About `size` sensors, 30 like a real input by default, that leave one
+    /// position in the search square for the distress beacon.
+    fn generate(&self, rng: &mut Rng, size: Option<usize>) -> Option<String> {
+        let (sensors, _) = generate_sensors(rng, size.unwrap_or(30), SEARCH);
+        let logs = sensors
+            .iter()
+            .map(|s| {
+                format!(
+                    "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+                    s.pos.0, s.pos.1, s.beacon_pos.0, s.beacon_pos.1
+                )
+            })
+            .collect::<Vec<String>>();
+        Some(logs.join("\n"))
+    }
 }
 
 #[cfg(test)]
@@ -165,15 +258,44 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3";
 
     #[test]
     fn test_day15a() {
-        let answer = count_covered_positions(INPUT, 10);
+        let answer = count_covered_positions(&parse_input(INPUT).unwrap(), 10);
         assert_eq!(answer, 26);
     }
 
     #[test]
     fn test_day15b() {
         let (min, max) = (0, 20);
-        let (x, y) = pinpoint_beacon(INPUT, (min, max));
+        let (x, y) = pinpoint_beacon(&parse_input(INPUT).unwrap(), (min, max));
         let answer = x * 4000000 + y;
         assert_eq!(answer, 56000011);
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let e = parse_input("Sensor at x=2, y=18: closest beacon is at x=-2, y=1x").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 51, "1x"));
+        let e = parse_input("Sensor at x=2, y=18\nSensor").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 1, "Sensor at x=2, y=18"));
+        let e = parse_input("Sensor at x=2: closest beacon is at x=-2, y=15").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 11, "x=2"));
+    }
+
+    #[test]
+    fn test_generate() {
+        let search = 100;
+        for seed in 0..200 {
+            let mut rng = Rng::new(seed);
+            let (sensors, hidden) = generate_sensors(&mut rng, 1 + seed as usize % 60, search);
+            // Only the hidden position in the square is left uncovered.
+            for x in 0..=search {
+                for y in 0..=search {
+                    assert_eq!(check_coverage(&(x, y), &sensors), (x, y) != hidden);
+                }
+            }
+            for s in &sensors {
+                assert!(sensors.iter().all(|o| manhattan(&s.beacon_pos, &o.pos) >= o.dist));
+            }
+            assert_eq!(pinpoint_beacon(&sensors, (0, search)), hidden);
+        }
+    }
 }
@@ -1,8 +1,6 @@
-use std::collections::{HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
-use regex::Regex;
-
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem, Rng};
 
 pub struct Solution {}
 
@@ -12,7 +10,7 @@ const TIME_TO_MOVE: i32 = 1;
 const TIME_TO_TEACH: i32 = 4;
 
 #[derive(Debug)]
-struct Valve {
+pub struct Valve {
     name: String,
     rate: i32,
     edges: Vec<String>,
@@ -38,26 +36,54 @@ impl State {
 
 }
 
-fn parse_input_to_valves(input: &str) -> Vec<Valve> {
-    let re = Regex::new(
-        r"Valve (?P<name>[A-Z]+) has flow rate=(?P<rate>[0-9]+); tunnels? leads? to valves? (?P<edges>.*)"
-    ).unwrap();
+/// A valve's name, flow rate and the valves its tunnels lead to.
+type ValveLine<'a> = (&'a str, i32, Vec<&'a str>);
+
+fn parse_valve<'a>(input: &str, l: &'a str) -> Result<ValveLine<'a>, ParseError> {
+    let bad = || {
+        ParseError::at(input, l, "expected 'Valve .. has flow rate=..; tunnels lead to valves ..'")
+    };
+    let (name, rest) = l
+        .strip_prefix("Valve ")
+        .and_then(|l| l.split_once(" has flow rate="))
+        .ok_or_else(bad)?;
+    let (rate, rest) = rest.split_once("; ").ok_or_else(bad)?;
+    let edges = rest
+        .strip_prefix("tunnels lead to valves ")
+        .or_else(|| rest.strip_prefix("tunnel leads to valve "))
+        .ok_or_else(bad)?;
+
+    Ok((name, ParseError::number(input, rate)?, edges.split(", ").collect()))
+}
 
-    input
+fn parse_input_to_valves(input: &str) -> Result<Vec<Valve>, ParseError> {
+    let valves = input
         .lines()
-        .map(|l| re.captures(l).unwrap())
-        .map(|obj| Valve {
-            name: obj["name"].to_string(),
-            rate: obj["rate"].parse().unwrap(),
-            edges: obj["edges"]
-                .split(", ")
-                .map(|s| s.to_string())
-                .collect::<Vec<String>>(),
+        .map(|l| parse_valve(input, l))
+        .collect::<Result<Vec<ValveLine>, ParseError>>()?;
+
+    // Every tunnel has to lead somewhere, and the search starts at AA.
+    let names = valves.iter().map(|v| v.0).collect::<HashSet<&str>>();
+    if !names.contains("AA") {
+        return Err(ParseError::at(input, &input[input.len()..], "expected a valve AA"));
+    }
+    for (_, _, edges) in &valves {
+        if let Some(edge) = edges.iter().find(|e| !names.contains(*e)) {
+            return Err(ParseError::at(input, edge, "no such valve"));
+        }
+    }
+
+    Ok(valves
+        .into_iter()
+        .map(|(name, rate, edges)| Valve {
+            name: name.to_string(),
+            rate,
+            edges: edges.into_iter().map(|s| s.to_string()).collect(),
         })
-        .collect()
+        .collect())
 }
 
-fn build_graph(valves: &Vec<Valve>) -> (HashMap<String, Vec<String>>, HashMap<String, i32>) {
+fn build_graph(valves: &[Valve]) -> (HashMap<String, Vec<String>>, HashMap<String, i32>) {
     let flow = valves
         .iter()
         .map(|v| (v.name.clone(), v.rate))
@@ -90,7 +116,7 @@ fn dfs2(state: &mut State, graph: &HashMap<String, Vec<String>>, flows: &HashMap
     let mut best = i32::MIN;
     let pos = &state.elephant_pos;
     let flow = *flows.get(pos).unwrap();
-    if flow > 0 && !state.open.contains(&pos) {
+    if flow > 0 && !state.open.contains(pos) {
         graph.get(pos).unwrap().iter().for_each(|child| {
             let mut new_state = state.clone();
             new_state.open.push(pos.clone());
@@ -162,7 +188,7 @@ fn dfs(
         graph.get(node).unwrap().iter().for_each(|child| {
             path.push(node.to_string());
 
-            let result = dfs(&child, path, graph, flows, time_left - TIME_TO_MOVE - TIME_TO_OPEN, cache);
+            let result = dfs(child, path, graph, flows, time_left - TIME_TO_MOVE - TIME_TO_OPEN, cache);
 
             best = best.max(score(result, time_left, *flows.get(node).unwrap()));
 
@@ -181,10 +207,35 @@ fn dfs(
 }
 
 
+const EXAMPLE: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II
+";
+
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let valves = parse_input_to_valves(_input);
-        let (graph, flows) = build_graph(&valves);
+    type Input = Vec<Valve>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("1651"),
+            part2: Some("1707"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_input_to_valves(input)?)
+    }
+
+    fn part1(&self, valves: &Self::Input) -> Option<String> {
+        let (graph, flows) = build_graph(valves);
         let mut cache = HashMap::new();
         let mut path = vec![];
 
@@ -193,9 +244,8 @@ impl Problem for Solution {
         Some(answer.to_string())
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
-        let valves = parse_input_to_valves(_input);
-        let (graph, flows) = build_graph(&valves);
+    fn part2(&self, valves: &Self::Input) -> Option<String> {
+        let (graph, flows) = build_graph(valves);
         let mut cache = HashMap::new();
 
         let mut state = State::new();
@@ -205,37 +255,109 @@ impl Problem for Solution {
 
         Some(answer.to_string())
     }
+
+    /// `size` valves, 60 like a real input by default and between 2 and 676.
+    /// They form a connected network of tunnels starting at `AA`, with
+    /// about one valve in four worth opening.
+    fn generate(&self, rng: &mut Rng, size: Option<usize>) -> Option<String> {
+        let n = size.unwrap_or(60).clamp(2, 26 * 26);
+        let mut names = (b'A'..=b'Z')
+            .flat_map(|a| (b'A'..=b'Z').map(move |b| format!("{}{}", a as char, b as char)))
+            .filter(|name| name != "AA")
+            .collect::<Vec<String>>();
+        rng.shuffle(&mut names);
+        names.insert(0, "AA".to_string());
+        names.truncate(n);
+
+        // A random tree keeps every valve reachable, extra tunnels add loops.
+        let mut tunnels = vec![BTreeSet::new(); n];
+        let dig = |a: usize, b: usize, tunnels: &mut Vec<BTreeSet<usize>>| {
+            if a != b && tunnels[a].len() < 5 && tunnels[b].len() < 5 {
+                tunnels[a].insert(b);
+                tunnels[b].insert(a);
+            }
+        };
+        for i in 1..n {
+            while tunnels[i].is_empty() {
+                dig(i, rng.below(i), &mut tunnels);
+            }
+        }
+        for _ in 0..n / 4 {
+            dig(rng.below(n), rng.below(n), &mut tunnels);
+        }
+
+        let mut valves = (0..n)
+            .map(|i| {
+                let rate = if i > 0 && rng.chance(0.25) {
+                    rng.range(3..=25)
+                } else {
+                    0
+                };
+                let mut edges = tunnels[i]
+                    .iter()
+                    .map(|&j| names[j].as_str())
+                    .collect::<Vec<&str>>();
+                rng.shuffle(&mut edges);
+                let lead = if edges.len() == 1 {
+                    "tunnel leads to valve"
+                } else {
+                    "tunnels lead to valves"
+                };
+                format!(
+                    "Valve {} has flow rate={}; {} {}",
+                    names[i],
+                    rate,
+                    lead,
+                    edges.join(", ")
+                )
+            })
+            .collect::<Vec<String>>();
+        rng.shuffle(&mut valves);
+        Some(valves.join("\n"))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
-Valve BB has flow rate=13; tunnels lead to valves CC, AA
-Valve CC has flow rate=2; tunnels lead to valves DD, BB
-Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
-Valve EE has flow rate=3; tunnels lead to valves FF, DD
-Valve FF has flow rate=0; tunnels lead to valves EE, GG
-Valve GG has flow rate=0; tunnels lead to valves FF, HH
-Valve HH has flow rate=22; tunnel leads to valve GG
-Valve II has flow rate=0; tunnels lead to valves AA, JJ
-Valve JJ has flow rate=21; tunnel leads to valve II
-";
+    aoc_core::example_tests!(Solution {}, part1, #[ignore] part2);
+
     #[test]
-    fn test_day16a() {
-        let solution = Solution {};
-        let answer = solution.part1(INPUT).unwrap();
-        
-        assert_eq!(answer, "1651");
+    fn test_parse_errors() {
+        let e = parse_input_to_valves("Valve AA has flow rate=x; tunnel leads to valve AA")
+            .unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 24, "x"));
+        let e = parse_input_to_valves("Valve AA has flow rate=0; tunnel leads to valve BB")
+            .unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 49, "BB"));
+        let e = parse_input_to_valves("Valve AA has flow rate=0").unwrap_err();
+        assert_eq!((e.line, e.column), (1, 1));
+        let e = parse_input_to_valves("Valve BB has flow rate=0; tunnel leads to valve BB")
+            .unwrap_err();
+        assert_eq!((e.line, e.column), (1, 51));
     }
 
     #[test]
-    #[ignore]
-    fn test_day16b() {
-        let solution = Solution {};
-        let answer = solution.part2(INPUT).unwrap();
-        
-        assert_eq!(answer, "1707");
+    fn test_generate() {
+        let input = Solution {}.generate(&mut Rng::new(1), Some(8)).unwrap();
+        let valves = Solution {}.parse(&input).unwrap();
+        assert_eq!(valves.len(), 8);
+        let (graph, flows) = build_graph(&valves);
+        assert_eq!(flows["AA"], 0);
+
+        // Every valve is reachable from AA, through tunnels going both ways.
+        let mut seen = HashSet::from(["AA"]);
+        let mut todo = vec!["AA"];
+        while let Some(valve) = todo.pop() {
+            for next in &graph[valve] {
+                assert!(graph[next.as_str()].iter().any(|v| v == valve));
+                if seen.insert(next.as_str()) {
+                    todo.push(next);
+                }
+            }
+        }
+        assert_eq!(seen.len(), 8);
+        assert!(Solution {}.part1(&valves).is_some());
     }
 }
@@ -1,11 +1,14 @@
 use std::collections::HashSet;
 
-use crate::problem::Problem;
+use aoc_core::{AOCError, Context, Example, Frame, ParseError, Problem, Rng};
 
 pub struct Solution {}
 
 const WIDTH: usize = 7;
 
+/// How much of the top of the tower each frame shows.
+const FRAME_ROWS: usize = 40;
+
 struct Game {
     board: Board,
     block_order: Vec<BlockType>,
@@ -26,9 +29,10 @@ impl Game {
     }
 
     fn run(&mut self, n_blocks: usize) {
+        let ctx = Context::current();
         for i in 0..n_blocks {
             if i % 100 == 0 {
-                println!("Dropping block {}", i);
+                ctx.progress(i as u64, n_blocks as u64);
             }
             let next_block = self.block_order[self.block_count % self.block_order.len()];
             self.drop_block(next_block);
@@ -37,7 +41,7 @@ impl Game {
 
     fn drop_block(&mut self, block_type: BlockType) {
         let mut block = Block::new(block_type);
-        let height = block.get_height() as i32;
+        let _height = block.get_height() as i32;
         block.points.iter_mut().for_each(|p| {
             p.x += 2;
             p.y += self.board.height as i32 + 3
@@ -65,6 +69,11 @@ impl Game {
                     .max((block.points.iter().map(|p| p.y).max().unwrap_or(0)) as usize + 1);
 
                 self.block_count += 1;
+                Context::current().frame(|| {
+                    self.board
+                        .draw(FRAME_ROWS)
+                        .annotate(format!("{} rocks, height {}", self.block_count, self.board.height))
+                });
                 break;
             }
         }
@@ -86,10 +95,6 @@ impl Board {
         }
     }
 
-    fn is_filled(&self, point: &Point) -> bool {
-        self.data.contains(point)
-    }
-
     fn collides(&self, block: &Block) -> bool {
         block
             .points
@@ -97,15 +102,31 @@ impl Board {
             .any(|p| self.data.contains(p) || p.y < 0 || p.x < 0 || p.x >= self.width as i32)
     }
 
+    /// The top `rows` of the chamber between its walls.
+    fn draw(&self, rows: usize) -> Frame {
+        let mut frame = Frame::new(self.width + 2, rows);
+        for row in 0..rows {
+            let y = self.height as i32 - 1 - row as i32;
+            frame.set(0, row, '|');
+            frame.set(self.width + 1, row, '|');
+            for x in 0..self.width {
+                if y < 0 || self.data.contains(&Point { x: x as i32, y }) {
+                    frame.set(x + 1, row, '#');
+                }
+            }
+        }
+        frame
+    }
+
     fn lock_block(&mut self, block: &Block) {
         block.points.iter().for_each(|p| {
-            self.data.insert(p.clone());
+            self.data.insert(*p);
         })
     }
 }
 
 #[derive(Debug, Copy, Clone)]
-enum Dir {
+pub enum Dir {
     Left,
     Right,
     Down,
@@ -184,10 +205,6 @@ impl Block {
             .collect()
     }
 
-    fn collides_with(&self, block: &Block) -> bool {
-        self.points.iter().any(|p| block.points.contains(p))
-    }
-
     fn get_height(&self) -> usize {
         match self.block_type {
             BlockType::Minus => 1,
@@ -199,13 +216,16 @@ impl Block {
     }
 }
 
-fn parse_directions(input: &str) -> Vec<Dir> {
+fn parse_directions(input: &str) -> Result<Vec<Dir>, ParseError> {
+    if input.is_empty() {
+        return Err(ParseError::at(input, input, "expected at least one jet"));
+    }
     input
-        .chars()
-        .map(|c| match c {
-            '<' => Dir::Left,
-            '>' => Dir::Right,
-            _ => panic!("Could not parse direction {}", c),
+        .char_indices()
+        .map(|(i, c)| match c {
+            '<' => Ok(Dir::Left),
+            '>' => Ok(Dir::Right),
+            _ => Err(ParseError::at(input, &input[i..i + c.len_utf8()], "expected '<' or '>'")),
         })
         .collect()
 }
@@ -220,45 +240,70 @@ fn get_block_order() -> Vec<BlockType> {
     ]
 }
 
+const EXAMPLE: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
+    type Input = Vec<Dir>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("3068"),
+            part2: None,
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_directions(input)?)
+    }
+
+    fn part1(&self, directions: &Self::Input) -> Option<String> {
         let ticks = 2022;
-        let directions = parse_directions(_input);
         let block_order = get_block_order();
-        let mut game = Game::new(WIDTH, block_order, directions);
+        let mut game = Game::new(WIDTH, block_order, directions.clone());
         game.run(ticks);
 
         let answer = game.board.height;
         Some(answer.to_string())
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
+    fn part2(&self, directions: &Self::Input) -> Option<String> {
         let ticks = 1_000_000_000_000;
-        let directions = parse_directions(_input);
         let block_order = get_block_order();
-        let mut game = Game::new(WIDTH, block_order, directions);
+        let mut game = Game::new(WIDTH, block_order, directions.clone());
         game.run(ticks);
 
         let answer = game.board.height;
         Some(answer.to_string())
     }
+
+    /// `size` jets, 10091 like a real input by default.
+    fn generate(&self, rng: &mut Rng, size: Option<usize>) -> Option<String> {
+        let jets = (0..size.unwrap_or(10_091))
+            .map(|_| *rng.choose(&['<', '>']))
+            .collect();
+        Some(jets)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+    aoc_core::example_tests!(Solution {}, part1);
 
     #[test]
-    fn test_day17a() {
-        let ticks = 2022;
-        let directions = parse_directions(INPUT);
-        let block_order = get_block_order();
-        let mut game = Game::new(WIDTH, block_order, directions);
-        game.run(ticks);
+    fn test_parse_errors() {
+        let e = parse_directions(">><x<").err().unwrap();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 4, "x"));
+        assert!(parse_directions("").is_err());
+    }
 
-        let answer = game.board.height;
-        assert_eq!(answer, 3068);
+    #[test]
+    fn test_generate() {
+        let input = Solution {}.generate(&mut Rng::new(1), Some(500)).unwrap();
+        assert_eq!(input.len(), 500);
+        let directions = Solution {}.parse(&input).unwrap();
+        assert!(Solution {}.part1(&directions).is_some());
     }
 }
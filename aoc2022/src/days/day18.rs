@@ -2,25 +2,42 @@ use std::collections::{HashSet, VecDeque};
 
 use itertools::Itertools;
 
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 
 pub struct Solution {}
 
 type Cube = (i32, i32, i32);
 
-fn parse_input(input: &str) -> Vec<Cube> {
+/// The largest coordinate a cube can have, which bounds the size of the grid.
+const MAX_COORD: i32 = 99;
+
+fn parse_coord(input: &str, text: &str) -> Result<i32, ParseError> {
+    let c = ParseError::number(input, text)?;
+    if !(0..=MAX_COORD).contains(&c) {
+        let message = format!("expected a coordinate from 0 to {}", MAX_COORD);
+        return Err(ParseError::at(input, text, message));
+    }
+    Ok(c)
+}
+
+fn parse_input(input: &str) -> Result<Vec<Cube>, ParseError> {
+    if input.is_empty() {
+        return Err(ParseError::at(input, input, "expected at least one cube"));
+    }
     input
         .lines()
         .map(|l| {
             l.split(',')
-                .map(|c| c.parse::<i32>().unwrap())
+                .map(|c| parse_coord(input, c))
+                .collect::<Result<Vec<i32>, ParseError>>()?
+                .into_iter()
                 .collect_tuple()
-                .unwrap()
+                .ok_or_else(|| ParseError::at(input, l, "expected a cube like '2,2,2'"))
         })
-        .collect::<Vec<Cube>>()
+        .collect()
 }
 
-fn get_dimensions(cubes: &Vec<Cube>) -> (usize, usize, usize) {
+fn get_dimensions(cubes: &[Cube]) -> (usize, usize, usize) {
     let dim_x = (cubes.iter().map(|c| c.0).max().unwrap() + 3) as usize;
     let dim_y = (cubes.iter().map(|c| c.1).max().unwrap() + 3) as usize;
     let dim_z = (cubes.iter().map(|c| c.2).max().unwrap() + 3) as usize;
@@ -28,7 +45,7 @@ fn get_dimensions(cubes: &Vec<Cube>) -> (usize, usize, usize) {
     (dim_x, dim_y, dim_z)
 }
 
-fn to_grid(cubes: &Vec<Cube>, dimensions: (usize, usize, usize)) -> Vec<Vec<Vec<i32>>> {
+fn to_grid(cubes: &[Cube], dimensions: (usize, usize, usize)) -> Vec<Vec<Vec<i32>>> {
     let (dim_x, dim_y, dim_z) = dimensions;
     let mut grid = vec![vec![vec![0; dim_z]; dim_y]; dim_x];
     cubes
@@ -37,7 +54,7 @@ fn to_grid(cubes: &Vec<Cube>, dimensions: (usize, usize, usize)) -> Vec<Vec<Vec<
     grid
 }
 
-fn count_surfaces(cubes: &Vec<(i32, i32, i32)>, grid: &Vec<Vec<Vec<i32>>>) -> usize {
+fn count_surfaces(cubes: &[Cube], grid: &[Vec<Vec<i32>>]) -> usize {
     cubes.iter().fold(0, |acc, (x, y, z)| {
         acc + NEIGHBORHOOD.iter().fold(0, |a, (ox, oy, oz)| {
             a + 1 - grid[(x + 1 + ox) as usize][(y + 1 + oy) as usize][(z + 1 + oz) as usize]
@@ -45,7 +62,7 @@ fn count_surfaces(cubes: &Vec<(i32, i32, i32)>, grid: &Vec<Vec<Vec<i32>>>) -> us
     }) as usize
 }
 
-fn flood_fill(grid: &Vec<Vec<Vec<i32>>>, dimensions: (usize, usize, usize)) -> usize {
+fn flood_fill(grid: &[Vec<Vec<i32>>], dimensions: (usize, usize, usize)) -> usize {
     let (dim_x, dim_y, dim_z) = dimensions;
     let mut q = VecDeque::new();
     let mut visited = HashSet::new();
@@ -59,8 +76,8 @@ fn flood_fill(grid: &Vec<Vec<Vec<i32>>>, dimensions: (usize, usize, usize)) -> u
         let (x, y, z) = p;
         NEIGHBORHOOD.iter().for_each(|(ox, oy, oz)| {
             let (_x, _y, _z) = (x + ox, y + oy, z + oz);
-            if !visited.contains(&(_x, _y, _z)) && !q.contains(&(_x, _y, _z)) {
-                if _x >= 0
+            if !visited.contains(&(_x, _y, _z)) && !q.contains(&(_x, _y, _z))
+                && _x >= 0
                     && _x < (dim_x as i32)
                     && _y >= 0
                     && _y < (dim_y as i32)
@@ -73,28 +90,53 @@ fn flood_fill(grid: &Vec<Vec<Vec<i32>>>, dimensions: (usize, usize, usize)) -> u
                         num_surfaces += 1;
                     }
                 }
-            }
         })
     }
     num_surfaces
 }
 
+const EXAMPLE: &str = "2,2,2
+1,2,2
+3,2,2
+2,1,2
+2,3,2
+2,2,1
+2,2,3
+2,2,4
+2,2,6
+1,2,5
+3,2,5
+2,1,5
+2,3,5
+";
+
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let cubes = parse_input(_input);
+    type Input = Vec<Cube>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("64"),
+            part2: Some("58"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_input(input)?)
+    }
 
-        let (dim_x, dim_y, dim_z) = get_dimensions(&cubes);
+    fn part1(&self, cubes: &Self::Input) -> Option<String> {
+        let (dim_x, dim_y, dim_z) = get_dimensions(cubes);
 
-        let grid = to_grid(&cubes, (dim_x, dim_y, dim_z));
-        let num_surfaces = count_surfaces(&cubes, &grid);
+        let grid = to_grid(cubes, (dim_x, dim_y, dim_z));
+        let num_surfaces = count_surfaces(cubes, &grid);
 
         Some(num_surfaces.to_string())
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
-        let cubes = parse_input(_input);
-        let (dim_x, dim_y, dim_z) = get_dimensions(&cubes);
-        let grid = to_grid(&cubes, (dim_x, dim_y, dim_z));
+    fn part2(&self, cubes: &Self::Input) -> Option<String> {
+        let (dim_x, dim_y, dim_z) = get_dimensions(cubes);
+        let grid = to_grid(cubes, (dim_x, dim_y, dim_z));
 
         let n_surfaces = flood_fill(&grid, (dim_x, dim_y, dim_z));
 
@@ -115,34 +157,15 @@ const NEIGHBORHOOD: [(i32, i32, i32); 6] = [
 mod tests {
     use super::*;
 
-    // const INPUT: &str = "1,1,1\n2,1,1";
-
-    const INPUT: &str = "2,2,2
-1,2,2
-3,2,2
-2,1,2
-2,3,2
-2,2,1
-2,2,3
-2,2,4
-2,2,6
-1,2,5
-3,2,5
-2,1,5
-2,3,5
-";
-
-    #[test]
-    fn test_day18a() {
-        let solution = Solution {};
-        let answer = solution.part1(INPUT).unwrap();
-        assert_eq!(answer, "64");
-    }
+    aoc_core::example_tests!(Solution {});
 
     #[test]
-    fn test_day18b() {
-        let solution = Solution {};
-        let answer = solution.part2(INPUT).unwrap();
-        assert_eq!(answer, "58");
+    fn test_parse_errors() {
+        let e = parse_input("2,2,2\n1,x,2").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 3, "x"));
+        let e = parse_input("2,2,2\n1,2").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "1,2"));
+        let e = parse_input("2,-1,2").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 3, "-1"));
     }
 }
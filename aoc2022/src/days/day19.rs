@@ -1,11 +1,9 @@
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    fmt::Display,
-    ops::{Add, Mul, Sub},
+    collections::{HashSet, VecDeque},
+    ops::{Add, Sub},
 };
 
-use crate::problem::Problem;
-use regex::Regex;
+use aoc_core::{AOCError, Context, Example, ParseError, Problem, Rng};
 
 pub struct Solution {}
 
@@ -47,7 +45,7 @@ impl Resources {
     }
 
     fn add_production(&self, material: Material) -> Resources {
-        let mut new_production = self.clone();
+        let mut new_production = *self;
         match material {
             Ore => new_production.ore += 1,
             Clay => new_production.clay += 1,
@@ -88,7 +86,7 @@ impl Sub for Resources {
 use Material::*;
 
 #[derive(Debug)]
-struct Blueprint {
+pub struct Blueprint {
     id: isize,
     ore_robot_cost: Resources,
     clay_robot_cost: Resources,
@@ -97,20 +95,29 @@ struct Blueprint {
 }
 
 impl Blueprint {
-    fn from_str(input: &str) -> Self {
-        let re = Regex::new(r"(?P<num>[-\d]*)").unwrap();
-        let c = re
-            .captures_iter(input)
-            .filter_map(|digits| digits["num"].parse().ok())
-            .collect::<Vec<isize>>();
-
-        Blueprint {
-            id: c[0],
-            ore_robot_cost: Resources::new(c[1], 0, 0, 0),
-            clay_robot_cost: Resources::new(c[2], 0, 0, 0),
-            obsidian_robot_costs: Resources::new(c[3], c[4], 0, 0),
-            geode_robot_costs: Resources::new(c[5], 0, c[6], 0),
-        }
+    fn from_str(input: &str, line: &str) -> Result<Self, ParseError> {
+        let words = line.split(' ').collect::<Vec<&str>>();
+        let [
+            "Blueprint", id,
+            "Each", "ore", "robot", "costs", ore, "ore.",
+            "Each", "clay", "robot", "costs", clay, "ore.",
+            "Each", "obsidian", "robot", "costs", obsidian_ore, "ore", "and", obsidian_clay, "clay.",
+            "Each", "geode", "robot", "costs", geode_ore, "ore", "and", geode_obsidian, "obsidian.",
+        ] = words[..] else {
+            return Err(ParseError::at(input, line, "expected a blueprint like the example's"));
+        };
+        let id = id
+            .strip_suffix(':')
+            .ok_or_else(|| ParseError::at(input, id, "expected an id like '1:'"))?;
+        let cost = |text| ParseError::number::<u8>(input, text).map(isize::from);
+
+        Ok(Blueprint {
+            id: ParseError::number(input, id)?,
+            ore_robot_cost: Resources::new(cost(ore)?, 0, 0, 0),
+            clay_robot_cost: Resources::new(cost(clay)?, 0, 0, 0),
+            obsidian_robot_costs: Resources::new(cost(obsidian_ore)?, cost(obsidian_clay)?, 0, 0),
+            geode_robot_costs: Resources::new(cost(geode_ore)?, 0, cost(geode_obsidian)?, 0),
+        })
     }
 
     fn get_cost(&self, material: Material) -> Resources {
@@ -162,13 +169,11 @@ impl State {
         }
     }
 
-    fn time_to(cost: isize, resource: isize, production: isize) -> isize {
-        (cost - resource + production - 1) / production.max(0) + 1
-    }
-
     fn time_until_buildable(&self, material: Material, blueprint: &Blueprint) -> isize {
         let cost = blueprint.get_cost(material);
-        let dt = match material {
+        
+
+        match material {
             Ore => {
                 if self.production.ore > 0 {
                     (cost.ore - self.resources.ore) / self.production.ore
@@ -229,9 +234,7 @@ impl State {
                     0
                 }
             }
-        };
-
-        dt
+        }
     }
 
     fn can_build(&self, material: Material, blueprint: &Blueprint) -> Buildable {
@@ -240,7 +243,7 @@ impl State {
             let new_production = self.production.add_production(material);
             Buildable::Now(new_resources, new_production)
         } else {
-            let dt = self.time_until_buildable(material, &blueprint);
+            let dt = self.time_until_buildable(material, blueprint);
             if dt == 0 {
                 Buildable::Never
             } else {
@@ -252,7 +255,7 @@ impl State {
     fn valid_futures(&self, blueprint: &Blueprint) -> Vec<State> {
         [Ore, Clay, Obsidian, Geode]
             .iter()
-            .flat_map(|&robot_type| match self.can_build(robot_type, &blueprint) {
+            .flat_map(|&robot_type| match self.can_build(robot_type, blueprint) {
                 Now(resources, production) => {
                     let new_resources = resources + self.production;
                     let state_built = State {
@@ -264,9 +267,9 @@ impl State {
                 }
                 Future(t) => {
                     let new_resources =
-                        self.resources + self.production.scalar_multiply(t as isize);
+                        self.resources + self.production.scalar_multiply(t);
                     vec![State {
-                        time: self.time - t as isize,
+                        time: self.time - t,
                         resources: new_resources,
                         production: self.production,
                     }]
@@ -284,11 +287,13 @@ fn find_highest_geode_produced(state: State, blueprint: &Blueprint) -> isize {
     stack.push_back(state);
 
     let mut best = 0;
+    let ctx = Context::current();
 
     while let Some(s) = stack.pop_back() {
         if !visited.contains(&s) {
+            ctx.count("states expanded");
             best = best.max(s.resources.geode);
-            let next_states = s.valid_futures(&blueprint);
+            let next_states = s.valid_futures(blueprint);
             visited.insert(s);
             for future_state in next_states {
                 if !stack.contains(&future_state) {
@@ -305,16 +310,39 @@ fn find_highest_geode_produced(state: State, blueprint: &Blueprint) -> isize {
     best
 }
 
+const EXAMPLE: &str = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
+Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.";
+
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
+    type Input = Vec<Blueprint>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("33"),
+            part2: None,
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        let blueprints = input
+            .lines()
+            .map(|l| Blueprint::from_str(input, l))
+            .collect::<Result<Self::Input, ParseError>>()?;
+        Ok(blueprints)
+    }
+
+    fn part1(&self, blueprints: &Self::Input) -> Option<String> {
         let time = 24;
         let state = State::new(time);
-        let answer: isize = _input
-            .lines()
-            .map(|l| Blueprint::from_str(l))
-            .map(|bp| {
-                println!("Blueprint {}", &bp.id);
-                let best = find_highest_geode_produced(state.clone(), &bp);
+        let ctx = Context::current();
+        let answer: isize = blueprints
+            .iter()
+            .enumerate()
+            .map(|(i, bp)| {
+                ctx.progress(i as u64, blueprints.len() as u64);
+                let best = find_highest_geode_produced(state, bp);
+                ctx.info(format_args!("blueprint {}: {} geodes", bp.id, best));
                 bp.id * best
             })
             .sum();
@@ -322,32 +350,67 @@ impl Problem for Solution {
         Some(answer.to_string())
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
+    fn part2(&self, _input: &Self::Input) -> Option<String> {
         // Some(format!("{}", "undefined"))
         None
     }
+
+    /// `size` blueprints, 30 like a real input by default, with costs in the
+    /// ranges real inputs use.
+    fn generate(&self, rng: &mut Rng, size: Option<usize>) -> Option<String> {
+        let blueprints = (1..=size.unwrap_or(30))
+            .map(|id| {
+                format!(
+                    "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. \
+                     Each obsidian robot costs {} ore and {} clay. \
+                     Each geode robot costs {} ore and {} obsidian.",
+                    id,
+                    rng.range(2..=4),
+                    rng.range(2..=4),
+                    rng.range(2..=4),
+                    rng.range(5..=20),
+                    rng.range(2..=4),
+                    rng.range(7..=20),
+                )
+            })
+            .collect::<Vec<String>>();
+        Some(blueprints.join("\n"))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
-Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.";
+    aoc_core::example_tests!(Solution {}, part1);
 
     #[test]
-    fn test_day19a() {
-        let time = 24;
-        let state = State::new(time);
-        let answer: isize = INPUT
-            .lines()
-            .map(|l| Blueprint::from_str(l))
-            .map(|bp| {
-                let best = find_highest_geode_produced(state.clone(), &bp);
-                bp.id * best
-            })
-            .sum();
+    fn test_parse_errors() {
+        let bad = EXAMPLE.replace("14 clay", "x clay");
+        let line = bad.lines().next().unwrap();
+        let e = Blueprint::from_str(&bad, line).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 107, "x"));
+        let line = bad.lines().nth(1).unwrap();
+        let e = Blueprint::from_str(&bad, &line[..40]).unwrap_err();
+        assert_eq!((e.line, e.column), (2, 1));
+    }
+
+    #[test]
+    fn test_generate() {
+        fn shape(line: &str) -> Vec<&str> {
+            line.split(|c: char| c.is_ascii_digit())
+                .filter(|s| !s.is_empty())
+                .collect()
+        }
 
-        assert_eq!(answer, 33);
+        let input = Solution {}.generate(&mut Rng::new(1), Some(3)).unwrap();
+        for line in input.lines() {
+            assert_eq!(shape(line), shape(EXAMPLE.lines().next().unwrap()));
+        }
+        let blueprints = Solution {}.parse(&input).unwrap();
+        assert_eq!(blueprints.iter().map(|b| b.id).collect::<Vec<_>>(), [1, 2, 3]);
+        assert!(blueprints
+            .iter()
+            .all(|b| (5..=20).contains(&b.obsidian_robot_costs.clay)));
     }
 }
@@ -1,11 +1,24 @@
-use std::collections::VecDeque;
 
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 
 pub struct Solution {}
 
 type Entry = (usize, i64);
 
+fn parse_input(input: &str) -> Result<Vec<i64>, ParseError> {
+    let numbers = input
+        .lines()
+        .map(|n| ParseError::number(input, n))
+        .collect::<Result<Vec<i64>, ParseError>>()?;
+    // The grove coordinates are counted from the 0, and mixing moves
+    // numbers around the others.
+    if numbers.len() < 2 || !numbers.contains(&0) {
+        let end = &input[input.len()..];
+        return Err(ParseError::at(input, end, "expected a 0 among at least two numbers"));
+    }
+    Ok(numbers)
+}
+
 fn mix(entries: &mut Vec<Entry>, rounds: usize) {
     let n_items = entries.len();
 
@@ -27,10 +40,10 @@ fn mix(entries: &mut Vec<Entry>, rounds: usize) {
     }
 }
 
-fn decrypt(input: &str, rounds: usize, key: Option<i64>)  -> i64 {
-    let mut entries = input
-        .lines()
-        .map(|n| n.parse::<i64>().unwrap())
+fn decrypt(numbers: &[i64], rounds: usize, key: Option<i64>)  -> i64 {
+    let mut entries = numbers
+        .iter()
+        .copied()
         .enumerate()
         .collect::<Vec<Entry>>();
 
@@ -47,16 +60,38 @@ fn decrypt(input: &str, rounds: usize, key: Option<i64>)  -> i64 {
         + entries[(zero + 3000).rem_euclid(n_items)].1
 }
 
+const EXAMPLE: &str = "1
+2
+-3
+3
+-2
+0
+4";
+
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let answer = decrypt(_input, 1, None);
+    type Input = Vec<i64>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("3"),
+            part2: Some("1623178306"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(&self, numbers: &Self::Input) -> Option<String> {
+        let answer = decrypt(numbers, 1, None);
         Some(answer.to_string())
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
+    fn part2(&self, numbers: &Self::Input) -> Option<String> {
         let decryption_key: i64 = 811589153;
         let rounds = 10;
-        let answer = decrypt(_input, rounds, Some(decryption_key));
+        let answer = decrypt(numbers, rounds, Some(decryption_key));
         Some(answer.to_string())
     }
 }
@@ -64,28 +99,35 @@ impl Problem for Solution {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
-    const INPUT: &str = "1
-2
--3
-3
--2
-0
-4";
+    aoc_core::example_tests!(Solution {});
 
     #[test]
-    fn test_day20a() {
-        let solution = Solution {};
-        let answer = solution.part1(INPUT).unwrap();
-
-        assert_eq!(answer, "3");
+    fn test_parse_errors() {
+        let e = parse_input("1\n2x\n0").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "2x"));
+        let e = parse_input("1\n2").unwrap_err();
+        assert_eq!((e.line, e.column), (2, 2));
+        assert!(parse_input("0").is_err());
     }
 
-    #[test]
-    fn test_day20b() {
-        let solution = Solution {};
-        let answer = solution.part2(INPUT).unwrap();
-
-        assert_eq!(answer, "1623178306");
+    proptest! {
+        #[test]
+        fn test_mix_permutes(
+            numbers in prop::collection::vec(-10_000i64..10_000, 2..50),
+            rounds in 1usize..4,
+            key in prop_oneof![Just(1i64), Just(811589153)],
+        ) {
+            let entries = numbers
+                .iter()
+                .map(|n| n * key)
+                .enumerate()
+                .collect::<Vec<Entry>>();
+            let mut mixed = entries.clone();
+            mix(&mut mixed, rounds);
+            mixed.sort();
+            prop_assert_eq!(mixed, entries);
+        }
     }
 }
@@ -1,13 +1,13 @@
 use std::collections::HashMap;
 
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, ParseError, Problem};
 
 pub struct Solution {}
 
 type Name = String;
 
 #[derive(Debug)]
-enum Op {
+pub enum Op {
     Add,
     Sub,
     Mul,
@@ -15,13 +15,13 @@ enum Op {
 }
 
 impl Op {
-    fn from_char(c: char) -> Self {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            '+' => Op::Add,
-            '-' => Op::Sub,
-            '*' => Op::Mul,
-            '/' => Op::Div,
-            _ => unreachable!(),
+            '+' => Some(Op::Add),
+            '-' => Some(Op::Sub),
+            '*' => Some(Op::Mul),
+            '/' => Some(Op::Div),
+            _ => None,
         }
     }
     fn eval(&self, left: i64, right: i64) -> i64 {
@@ -53,25 +53,34 @@ impl Op {
 }
 
 #[derive(Debug)]
-enum Job {
+pub enum Job {
     Constant(i64),
     Expr(Name, Op, Name),
 }
 
-fn parse_jobs(jobs: &str) -> HashMap<Name, Job> {
+fn parse_jobs(jobs: &str) -> Result<HashMap<Name, Job>, ParseError> {
     jobs.lines()
+        .filter(|l| !l.trim().is_empty())
         .map(|l| {
-            let (name, e) = l.split_once(": ").unwrap();
-            let expr = match e.split(' ').collect::<Vec<&str>>()[..] {
-                [c] => Job::Constant(c.parse::<i64>().unwrap()),
-                [a, o, b] => Job::Expr(
-                    a.to_string(),
-                    Op::from_char(o.chars().next().unwrap()),
-                    b.to_string(),
+            let (name, e) = l
+                .split_once(": ")
+                .ok_or_else(|| ParseError::at(jobs, l, "expected '<name>: <job>'"))?;
+            let expr = match e.split_whitespace().collect::<Vec<&str>>()[..] {
+                [c] => Job::Constant(
+                    c.parse::<i64>()
+                        .map_err(|_| ParseError::at(jobs, c, "expected a number"))?,
                 ),
-                _ => unreachable!(),
+                [a, o, b] => {
+                    let op = match o.chars().collect::<Vec<char>>()[..] {
+                        [c] => Op::from_char(c),
+                        _ => None,
+                    }
+                    .ok_or_else(|| ParseError::at(jobs, o, "expected one of + - * /"))?;
+                    Job::Expr(a.to_string(), op, b.to_string())
+                }
+                _ => return Err(ParseError::at(jobs, e, "expected a number or an expression")),
             };
-            (name.to_string(), expr)
+            Ok((name.to_string(), expr))
         })
         .collect()
 }
@@ -147,30 +156,7 @@ fn populate_above_humn(jobs: &HashMap<Name, Job>, memory: &mut HashMap<Name, i64
     result
 }
 
-impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let jobs = parse_jobs(_input);
-        let mut memory = HashMap::new();
-        let answer = trace(&"root".to_string(), &jobs, &mut memory);
-
-        Some(answer.to_string())
-    }
-
-    fn part2(&self, _input: &str) -> Option<String> {
-        let jobs = parse_jobs(_input);
-        let mut memory = HashMap::new();
-        populate_memory(&"root".to_string(), &jobs, &mut memory);
-        let answer = populate_above_humn(&jobs, &mut memory);
-
-        Some(answer.to_string())
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "root: pppw + sjmn
+const EXAMPLE: &str = "root: pppw + sjmn
 dbpl: 5
 cczh: sllz + lgvd
 zczc: 2
@@ -187,19 +173,51 @@ drzm: hmdt - zczc
 hmdt: 32
 ";
 
-    #[test]
-    fn test_day21a() {
-        let jobs = parse_jobs(INPUT);
+impl Problem for Solution {
+    type Input = HashMap<Name, Job>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("152"),
+            part2: Some("301"),
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(parse_jobs(input)?)
+    }
+
+    fn part1(&self, jobs: &Self::Input) -> Option<String> {
         let mut memory = HashMap::new();
-        let answer = trace(&"root".to_string(), &jobs, &mut memory);
-        assert_eq!(answer, 152);
+        let answer = trace(&"root".to_string(), jobs, &mut memory);
+
+        Some(answer.to_string())
     }
 
+    fn part2(&self, jobs: &Self::Input) -> Option<String> {
+        let mut memory = HashMap::new();
+        populate_memory(&"root".to_string(), jobs, &mut memory);
+        let answer = populate_above_humn(jobs, &mut memory);
+
+        Some(answer.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_core::example_tests!(Solution {});
+
     #[test]
-    fn test_day21b() {
-        let solution = Solution {};
-        let answer = solution.part2(INPUT).unwrap();
+    fn test_parse_errors() {
+        let e = parse_jobs("root: pppw % sjmn\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 12, "%"));
+
+        let e = parse_jobs("dbpl: 5\nzczc: two\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 7, "two"));
 
-        assert_eq!(answer, "301");
+        assert!(parse_jobs("dbpl: 5\r\nzczc: 2\r\n\n").is_ok());
     }
 }
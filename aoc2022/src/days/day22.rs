@@ -1,20 +1,18 @@
 use std::collections::HashMap;
 
-use regex::Regex;
-
-use crate::problem::Problem;
+use aoc_core::{AOCError, Context, Example, ParseError, Problem};
 
 pub struct Solution {}
 
 #[derive(Debug, PartialEq)]
-enum Tile {
+pub enum Tile {
     Portal,
     Wall,
     Floor,
 }
 
 #[derive(Debug)]
-enum Action {
+pub enum Action {
     Walk(usize),
     RotateCW(),
     RotateCCW()
@@ -23,81 +21,92 @@ enum Action {
 type Point = (usize, usize);
 type Room = HashMap<Point, Tile>;
 
-fn parse_room(raw: &str) -> Room {
-    raw.lines()
-        .enumerate()
-        .flat_map(|(row, l)| {
-            l.chars().enumerate()
-                .map(|(col, c)| {
-                    let tile = match c {
-                        ' ' => Tile::Portal,
-                        '#' => Tile::Wall,
-                        '.' => Tile::Floor,
-                        _ => panic!("unrecogized tile found"),
-                    };
-                    ((col, row), tile)
-                })
-                .collect::<Vec<(Point, Tile)>>()
-        })
-        .collect::<Room>()
-}
-
-fn parse_actions(raw: &str) -> Vec<Action> {
-    let re = Regex::new(r"(?P<val>:\d+|[LR])").unwrap();
-    // re.captures_iter().for_each(|c| dbg!(c));
-    re.captures_iter(raw).map(|c| match c["val"].parse::<usize>() {
-        Ok(x) => Action::Walk(x),
-        Err(_) => match &c["val"] {
-            "L" => Action::RotateCCW(),
-            "R" => Action::RotateCW(),
-            _ => panic!("unknown action")
+/// Steps for facing right, down, left and up, the order of the scores.
+const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+fn parse_room(raw: &str) -> Result<Room, ParseError> {
+    let mut room = Room::new();
+    for (row, l) in raw.lines().enumerate() {
+        for (col, (i, c)) in l.char_indices().enumerate() {
+            let tile = match c {
+                ' ' => Tile::Portal,
+                '#' => Tile::Wall,
+                '.' => Tile::Floor,
+                _ => {
+                    let text = &l[i..i + c.len_utf8()];
+                    return Err(ParseError::at(raw, text, "unrecognized tile, expected ' ', '#' or '.'"));
+                }
+            };
+            room.insert((col, row), tile);
         }
-    }).collect::<Vec<Action>>()
+    }
+    Ok(room)
 }
 
-
-fn find_start(cells: &Room) -> Point {
-    // Scan along first row
-    let mut col = 0;
-    loop {
-        if let Some(x) = cells.get(&(col, 0)) {
-            if *x == Tile::Floor {
-                return (col, 0)
+fn parse_actions(input: &str, raw: &str) -> Result<Vec<Action>, ParseError> {
+    let mut actions = Vec::new();
+    let mut rest = raw;
+    while let Some(c) = rest.chars().next() {
+        let len = match c {
+            'L' => {
+                actions.push(Action::RotateCCW());
+                1
             }
-        }
-        col += 1;
+            'R' => {
+                actions.push(Action::RotateCW());
+                1
+            }
+            '0'..='9' => {
+                let len = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+                actions.push(Action::Walk(ParseError::number(input, &rest[..len])?));
+                len
+            }
+            _ => {
+                let text = &rest[..c.len_utf8()];
+                return Err(ParseError::at(input, text, "expected a number of steps, 'L' or 'R'"));
+            }
+        };
+        rest = &rest[len..];
     }
+    Ok(actions)
 }
 
-fn score(row: usize, col: usize, facing: usize) -> usize {
-    1000 * row + 4 * col + facing
-}
-
-impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
-        let (cells, actions) = _input.split_once("\n\n").unwrap();
-        let cells = parse_room(cells);
-        let start = find_start(&cells);
-        let actions = parse_actions(actions);
-        dbg!(&actions);
-
-
-
-        todo!()
 
+fn find_start(cells: &Room) -> Option<Point> {
+    // Scan along first row
+    (0..)
+        .take_while(|&col| cells.contains_key(&(col, 0)))
+        .find(|&col| cells[&(col, 0)] == Tile::Floor)
+        .map(|col| (col, 0))
+}
 
+/// The tile one step from `(col, row)`, wrapping around to the far side of
+/// the map when the step leaves it.
+fn next_tile(cells: &Room, (col, row): Point, facing: usize) -> Point {
+    let (dx, dy) = DIRECTIONS[facing];
+    let on_map = |c: isize, r: isize| {
+        c >= 0
+            && r >= 0
+            && matches!(cells.get(&(c as usize, r as usize)), Some(Tile::Wall | Tile::Floor))
+    };
+    let (c, r) = (col as isize + dx, row as isize + dy);
+    if on_map(c, r) {
+        return (c as usize, r as usize);
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
-        // Some(format!("{}", "undefined"))
-        None
+    let (mut c, mut r) = (col as isize, row as isize);
+    while on_map(c - dx, r - dy) {
+        c -= dx;
+        r -= dy;
     }
+    (c as usize, r as usize)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    const INPUT: &str = "        ...#
+fn score(row: usize, col: usize, facing: usize) -> usize {
+    1000 * row + 4 * col + facing
+}
+
+const EXAMPLE: &str = "        ...#
         .#..
         #...
         ....
@@ -113,10 +122,84 @@ mod tests {
 10R5L5R10L4R5L5
 ";
 
+impl Problem for Solution {
+    type Input = (Room, Vec<Action>);
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("6032"),
+            part2: None,
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        let (cells, actions) = input.split_once("\n\n").ok_or_else(|| {
+            // The map ends at the first line that is not tiles, which is
+            // where the blank line belongs.
+            let end = input
+                .lines()
+                .find(|l| !l.chars().all(|c| matches!(c, ' ' | '#' | '.')))
+                .map_or(&input[input.len()..], |l| &l[..0]);
+            ParseError::at(input, end, "expected a blank line between the map and the path")
+        })?;
+        Ok((parse_room(cells)?, parse_actions(input, actions)?))
+    }
+
+    fn part1(&self, (cells, actions): &Self::Input) -> Option<String> {
+        Context::current().debug(format_args!("{} actions", actions.len()));
+        let mut pos = find_start(cells)?;
+        let mut facing = 0;
+        for action in actions {
+            match action {
+                Action::Walk(steps) => {
+                    for _ in 0..*steps {
+                        let next = next_tile(cells, pos, facing);
+                        if cells[&next] == Tile::Wall {
+                            break;
+                        }
+                        pos = next;
+                    }
+                }
+                Action::RotateCW() => facing = (facing + 1) % 4,
+                Action::RotateCCW() => facing = (facing + 3) % 4,
+            }
+        }
+
+        Some(score(pos.1 + 1, pos.0 + 1, facing).to_string())
+    }
+
+    fn part2(&self, _input: &Self::Input) -> Option<String> {
+        // Some(format!("{}", "undefined"))
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_core::example_tests!(Solution {}, part1);
+
+    #[test]
+    fn test_parse_room_error() {
+        let e = parse_room("  ..#\n  .x.#\n").unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 4, "x"));
+    }
+
+    #[test]
+    fn test_parse_actions() {
+        let input = "10R5L12";
+        let actions = parse_actions(input, input).unwrap();
+        assert_eq!(format!("{:?}", actions), "[Walk(10), RotateCW, Walk(5), RotateCCW, Walk(12)]");
+        let input = "10R5X2";
+        let e = parse_actions(input, input).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (1, 5, "X"));
+    }
+
     #[test]
-    fn test_22a() {
-        let solution = Solution {};
-        let answer = solution.part1(INPUT).unwrap();
-        assert_eq!(answer, "6032");
+    fn test_parse_missing_blank_line() {
+        let e = aoc_core::parse_error(&Solution {}, "  ..#\n  ...#\n10R5\n");
+        assert_eq!((e.line, e.column), (3, 1));
     }
 }
@@ -1,14 +1,20 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Problem};
 
 pub struct Solution {}
 
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
+    type Input = String;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(input.to_string())
+    }
+
+    fn part1(&self, _input: &Self::Input) -> Option<String> {
         // Some(format!("{}", "undefined"))
         None
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
+    fn part2(&self, _input: &Self::Input) -> Option<String> {
         // Some(format!("{}", "undefined"))
         None
     }
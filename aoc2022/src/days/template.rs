@@ -1,16 +1,39 @@
-use crate::problem::Problem;
+use aoc_core::{AOCError, Example, Problem};
 
 pub struct Solution {}
 
+const EXAMPLE: &str = "";
+
 impl Problem for Solution {
-    fn part1(&self, _input: &str) -> Option<String> {
+    type Input = String;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: None,
+            part2: None,
+        },
+    ];
+
+    fn parse(&self, input: &str) -> Result<Self::Input, AOCError> {
+        Ok(input.to_string())
+    }
+
+    fn part1(&self, _input: &Self::Input) -> Option<String> {
         // Some(format!("{}", "undefined"))
         None
     }
 
-    fn part2(&self, _input: &str) -> Option<String> {
+    fn part2(&self, _input: &Self::Input) -> Option<String> {
         // Some(format!("{}", "undefined"))
         None
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Fill in EXAMPLE and its answers, then drop the #[ignore]s.
+    aoc_core::example_tests!(Solution {}, #[ignore] part1, #[ignore] part2);
+}
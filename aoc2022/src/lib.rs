@@ -0,0 +1,18 @@
+use aoc_core::Year;
+
+mod days;
+
+/// This year's solutions, for the `aoc2022` binary and the multi-year `aoc`.
+pub const YEAR: Year = Year {
+    year: 2022,
+    days: days::DAYS,
+    get_solver: days::get_solver,
+    root: env!("CARGO_MANIFEST_DIR"),
+    embedded_input: days::embedded_input,
+};
+
+/// The parsers exercised by the fuzz targets in `../fuzz`.
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing {
+    pub use crate::days::day13::{parse_packet, Packet};
+}
@@ -1,5 +0,0 @@
-pub trait Problem {
-    fn part1(&self, _input: &str) -> Option<String>;
-    fn part2(&self, _input: &str) -> Option<String>;
-}
-
@@ -0,0 +1,19 @@
+#![no_main]
+
+use aoc2021::fuzzing::{encode_packet, parse_packet};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(message) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(packet) = parse_packet(message) else {
+        return;
+    };
+
+    // Padding and literal groups may differ from the input, but the encoding
+    // of a packet must parse back to the same encoding.
+    let encoded = encode_packet(&packet);
+    let reparsed = parse_packet(&encoded).expect("encoded packet does not parse");
+    assert_eq!(encode_packet(&reparsed), encoded);
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use aoc2021::fuzzing::{format_expr, parse_expr};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(line) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(expr) = parse_expr(line) {
+        assert_eq!(format_expr(&expr), line);
+    }
+});
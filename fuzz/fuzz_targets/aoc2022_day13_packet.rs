@@ -0,0 +1,14 @@
+#![no_main]
+
+use aoc2022::fuzzing::parse_packet;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(line) = std::str::from_utf8(data) else {
+        return;
+    };
+    // Leading zeros are lost in printing, so compare the packets.
+    if let Ok(packet) = parse_packet(line) {
+        assert_eq!(parse_packet(&packet.to_string()), Ok(packet));
+    }
+});